@@ -0,0 +1,80 @@
+//! Allocation count of the layout renderer
+//!
+//! Renders the sample system at width 120 with a counting global allocator
+//! and reports allocations and time per render. Run with `cargo bench`.
+
+use neofetch_rs::config::Config;
+use neofetch_rs::output::render_layout;
+use neofetch_rs::system_info::SystemInfo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Passes everything to the system allocator, counting allocations
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: every call is forwarded unchanged to the system allocator
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Renders measured after one warm-up render
+const ITERATIONS: usize = 1000;
+
+/// Allocations per render the renderer must stay under
+///
+/// The renderer before the hot-path rework needed 1008 allocations per
+/// render of this profile; the budget holds it to at least 2x fewer.
+const ALLOCATION_BUDGET: usize = 1008 / 2;
+
+fn main() {
+    colored::control::set_override(true);
+    let system_info = SystemInfo::sample().expect("sample system info");
+    let mut config = Config::default();
+    config.display.ascii_distro = Some("arch".to_string());
+
+    render_layout(&system_info, &config, 120).expect("render");
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(render_layout(&system_info, &config, 120).expect("render"));
+    }
+    let elapsed = start.elapsed();
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / ITERATIONS;
+    let bytes = (ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes) / ITERATIONS;
+
+    println!(
+        "render_layout (width 120): {} allocations, {} bytes, {:?} per render",
+        allocations,
+        bytes,
+        elapsed / ITERATIONS as u32
+    );
+    assert!(
+        allocations < ALLOCATION_BUDGET,
+        "{} allocations per render, budget is {}",
+        allocations,
+        ALLOCATION_BUDGET
+    );
+}
@@ -0,0 +1,193 @@
+//! Privacy scrubbing for shared output
+//!
+//! `--anonymize` runs these rules over the gathered information before it is
+//! rendered, so every output format hides the same things.
+
+use crate::system_info::{SystemInfo, FIELDS};
+
+/// Stand-in for the real username
+pub const ANONYMOUS_USER: &str = "user";
+
+/// Stand-in for the real hostname
+pub const ANONYMOUS_HOST: &str = "host";
+
+/// Scrub identifying information from gathered system info
+///
+/// The username and hostname are replaced wherever they stand alone, IP
+/// addresses are masked, the Users row is dropped and serial-number-like
+/// tokens are removed from the Host string.
+pub fn anonymize(system_info: &mut SystemInfo, username: &str, hostname: &str) {
+    let short_hostname = hostname.split('.').next().unwrap_or(hostname);
+
+    for field in FIELDS {
+        if let Some(value) = system_info.get_field_mut(field) {
+            *value = scrub_names(value, username, &[hostname, short_hostname]);
+        }
+    }
+
+    system_info.title = format!("{}@{}", ANONYMOUS_USER, ANONYMOUS_HOST);
+    system_info.local_ip = mask_ips(&system_info.local_ip);
+    system_info.public_ip = mask_ips(&system_info.public_ip);
+    system_info.users = "Unknown".to_string();
+    system_info.host = scrub_serials(&system_info.host);
+
+    for (_, value) in &mut system_info.custom {
+        *value = mask_ips(&scrub_names(value, username, &[hostname, short_hostname]));
+    }
+}
+
+/// Replace the username and hostnames, longest hostname first
+///
+/// Only whole tokens are replaced, ignoring ASCII case, so a host called
+/// `arch` doesn't turn the kernel `6.8.1-arch1-1` into `6.8.1-host1-1`.
+fn scrub_names(value: &str, username: &str, hostnames: &[&str]) -> String {
+    let mut scrubbed = value.to_string();
+    for hostname in hostnames.iter().filter(|name| name.len() > 1) {
+        scrubbed = replace_token(&scrubbed, hostname, ANONYMOUS_HOST);
+    }
+    if username.len() > 1 {
+        scrubbed = replace_token(&scrubbed, username, ANONYMOUS_USER);
+    }
+    scrubbed
+}
+
+/// Replace `name` where it isn't part of a longer word or number
+fn replace_token(value: &str, name: &str, replacement: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so matches index `value` too
+    let haystack = value.to_ascii_lowercase();
+    let needle = name.to_ascii_lowercase();
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric());
+
+    let mut replaced = String::with_capacity(value.len());
+    let mut copied = 0;
+    for (start, _) in haystack.match_indices(&needle) {
+        let end = start + needle.len();
+        if is_boundary(value[..start].chars().next_back())
+            && is_boundary(value[end..].chars().next())
+        {
+            replaced.push_str(&value[copied..start]);
+            replaced.push_str(replacement);
+            copied = end;
+        }
+    }
+    replaced.push_str(&value[copied..]);
+    replaced
+}
+
+/// Mask every IP address in a value, e.g. `192.168.1.20` -> `192.168.x.x`
+///
+/// IPv6 addresses keep only their first group.
+pub fn mask_ips(value: &str) -> String {
+    value
+        .split(' ')
+        .map(|word| {
+            let trimmed = word.trim_end_matches(',');
+            let suffix = &word[trimmed.len()..];
+            if let Ok(ip) = trimmed.parse::<std::net::Ipv4Addr>() {
+                let [a, b, ..] = ip.octets();
+                format!("{}.{}.x.x{}", a, b, suffix)
+            } else if let Some(ip) = trimmed
+                .split('/')
+                .next()
+                .and_then(|ip| ip.parse::<std::net::Ipv6Addr>().ok())
+            {
+                format!("{:x}:x{}", ip.segments()[0], suffix)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Drop tokens that look like serial numbers: eight or more letters and
+/// digits, mixed, such as `PF2XK9ZL`
+///
+/// Model names such as `B450M-DS3H` or `MS-7C02` have dashes and are kept.
+pub fn scrub_serials(value: &str) -> String {
+    value
+        .split_whitespace()
+        .filter(|token| {
+            let token = token.trim_matches(|c: char| "()[],;".contains(c));
+            let has_digit = token.chars().any(|c| c.is_ascii_digit());
+            let has_letter = token.chars().any(|c| c.is_ascii_alphabetic());
+            let alphanumeric = token.chars().all(|c| c.is_ascii_alphanumeric());
+            !(token.len() >= 8 && has_digit && has_letter && alphanumeric)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_replaced_as_whole_tokens() {
+        for (value, expected) in [
+            ("6.8.1-arch1-1", "6.8.1-arch1-1"),
+            ("/home/zibo/.cargo/bin/zsh", "/home/user/.cargo/bin/zsh"),
+            ("zibo@arch", "user@host"),
+            ("ARCH.local", "host.local"),
+            ("archer, zibowang", "archer, zibowang"),
+            ("arch arch", "host host"),
+            ("Archive", "Archive"),
+            ("arch-laptop", "host-laptop"),
+            ("", ""),
+        ] {
+            assert_eq!(
+                scrub_names(value, "zibo", &["arch"]),
+                expected,
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn fqdn_is_replaced_before_the_short_name() {
+        let hostnames = ["arch.lan.example.org", "arch"];
+        assert_eq!(
+            scrub_names("ssh arch.lan.example.org or arch", "zibo", &hostnames),
+            "ssh host or host"
+        );
+    }
+
+    #[test]
+    fn one_letter_names_are_left_alone() {
+        assert_eq!(scrub_names("a b c", "a", &["b"]), "a b c");
+    }
+
+    #[test]
+    fn serials_are_dropped_and_models_kept() {
+        for (host, expected) in [
+            ("Gigabyte B450M-DS3H", "Gigabyte B450M-DS3H"),
+            ("Micro-Star MS-7C02 1.0", "Micro-Star MS-7C02 1.0"),
+            ("ThinkPad X1 Carbon Gen 9", "ThinkPad X1 Carbon Gen 9"),
+            ("HP EliteBook 840 G8 5CG1234XYZ", "HP EliteBook 840 G8"),
+            ("Dell XPS 15 (PF2XK9ZL)", "Dell XPS 15"),
+            ("System Product Name", "System Product Name"),
+        ] {
+            assert_eq!(scrub_serials(host), expected, "{:?}", host);
+        }
+    }
+
+    #[test]
+    fn every_field_is_scrubbed() {
+        let mut system_info = SystemInfo::sample().unwrap();
+        system_info.title = "zibo@arch".to_string();
+        system_info.shell = "/home/zibo/.local/bin/fish".to_string();
+        system_info.local_ip = "192.168.1.20, fe80::1".to_string();
+        system_info.users = "zibo, guest".to_string();
+        system_info.custom = vec![("vpn".to_string(), "arch via 10.0.0.7".to_string())];
+
+        anonymize(&mut system_info, "zibo", "arch.lan");
+
+        assert_eq!(system_info.title, "user@host");
+        assert_eq!(system_info.kernel, "6.8.1-arch1-1");
+        assert_eq!(system_info.shell, "/home/user/.local/bin/fish");
+        assert_eq!(system_info.local_ip, "192.168.x.x, fe80:x");
+        assert_eq!(system_info.users, "Unknown");
+        assert_eq!(system_info.custom[0].1, "host via 10.0.x.x");
+    }
+}
@@ -2,8 +2,11 @@
 //!
 //! This module handles the ASCII art logos for different operating systems and distributions.
 
+use anyhow::{Context, Result};
 use colored::*;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
 
 /// ASCII art manager
 pub struct AsciiArt {
@@ -11,6 +14,12 @@ pub struct AsciiArt {
     colors: HashMap<String, Vec<Color>>,
 }
 
+impl Default for AsciiArt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AsciiArt {
     /// Create a new ASCII art manager
     pub fn new() -> Self {
@@ -23,6 +32,26 @@ impl AsciiArt {
         ascii_art
     }
 
+    /// The built-in logos, loaded once per process
+    ///
+    /// Building the logo table is most of the cost of a render, so repeated
+    /// renders share this one.
+    pub fn shared() -> &'static AsciiArt {
+        static SHARED: OnceLock<AsciiArt> = OnceLock::new();
+        SHARED.get_or_init(AsciiArt::new)
+    }
+
+    /// Load a custom ASCII logo from a text file, one logo line per file line
+    pub fn load_logo_file(path: &Path) -> Result<Vec<String>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read ascii file {}", path.display()))?;
+
+        Ok(content
+            .lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect())
+    }
+
     /// Load default ASCII logos for various operating systems
     fn load_default_logos(&mut self) {
         // macOS logo
@@ -171,6 +200,115 @@ impl AsciiArt {
         self.colors
             .insert("fedora".to_string(), vec![Color::Blue, Color::White]);
 
+        // NixOS logo
+        self.logos.insert(
+            "nixos".to_string(),
+            vec![
+                "          ::::.    ':::::     ::::'".to_string(),
+                "          ':::::    ':::::.  ::::'".to_string(),
+                "            :::::     '::::.:::::".to_string(),
+                "      .......:::::..... ::::::::".to_string(),
+                "     ::::::::::::::::::. ::::::    ::::.".to_string(),
+                "    ::::::::::::::::::::: :::::.  .::::'".to_string(),
+                "           .....           ::::' :::::'".to_string(),
+                "          :::::            '::' :::::'".to_string(),
+                " ........:::::               ' :::::::::::.".to_string(),
+                ":::::::::::::                 :::::::::::::".to_string(),
+                " ::::::::::: ..              :::::".to_string(),
+                "     .::::: .:::            :::::".to_string(),
+                "    .:::::  :::::          '''''    .....".to_string(),
+                "    :::::   ':::::.  ......:::::::::::::'".to_string(),
+                "     :::     ::::::. ':::::::::::::::::'".to_string(),
+                "            .:::::::: '::::::::::".to_string(),
+                "           .::::''::::.     '::::.".to_string(),
+                "          .::::'   ::::.     '::::.".to_string(),
+                "         .::::      ::::      '::::.".to_string(),
+            ],
+        );
+
+        self.colors
+            .insert("nixos".to_string(), vec![Color::Blue, Color::Cyan]);
+
+        // Linux Mint logo
+        self.logos.insert(
+            "linuxmint".to_string(),
+            vec![
+                " MMMMMMMMMMMMMMMMMMMMMMMMMmds+.".to_string(),
+                " MMm----::-://////////////oymNMd+`".to_string(),
+                " MMd      /++                -sNMd:".to_string(),
+                " MMNso/`  dMM    `.::-. .-::.` .hMN:".to_string(),
+                " ddddMMh  dMM   :hNMNMNhNMNMNh: `NMm".to_string(),
+                "     NMm  dMM  .NMN/-+MMM+-/NMN` dMM".to_string(),
+                "     NMm  dMM  -MMm  `MMM   dMM. dMM".to_string(),
+                "     NMm  dMM  -MMm  `MMM   dMM. dMM".to_string(),
+                "     NMm  dMM  .mmd  `mmm   yMM. dMM".to_string(),
+                "     NMm  dMM`  ..`   ...   ydm. dMM".to_string(),
+                "     hMM- +MMd/-------...-:sdds  dMM".to_string(),
+                "     -NMm- :hNMNNNmdddddddddy/`  dMM".to_string(),
+                "      -dMNs-``-::::-------.``    dMM".to_string(),
+                "       `/dMNmy+/:-------------:/yMMM".to_string(),
+                "          ./ydNMMMMMMMMMMMMMMMMMMMMM".to_string(),
+                "             .MMMMMMMMMMMMMMMMMMM".to_string(),
+            ],
+        );
+
+        self.colors
+            .insert("linuxmint".to_string(), vec![Color::Green, Color::White]);
+
+        // Manjaro logo
+        self.logos.insert(
+            "manjaro".to_string(),
+            vec![
+                "██████████████████  ████████".to_string(),
+                "██████████████████  ████████".to_string(),
+                "██████████████████  ████████".to_string(),
+                "██████████████████  ████████".to_string(),
+                "████████            ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+                "████████  ████████  ████████".to_string(),
+            ],
+        );
+
+        self.colors
+            .insert("manjaro".to_string(), vec![Color::Green]);
+
+        // Pop!_OS logo
+        self.logos.insert(
+            "pop".to_string(),
+            vec![
+                "             /////////////".to_string(),
+                "         /////////////////////".to_string(),
+                "      ///////*767////////////////".to_string(),
+                "    //////7676767676*//////////////".to_string(),
+                "   /////76767//7676767//////////////".to_string(),
+                "  /////767676///*76767///////////////".to_string(),
+                " ///////767676///76767.///7676*///////".to_string(),
+                "/////////767676//76767///767676////////".to_string(),
+                "//////////76767676767////76767/////////".to_string(),
+                "///////////76767676//////7676//////////".to_string(),
+                "////////////,7676,///////767///////////".to_string(),
+                "/////////////*7676///////76////////////".to_string(),
+                "///////////////7676////////////////////".to_string(),
+                " ///////////////7676///767////////////".to_string(),
+                "  //////////////////////'////////////".to_string(),
+                "   //////.7676767676767676767,//////".to_string(),
+                "    /////767676767676767676767/////".to_string(),
+                "      ///////////////////////////".to_string(),
+                "         /////////////////////".to_string(),
+                "             /////////////".to_string(),
+            ],
+        );
+
+        self.colors
+            .insert("pop".to_string(), vec![Color::Cyan, Color::White]);
+
         // Generic Linux logo
         self.logos.insert(
             "linux".to_string(),
@@ -246,6 +384,15 @@ impl AsciiArt {
             return self.logos.get("debian");
         } else if normalized_name.contains("fedora") {
             return self.logos.get("fedora");
+        } else if normalized_name.contains("mint") {
+            return self.logos.get("linuxmint");
+        } else if normalized_name.contains("manjaro") {
+            return self.logos.get("manjaro");
+        } else if normalized_name.contains("pop") {
+            // os-release names it "Pop!_OS" with ID=pop
+            return self.logos.get("pop");
+        } else if normalized_name.contains("nixos") {
+            return self.logos.get("nixos");
         } else if normalized_name.contains("mac") || normalized_name.contains("darwin") {
             return self.logos.get("macos");
         } else if normalized_name.contains("windows") {
@@ -258,6 +405,30 @@ impl AsciiArt {
         self.logos.get("linux")
     }
 
+    /// Names of the built-in logos, sorted
+    pub fn logo_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.logos.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Pick the name to look a logo up by, preferring os-release IDs
+    ///
+    /// The `ID` is tried first, then each `ID_LIKE` parent, and only then the
+    /// pretty OS name, which goes through the partial matches below.
+    pub fn resolve_logo_name<'a>(
+        &self,
+        distro_id: &'a str,
+        id_like: &'a str,
+        os_name: &'a str,
+    ) -> &'a str {
+        std::iter::once(distro_id)
+            .chain(id_like.split_whitespace())
+            .map(str::trim)
+            .find(|id| !id.is_empty() && self.logos.contains_key(&id.to_lowercase()))
+            .unwrap_or(os_name)
+    }
+
     /// Get colors for a specific OS/distribution
     pub fn get_colors(&self, os_name: &str) -> Option<&Vec<Color>> {
         let normalized_name = os_name.to_lowercase();
@@ -276,6 +447,15 @@ impl AsciiArt {
             return self.colors.get("debian");
         } else if normalized_name.contains("fedora") {
             return self.colors.get("fedora");
+        } else if normalized_name.contains("mint") {
+            return self.colors.get("linuxmint");
+        } else if normalized_name.contains("manjaro") {
+            return self.colors.get("manjaro");
+        } else if normalized_name.contains("pop") {
+            // os-release names it "Pop!_OS" with ID=pop
+            return self.colors.get("pop");
+        } else if normalized_name.contains("nixos") {
+            return self.colors.get("nixos");
         } else if normalized_name.contains("mac") || normalized_name.contains("darwin") {
             return self.colors.get("macos");
         } else if normalized_name.contains("windows") {
@@ -289,26 +469,68 @@ impl AsciiArt {
     }
 
     /// Apply colors to ASCII art lines
-    pub fn colorize_logo(&self, os_name: &str, logo: &[String]) -> Vec<String> {
+    ///
+    /// `ascii_colors` gives the color of each line in turn, as set by
+    /// `--ascii-colors`: `distro` keeps the distro's color for that line,
+    /// `fg` the terminal's default and a number picks one of 256 colors.
+    pub fn colorize_logo(
+        &self,
+        os_name: &str,
+        logo: &[String],
+        ascii_colors: &[String],
+    ) -> Vec<String> {
         let default_colors = vec![Color::White];
         let colors = self.get_colors(os_name).unwrap_or(&default_colors);
-        let mut colored_lines = Vec::new();
+        let mut colored_lines = Vec::with_capacity(logo.len());
 
         for (i, line) in logo.iter().enumerate() {
-            let color_index = i % colors.len();
-            let color = &colors[color_index];
-
-            let colored_line = match color {
-                Color::Red => line.red().to_string(),
-                Color::Green => line.green().to_string(),
-                Color::Yellow => line.yellow().to_string(),
-                Color::Blue => line.blue().to_string(),
-                Color::Magenta => line.magenta().to_string(),
-                Color::Cyan => line.cyan().to_string(),
-                Color::White => line.white().to_string(),
-                _ => line.to_string(),
+            // Pre-colored art keeps its own escape codes, unless color is off
+            if line.contains('\x1b') {
+                if colored::control::SHOULD_COLORIZE.should_colorize() {
+                    colored_lines.push(line.clone());
+                } else {
+                    colored_lines.push(self.strip_ansi_codes(line));
+                }
+                continue;
+            }
+
+            let slot = match ascii_colors {
+                [] => "distro",
+                slots => slots[i % slots.len()].as_str(),
+            };
+            let number_style;
+            let style = match (slot, slot.parse::<u8>()) {
+                ("distro", _) => match colors[i % colors.len()] {
+                    Color::Red => "31",
+                    Color::Green => "32",
+                    Color::Yellow => "33",
+                    Color::Blue => "34",
+                    Color::Magenta => "35",
+                    Color::Cyan => "36",
+                    Color::White => "37",
+                    _ => {
+                        colored_lines.push(line.clone());
+                        continue;
+                    }
+                },
+                (_, Ok(number)) => {
+                    number_style = match number {
+                        0..=7 => format!("3{}", number),
+                        8..=15 => format!("9{}", number - 8),
+                        _ => format!("38;5;{}", number),
+                    };
+                    &number_style
+                }
+                // `fg`
+                _ => {
+                    colored_lines.push(line.clone());
+                    continue;
+                }
             };
 
+            // Room for the color and reset codes
+            let mut colored_line = String::with_capacity(line.len() + 9);
+            crate::utils::push_styled(&mut colored_line, line, style);
             colored_lines.push(colored_line);
         }
 
@@ -332,9 +554,7 @@ impl AsciiArt {
         // Simple ANSI escape code removal
         let mut result = String::new();
         let mut in_escape = false;
-        let mut chars = text.chars();
-
-        while let Some(ch) = chars.next() {
+        for ch in text.chars() {
             if ch == '\x1b' {
                 in_escape = true;
             } else if in_escape && ch == 'm' {
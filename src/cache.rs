@@ -0,0 +1,234 @@
+//! On-disk cache for expensive system information
+//!
+//! Slow fields such as package counts are stored in `cache_dir/cache.json`
+//! along with the time they were gathered, and reused while still fresh.
+//! Hardware facts are kept per boot instead, see [`boot_key`].
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File name of the cache inside `cache_dir`
+pub const CACHE_FILE: &str = "cache.json";
+
+/// A cached value and when it was gathered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    saved_at: u64,
+    value: serde_json::Value,
+}
+
+/// Cached field values, keyed by field name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldCache {
+    entries: HashMap<String, Entry>,
+    /// The boot that `boot_entries` were gathered in
+    #[serde(default)]
+    boot_key: Option<String>,
+    /// Values that stay valid until the next reboot
+    #[serde(default)]
+    boot_entries: HashMap<String, serde_json::Value>,
+}
+
+impl FieldCache {
+    /// Load the cache, starting empty if it's missing or corrupt
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the cache, creating the cache directory if needed
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Get a value gathered less than `ttl` ago
+    pub fn get<T: DeserializeOwned>(&self, field: &str, ttl: Duration) -> Option<T> {
+        let entry = self.entries.get(field)?;
+        if now().saturating_sub(entry.saved_at) >= ttl.as_secs() {
+            return None;
+        }
+        serde_json::from_value(entry.value.clone()).ok()
+    }
+
+    /// Store a freshly gathered value
+    pub fn set<T: Serialize>(&mut self, field: &str, value: &T) {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.entries.insert(
+                field.to_string(),
+                Entry {
+                    saved_at: now(),
+                    value,
+                },
+            );
+        }
+    }
+}
+
+impl FieldCache {
+    /// Get a value stored during the boot identified by `boot_key`
+    pub fn get_boot<T: DeserializeOwned>(&self, boot_key: &str, field: &str) -> Option<T> {
+        if self.boot_key.as_deref() != Some(boot_key) {
+            return None;
+        }
+        serde_json::from_value(self.boot_entries.get(field)?.clone()).ok()
+    }
+
+    /// Store a value until the next reboot
+    ///
+    /// Values from an earlier boot are dropped first.
+    pub fn set_boot<T: Serialize>(&mut self, boot_key: &str, field: &str, value: &T) {
+        if self.boot_key.as_deref() != Some(boot_key) {
+            self.boot_entries.clear();
+            self.boot_key = Some(boot_key.to_string());
+        }
+        if let Ok(value) = serde_json::to_value(value) {
+            self.boot_entries.insert(field.to_string(), value);
+        }
+    }
+}
+
+/// Identify the current boot of this machine
+///
+/// `/etc/machine-id` plus the kernel's random `boot_id`, which changes on
+/// every boot. Other platforms use the hostname and boot time instead.
+/// `None` where neither is available, so nothing is cached per boot there.
+pub fn boot_key() -> Option<String> {
+    linux_boot_key(Path::new("/")).or_else(|| {
+        let boot_time = sysinfo::System::boot_time();
+        (boot_time > 0).then(|| format!("{}:{}", crate::utils::get_hostname(), boot_time))
+    })
+}
+
+/// [`boot_key`] from the machine and boot IDs under `root`
+fn linux_boot_key(root: &Path) -> Option<String> {
+    let read = |path: &str| {
+        std::fs::read_to_string(root.join(path))
+            .ok()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+    };
+    let machine_id = read("etc/machine-id")?;
+    let boot_id = read("proc/sys/kernel/random/boot_id")?;
+    Some(format!("{}:{}", machine_id, boot_id))
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fake root with a machine ID and the given `boot_id`
+    fn fake_root(boot_id: &str) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("etc")).unwrap();
+        std::fs::create_dir_all(root.path().join("proc/sys/kernel/random")).unwrap();
+        std::fs::write(
+            root.path().join("etc/machine-id"),
+            "b08dfa6083e7567a1921a715000001fb\n",
+        )
+        .unwrap();
+        set_boot_id(root.path(), boot_id);
+        root
+    }
+
+    fn set_boot_id(root: &Path, boot_id: &str) {
+        std::fs::write(root.join("proc/sys/kernel/random/boot_id"), boot_id).unwrap();
+    }
+
+    #[test]
+    fn boot_key_needs_both_ids() {
+        let root = fake_root("8d5c1e0a-4f3b-4a8e-9d57-2b6f0c9e1a44\n");
+        assert_eq!(
+            linux_boot_key(root.path()).as_deref(),
+            Some("b08dfa6083e7567a1921a715000001fb:8d5c1e0a-4f3b-4a8e-9d57-2b6f0c9e1a44")
+        );
+
+        set_boot_id(root.path(), " \n");
+        assert_eq!(linux_boot_key(root.path()), None);
+        std::fs::remove_file(root.path().join("proc/sys/kernel/random/boot_id")).unwrap();
+        assert_eq!(linux_boot_key(root.path()), None);
+        assert_eq!(linux_boot_key(&root.path().join("missing")), None);
+    }
+
+    #[test]
+    fn boot_entries_expire_when_boot_id_rotates() {
+        let root = fake_root("first-boot");
+        let path = root.path().join("cache").join(CACHE_FILE);
+        let first = linux_boot_key(root.path()).unwrap();
+
+        let mut cache = FieldCache::default();
+        cache.set_boot(&first, "hardware", &"Ryzen 7 5800X");
+        cache.set_boot(&first, "other", &1);
+        cache.set("packages", &[(1204, "pacman")]);
+        cache.save(&path).unwrap();
+
+        // Same boot: everything survives a reload
+        let cache = FieldCache::load(&path);
+        let key = linux_boot_key(root.path()).unwrap();
+        assert_eq!(
+            cache.get_boot::<String>(&key, "hardware").as_deref(),
+            Some("Ryzen 7 5800X")
+        );
+
+        // Reboot: boot values are gone, TTL values aren't affected
+        set_boot_id(root.path(), "second-boot");
+        let mut cache = FieldCache::load(&path);
+        let second = linux_boot_key(root.path()).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(cache.get_boot::<String>(&second, "hardware"), None);
+        assert_eq!(
+            cache.get::<Vec<(usize, String)>>("packages", Duration::from_secs(60)),
+            Some(vec![(1204, "pacman".to_string())])
+        );
+
+        // Storing for the new boot drops every value from the old one
+        cache.set_boot(&second, "hardware", &"Ryzen 7 5800X3D");
+        assert_eq!(cache.get_boot::<i32>(&second, "other"), None);
+        assert_eq!(cache.get_boot::<String>(&first, "hardware"), None);
+        cache.save(&path).unwrap();
+        assert_eq!(
+            FieldCache::load(&path)
+                .get_boot::<String>(&second, "hardware")
+                .as_deref(),
+            Some("Ryzen 7 5800X3D")
+        );
+    }
+
+    #[test]
+    fn expired_and_corrupt_entries_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CACHE_FILE);
+
+        let mut cache = FieldCache::default();
+        cache.set("packages", &3);
+        assert_eq!(
+            cache.get::<i32>("packages", Duration::from_secs(60)),
+            Some(3)
+        );
+        assert_eq!(cache.get::<i32>("packages", Duration::ZERO), None);
+        assert_eq!(
+            cache.get::<String>("packages", Duration::from_secs(60)),
+            None
+        );
+
+        std::fs::write(&path, "{not json").unwrap();
+        let cache = FieldCache::load(&path);
+        assert_eq!(cache.get::<i32>("packages", Duration::from_secs(60)), None);
+    }
+}
@@ -3,15 +3,43 @@
 //! This module handles command-line argument parsing and configuration.
 
 use crate::config::*;
-use anyhow::Result;
-use clap::{Arg, ArgAction, Command};
+use crate::paths::Paths;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{error::ErrorKind, Arg, ArgAction, ArgGroup, Command};
+use std::path::{Path, PathBuf};
 
-/// Parse command-line arguments and return a configuration
-pub fn parse_args() -> Result<Config> {
-    let matches = Command::new("neofetch-rs")
-        .version("7.1.0")
+/// Examples shown at the end of `--help`
+const EXAMPLES: &str = "\
+Examples:
+  neofetch-rs --ascii arch --color-blocks off
+  neofetch-rs --json | jq .memory
+  neofetch-rs --stdout --memory-unit mib";
+
+/// Build the command-line interface definition
+pub fn build_cli() -> Command {
+    Command::new("neofetch-rs")
+        .version(env!("CARGO_PKG_VERSION"))
         .author("Zibo Wang <zibo.w@outlook.com>")
         .about("A fast, highly customizable system info script written in Rust")
+        .after_help(EXAMPLES)
+        // Only one output format at a time. They all win over layout flags
+        // such as --stdout, --ascii or --color-blocks, which are ignored.
+        .group(ArgGroup::new("format").args([
+            "json",
+            "yaml",
+            "output_format",
+            "accessible",
+            "color_test",
+            "serve",
+            "tui",
+        ]))
+        .next_help_heading("Behavior")
+        .arg(
+            Arg::new("help_full")
+                .long("help-full")
+                .help("Print help along with every config key and its default")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("config")
                 .long("config")
@@ -25,6 +53,7 @@ pub fn parse_args() -> Result<Config> {
                 .help("Don't load any config file")
                 .action(ArgAction::SetTrue),
         )
+        .next_help_heading("Output formats")
         .arg(
             Arg::new("stdout")
                 .long("stdout")
@@ -37,6 +66,82 @@ pub fn parse_args() -> Result<Config> {
                 .help("Output system information in JSON format")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("yaml")
+                .long("yaml")
+                .help("Output system information in YAML format")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["json", "yaml", "jsonl"])
+                .help("Structured output format; jsonl prints one JSON object per field")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("accessible")
+                .long("accessible")
+                .help("Screen-reader friendly output: no logo, colors or shorthand")
+                .action(ArgAction::SetTrue),
+        )
+        .next_help_heading("Behavior")
+        .arg(
+            Arg::new("color_test")
+                .long("color-test")
+                .help("Print a terminal color test pattern instead of the system info")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("serve")
+                .long("serve")
+                .help("Answer field queries read from stdin, one per line")
+                .long_help(crate::serve::PROTOCOL)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("tui")
+                .long("tui")
+                .help("Browse fields and edit the config interactively")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no_cache")
+                .long("no-cache")
+                .help("Gather everything fresh, without reading or writing the cache")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("anonymize")
+                .long("anonymize")
+                .help("Hide usernames, hostnames, IP addresses and serial numbers")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("set_title")
+                .long("set-title")
+                .help("Put a short summary in the terminal window title")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("notify_low_disk")
+                .long("notify-low-disk")
+                .value_name("PERCENT")
+                .value_parser(clap::value_parser!(u8).range(0..=100))
+                .help("Send a notification when a disk is fuller than PERCENT"),
+        )
+        .arg(
+            Arg::new("preset").long("preset").value_name("NAME").help(
+                "Apply a named bundle of settings: default, tiny, full or one from [presets]",
+            ),
+        )
+        .arg(
+            Arg::new("portable")
+                .long("portable")
+                .value_name("DIR")
+                .help("Keep config and cache under DIR (also set by NEOFETCH_HOME)"),
+        )
         .arg(
             Arg::new("verbose")
                 .short('v')
@@ -44,11 +149,27 @@ pub fn parse_args() -> Result<Config> {
                 .help("Display verbose output")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Don't print warnings or notices")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .help("Print how long gathering and rendering took, including time queued for commands")
+                .action(ArgAction::SetTrue),
+        )
         // Info options
+        .next_help_heading("Info")
         .arg(
             Arg::new("title_fqdn")
                 .long("title-fqdn")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Hide/Show Fully qualified domain name")
                 .action(ArgAction::Set),
         )
@@ -63,6 +184,8 @@ pub fn parse_args() -> Result<Config> {
             Arg::new("os_arch")
                 .long("os-arch")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Hide/Show OS Architecture")
                 .action(ArgAction::Set),
         )
@@ -77,6 +200,8 @@ pub fn parse_args() -> Result<Config> {
             Arg::new("cpu_speed")
                 .long("cpu-speed")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Hide/Show cpu speed")
                 .action(ArgAction::Set),
         )
@@ -84,9 +209,36 @@ pub fn parse_args() -> Result<Config> {
             Arg::new("speed_type")
                 .long("speed-type")
                 .value_name("TYPE")
+                .value_parser([
+                    "scaling_cur_freq",
+                    "scaling",
+                    "base_frequency",
+                    "base",
+                    "cpuinfo_max_freq",
+                    "max",
+                    "bios_limit",
+                    "bios",
+                ])
                 .help("Change the type of cpu speed to display")
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("cpu_temp")
+                .long("cpu-temp")
+                .value_name("C/F/off")
+                .value_parser(["C", "c", "F", "f", "off"])
+                .help("Hide/Show CPU temperature")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("cpu_usage")
+                .long("cpu-usage")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Append the current CPU usage (adds ~200ms)")
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("distro_shorthand")
                 .long("distro-shorthand")
@@ -98,6 +250,8 @@ pub fn parse_args() -> Result<Config> {
             Arg::new("kernel_shorthand")
                 .long("kernel-shorthand")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Shorten the output of kernel")
                 .action(ArgAction::Set),
         )
@@ -108,18 +262,73 @@ pub fn parse_args() -> Result<Config> {
                 .help("Shorten the output of uptime")
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("uptime_style")
+                .long("uptime-style")
+                .value_name("STYLE")
+                .value_parser(["units", "relative"])
+                .help("Show uptime as a duration or as 'up since' the boot time")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("datetime_format")
+                .long("datetime-format")
+                .value_name("FORMAT")
+                .help("strftime format of the date line, e.g. '%F %R'; empty or invalid hides it")
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("shell_path")
                 .long("shell-path")
                 .value_name("BOOL")
-                .help("Show the path to $SHELL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show the full path to the shell")
                 .action(ArgAction::Set),
         )
         .arg(
             Arg::new("shell_version")
                 .long("shell-version")
                 .value_name("BOOL")
-                .help("Show $SHELL version")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show the shell version")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("locale_shorthand")
+                .long("locale-shorthand")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Hide the .UTF-8 suffix of the locale")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("gpu_memory")
+                .long("gpu-memory")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Append the video memory of a dedicated GPU")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("users_sessions")
+                .long("users-sessions")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("List the terminals or sessions of each logged-in user")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("refresh_rate")
+                .long("refresh-rate")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show the refresh rate of each display")
                 .action(ArgAction::Set),
         )
         .arg(
@@ -133,10 +342,91 @@ pub fn parse_args() -> Result<Config> {
             Arg::new("memory_percent")
                 .long("memory-percent")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Display memory percentage")
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("swap")
+                .long("swap")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Hide/Show swap usage")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("services")
+                .long("services")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show failed systemd units (or enabled runit/openrc services)")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("install_date")
+                .long("install-date")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show when the OS was installed")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("disk")
+                .long("disk")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show usage of the root filesystem")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("editor")
+                .long("editor")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show the default editor from $VISUAL or $EDITOR")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("multiplexer")
+                .long("multiplexer")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show the terminal multiplexer (tmux, screen or zellij)")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("show_container")
+                .long("show-container")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Show the container runtime as the host when in a container")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("show_virt")
+                .long("show-virt")
+                .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
+                .help("Tag the OS with (container) or the hypervisor, e.g. (kvm)")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("weather")
+                .long("weather")
+                .help("Show the current weather (fetched from wttr.in)")
+                .action(ArgAction::SetTrue),
+        )
         // Display options
+        .next_help_heading("Display")
         .arg(
             Arg::new("backend")
                 .long("backend")
@@ -162,36 +452,73 @@ pub fn parse_args() -> Result<Config> {
             Arg::new("ascii_colors")
                 .long("ascii-colors")
                 .value_name("COLORS")
-                .help("Colors to print the ascii art")
+                .help("Colors to print the ascii art: 0-255, 'distro' or 'fg', space or comma separated")
+                .num_args(1..)
                 .action(ArgAction::Set),
         )
         .arg(
             Arg::new("ascii_bold")
                 .long("ascii-bold")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Whether or not to bold the ascii logo")
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("banner")
+                .long("banner")
+                .value_name("FONT")
+                .num_args(0..=1)
+                .default_missing_value(crate::figlet::DEFAULT_FONT)
+                .help("Render the title as a large banner above the info")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("banner_as_logo")
+                .long("banner-as-logo")
+                .help("Show the banner in place of the logo")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("logo")
                 .short('L')
                 .long("logo")
                 .help("Hide the info text and only show the ascii logo")
+                .conflicts_with_all(["format", "stdout"])
                 .action(ArgAction::SetTrue),
         )
         // Color options
+        .next_help_heading("Colors")
+        .arg(
+            Arg::new("no_color")
+                .long("no-color")
+                .help("Disable all colors, also set by the NO_COLOR env var")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("color_blocks")
                 .long("color-blocks")
                 .value_name("BOOL")
+                .value_parser(BOOL_VALUES)
+                .ignore_case(true)
                 .help("Enable/Disable the color blocks")
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("colors")
+                .long("colors")
+                .value_name("COLORS")
+                .help("Colors for the title, @, underline, subtitle, colon and info (0-255)")
+                .num_args(1..)
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("block_range")
                 .long("block-range")
                 .value_name("RANGE")
                 .help("Range of colors to print as blocks")
+                .num_args(1..=2)
                 .action(ArgAction::Set),
         )
         .arg(
@@ -199,6 +526,7 @@ pub fn parse_args() -> Result<Config> {
                 .long("block-width")
                 .value_name("NUM")
                 .help("Width of the color blocks")
+                .value_parser(clap::value_parser!(u8).range(1..))
                 .action(ArgAction::Set),
         )
         .arg(
@@ -206,15 +534,82 @@ pub fn parse_args() -> Result<Config> {
                 .long("block-height")
                 .value_name("NUM")
                 .help("Height of the color blocks")
+                .value_parser(clap::value_parser!(u8).range(1..))
                 .action(ArgAction::Set),
         )
-        .get_matches();
+        .subcommand(
+            Command::new("config")
+                .about("Inspect the config file")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("diff-defaults")
+                        .about("Show new, changed and unknown keys compared to the defaults")
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .help("Print the report as JSON")
+                                .action(ArgAction::SetTrue),
+                        ),
+                ),
+        )
+}
+
+/// Parse command-line arguments and return a configuration
+pub fn parse_args() -> Result<Config> {
+    let mut command = build_cli();
+    let matches = command.clone().get_matches();
+
+    // --ascii picks the ascii backend, so any other backend contradicts it
+    if let (Some(backend), Some(_)) = (
+        matches.get_one::<String>("backend"),
+        matches.get_one::<String>("ascii"),
+    ) {
+        if backend != "ascii" {
+            command
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "'--backend {}' cannot be used with '--ascii', which implies '--backend ascii'",
+                        backend
+                    ),
+                )
+                .exit();
+        }
+    }
+
+    if matches.get_flag("help_full") {
+        println!("{}", command.render_long_help());
+        println!("Config keys (with defaults):");
+        println!("{}", config_reference()?);
+        std::process::exit(0);
+    }
+
+    let paths = Paths::resolve(matches.get_one::<String>("portable").map(Path::new));
+
+    if let Some(("config", config_matches)) = matches.subcommand() {
+        if let Some(("diff-defaults", diff_matches)) = config_matches.subcommand() {
+            let config_file = matches
+                .get_one::<String>("config")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| paths.config_file());
+            let source = std::fs::read_to_string(&config_file)
+                .with_context(|| format!("No config file at {}", config_file.display()))?;
+            let diff = crate::config_diff::diff_defaults(&source)?;
+
+            if diff_matches.get_flag("json") {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+            } else {
+                println!("{}", diff.to_text());
+            }
+            std::process::exit(0);
+        }
+    }
 
     // Start with default config or load from file
     let mut config = if matches.get_flag("no_config") {
         Config::default()
     } else {
-        Config::load().unwrap_or_default()
+        Config::load(&paths).unwrap_or_default()
     };
 
     // Override with command-line arguments
@@ -222,6 +617,18 @@ pub fn parse_args() -> Result<Config> {
         let config_str = std::fs::read_to_string(config_file)?;
         config = toml::from_str(&config_str)?;
     }
+    config.apply_paths(&paths);
+    config.behavior.config_file = Some(
+        matches
+            .get_one::<String>("config")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| paths.config_file()),
+    );
+
+    // Presets sit between the config file and explicit flags
+    if let Some(preset) = matches.get_one::<String>("preset") {
+        config.apply_preset(preset)?;
+    }
 
     if matches.get_flag("stdout") {
         config.display.stdout = true;
@@ -232,10 +639,70 @@ pub fn parse_args() -> Result<Config> {
         config.behavior.json = true;
     }
 
+    if matches.get_flag("yaml") {
+        config.behavior.yaml = true;
+    }
+
+    if let Some(value) = matches.get_one::<String>("output_format") {
+        match value.as_str() {
+            "json" => config.behavior.json = true,
+            "yaml" => config.behavior.yaml = true,
+            _ => config.behavior.jsonl = true,
+        }
+    }
+
+    if matches.get_flag("accessible") {
+        config.behavior.accessible = true;
+        config.format.color_blocks = false;
+        config.info.uptime_shorthand = UptimeShorthand::Off;
+        config.info.distro_shorthand = DistroShorthand::Off;
+    }
+
+    if matches.get_flag("color_test") {
+        config.behavior.color_test = true;
+    }
+
+    if matches.get_flag("serve") {
+        config.behavior.serve = true;
+    }
+
+    if matches.get_flag("tui") {
+        if !cfg!(feature = "tui") {
+            bail!(
+                "--tui needs neofetch-rs built with the tui feature (cargo build --features tui)"
+            );
+        }
+        config.behavior.tui = true;
+    }
+
+    if matches.get_flag("no_cache") {
+        config.behavior.no_cache = true;
+    }
+
+    if matches.get_flag("anonymize") {
+        config.behavior.anonymize = true;
+    }
+
+    if matches.get_flag("set_title") {
+        config.behavior.set_title = true;
+    }
+
+    if let Some(percent) = matches.get_one::<u8>("notify_low_disk") {
+        config.behavior.notify_low_disk = Some(*percent);
+    }
+
     if matches.get_flag("verbose") {
         config.behavior.verbose = true;
     }
 
+    if matches.get_flag("quiet") {
+        config.behavior.quiet = true;
+    }
+
+    if matches.get_flag("stats") {
+        config.behavior.stats = true;
+    }
+
     if matches.get_flag("logo") {
         config.display.image_backend = ImageBackend::Ascii;
         // Hide info text, only show logo
@@ -243,7 +710,7 @@ pub fn parse_args() -> Result<Config> {
 
     // Info options
     if let Some(value) = matches.get_one::<String>("title_fqdn") {
-        config.info.title_fqdn = value.parse().unwrap_or(false);
+        config.info.title_fqdn = parse_bool(value).unwrap_or(false);
     }
 
     if let Some(value) = matches.get_one::<String>("package_managers") {
@@ -256,7 +723,7 @@ pub fn parse_args() -> Result<Config> {
     }
 
     if let Some(value) = matches.get_one::<String>("os_arch") {
-        config.info.os_arch = value.parse().unwrap_or(true);
+        config.info.os_arch = parse_bool(value).unwrap_or(true);
     }
 
     if let Some(value) = matches.get_one::<String>("cpu_cores") {
@@ -268,6 +735,31 @@ pub fn parse_args() -> Result<Config> {
         };
     }
 
+    if let Some(value) = matches.get_one::<String>("cpu_speed") {
+        config.info.cpu_speed = parse_bool(value).unwrap_or(true);
+    }
+
+    if let Some(value) = matches.get_one::<String>("speed_type") {
+        config.info.speed_type = match value.as_str() {
+            "scaling_cur_freq" | "scaling" => SpeedType::Scaling,
+            "base_frequency" | "base" => SpeedType::Base,
+            "cpuinfo_max_freq" | "max" => SpeedType::Max,
+            _ => SpeedType::Bios,
+        };
+    }
+
+    if let Some(value) = matches.get_one::<String>("cpu_temp") {
+        config.info.cpu_temp = match value.as_str() {
+            "C" | "c" => CpuTemp::C,
+            "F" | "f" => CpuTemp::F,
+            _ => CpuTemp::Off,
+        };
+    }
+
+    if let Some(value) = matches.get_one::<String>("cpu_usage") {
+        config.info.cpu_usage = parse_bool(value).unwrap_or(false);
+    }
+
     if let Some(value) = matches.get_one::<String>("distro_shorthand") {
         config.info.distro_shorthand = match value.as_str() {
             "on" => DistroShorthand::On,
@@ -277,6 +769,10 @@ pub fn parse_args() -> Result<Config> {
         };
     }
 
+    if let Some(value) = matches.get_one::<String>("kernel_shorthand") {
+        config.info.kernel_shorthand = parse_bool(value).unwrap_or(true);
+    }
+
     if let Some(value) = matches.get_one::<String>("uptime_shorthand") {
         config.info.uptime_shorthand = match value.as_str() {
             "on" => UptimeShorthand::On,
@@ -286,6 +782,41 @@ pub fn parse_args() -> Result<Config> {
         };
     }
 
+    if let Some(value) = matches.get_one::<String>("uptime_style") {
+        config.info.uptime_style = match value.as_str() {
+            "relative" => UptimeStyle::Relative,
+            _ => UptimeStyle::Units,
+        };
+    }
+
+    if let Some(value) = matches.get_one::<String>("datetime_format") {
+        config.info.datetime_format = value.clone();
+    }
+
+    if let Some(value) = matches.get_one::<String>("shell_path") {
+        config.info.shell_path = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("shell_version") {
+        config.info.shell_version = parse_bool(value).unwrap_or(true);
+    }
+
+    if let Some(value) = matches.get_one::<String>("locale_shorthand") {
+        config.info.locale_shorthand = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("gpu_memory") {
+        config.info.gpu_memory = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("users_sessions") {
+        config.info.users_sessions = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("refresh_rate") {
+        config.info.refresh_rate = parse_bool(value).unwrap_or(false);
+    }
+
     if let Some(value) = matches.get_one::<String>("memory_unit") {
         config.info.memory_unit = match value.as_str() {
             "kib" => MemoryUnit::Kib,
@@ -295,6 +826,46 @@ pub fn parse_args() -> Result<Config> {
         };
     }
 
+    if let Some(value) = matches.get_one::<String>("memory_percent") {
+        config.info.memory_percent = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("swap") {
+        config.info.swap = parse_bool(value).unwrap_or(true);
+    }
+
+    if let Some(value) = matches.get_one::<String>("services") {
+        config.info.services = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("install_date") {
+        config.info.install_date = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("disk") {
+        config.info.disk = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("editor") {
+        config.info.editor = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("multiplexer") {
+        config.info.multiplexer = parse_bool(value).unwrap_or(false);
+    }
+
+    if let Some(value) = matches.get_one::<String>("show_container") {
+        config.info.show_container = parse_bool(value).unwrap_or(true);
+    }
+
+    if let Some(value) = matches.get_one::<String>("show_virt") {
+        config.info.show_virt = parse_bool(value);
+    }
+
+    if matches.get_flag("weather") {
+        config.info.weather = true;
+    }
+
     // Display options
     if let Some(value) = matches.get_one::<String>("backend") {
         config.display.image_backend = match value.as_str() {
@@ -317,27 +888,321 @@ pub fn parse_args() -> Result<Config> {
         };
     }
 
+    if let Some(value) = matches.get_one::<String>("source") {
+        config.display.image_source = match value.as_str() {
+            "auto" => ImageSource::Auto,
+            "ascii" => ImageSource::Ascii,
+            "wallpaper" => ImageSource::Wallpaper,
+            path => ImageSource::Path(PathBuf::from(path)),
+        };
+    }
+
     if let Some(value) = matches.get_one::<String>("ascii") {
         config.display.image_backend = ImageBackend::Ascii;
         config.display.ascii_distro = Some(value.clone());
     }
 
+    if let Some(value) = matches.get_one::<String>("banner") {
+        config.display.banner = value.clone();
+    }
+
+    if matches.get_flag("banner_as_logo") {
+        config.display.banner_as_logo = true;
+        if config.display.banner.is_empty() {
+            config.display.banner = crate::figlet::DEFAULT_FONT.to_string();
+        }
+    }
+
+    if let Some(value) = matches
+        .get_many::<String>("ascii_colors")
+        .map(|values| values.cloned().collect::<Vec<_>>().join(" "))
+    {
+        config.display.ascii_colors = parse_colors("--ascii-colors", &value, true)
+            .unwrap_or_else(|error| usage_error(&mut command, error));
+    }
+
+    if let Some(value) = matches
+        .get_many::<String>("colors")
+        .map(|values| values.cloned().collect::<Vec<_>>().join(" "))
+    {
+        config.info.colors = parse_colors("--colors", &value, false)
+            .unwrap_or_else(|error| usage_error(&mut command, error))
+            .iter()
+            .map(|color| color.parse())
+            .collect::<Result<_, _>>()?;
+    }
+
     if let Some(value) = matches.get_one::<String>("ascii_bold") {
-        config.display.ascii_bold = value.parse().unwrap_or(true);
+        config.display.ascii_bold = parse_bool(value).unwrap_or(true);
     }
 
     // Color options
+    if matches.get_flag("no_color") {
+        config.format.no_color = true;
+    }
+
     if let Some(value) = matches.get_one::<String>("color_blocks") {
-        config.format.color_blocks = value.parse().unwrap_or(true);
+        config.format.color_blocks = parse_bool(value).unwrap_or(true);
+    }
+
+    if let Some(value) = matches
+        .get_many::<String>("block_range")
+        .map(|values| values.cloned().collect::<Vec<_>>().join(" "))
+    {
+        config.format.block_range =
+            parse_block_range(&value).unwrap_or_else(|error| usage_error(&mut command, error));
     }
 
-    if let Some(value) = matches.get_one::<String>("block_width") {
-        config.format.block_width = value.parse().unwrap_or(3);
+    if let Some(value) = matches.get_one::<u8>("block_width") {
+        config.format.block_width = *value;
     }
 
-    if let Some(value) = matches.get_one::<String>("block_height") {
-        config.format.block_height = value.parse().unwrap_or(1);
+    if let Some(value) = matches.get_one::<u8>("block_height") {
+        config.format.block_height = *value;
     }
 
     Ok(config)
 }
+
+/// List every config key with its default value, one `section.key = value`
+/// per line
+///
+/// Comes from the same key registry as `config diff-defaults`, so it can't
+/// drift from the real keys. Options without a default are marked unset.
+pub fn config_reference() -> Result<String> {
+    let mut lines: Vec<String> = crate::config_diff::known_keys()?
+        .into_iter()
+        .map(|(key, default)| match default {
+            Some(default) => format!("  {} = {}", key, default),
+            None => format!("  {} (unset)", key),
+        })
+        .collect();
+    lines.push("  presets.<name> = { <any keys above> } (select with --preset)".to_string());
+
+    Ok(lines.join("\n"))
+}
+
+/// Exit with a usage error for a flag value that failed to parse
+fn usage_error(command: &mut Command, error: anyhow::Error) -> ! {
+    command.error(ErrorKind::InvalidValue, error).exit()
+}
+
+/// Values accepted by `BOOL` options, in any case, see [`parse_bool`]
+const BOOL_VALUES: [&str; 8] = ["on", "off", "true", "false", "yes", "no", "1", "0"];
+
+/// Parse a boolean flag value, accepting neofetch's on/off as well as true/false
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a color block range such as `0-7`, `8-15` or `0 7`
+fn parse_block_range(value: &str) -> Result<(u8, u8)> {
+    let invalid = || {
+        anyhow!(
+            "invalid --block-range '{}': expected START-END with 0 <= START <= END <= 15",
+            value
+        )
+    };
+
+    let (start, end) = value
+        .split_once('-')
+        .or_else(|| value.trim().split_once(char::is_whitespace))
+        .ok_or_else(invalid)?;
+    let start: u8 = start.trim().parse().map_err(|_| invalid())?;
+    let end: u8 = end.trim().parse().map_err(|_| invalid())?;
+
+    if start > end || end > 15 {
+        return Err(invalid());
+    }
+
+    Ok((start, end))
+}
+
+/// Parse a list of colors such as `4 6 1` or `4,6,1`
+///
+/// Each color is a number from 0 to 255; with `keywords` the `distro` and
+/// `fg` keywords are accepted too.
+fn parse_colors(flag: &str, value: &str, keywords: bool) -> Result<Vec<String>> {
+    let colors: Vec<String> = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|color| !color.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    if colors.is_empty() {
+        bail!("invalid {} '{}': expected at least one color", flag, value);
+    }
+
+    for color in &colors {
+        let is_keyword = keywords && (color == "distro" || color == "fg");
+        if !is_keyword && color.parse::<u8>().is_err() {
+            let expected = if keywords {
+                "a number from 0 to 255, 'distro' or 'fg'"
+            } else {
+                "a number from 0 to 255"
+            };
+            bail!(
+                "invalid {} '{}': '{}' is not {}",
+                flag,
+                value,
+                color,
+                expected
+            );
+        }
+    }
+
+    Ok(colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<clap::ArgMatches, clap::Error> {
+        build_cli().try_get_matches_from(std::iter::once("neofetch").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn block_ranges() {
+        for (value, expected) in [
+            ("0-7", Some((0, 7))),
+            ("8-15", Some((8, 15))),
+            ("0 15", Some((0, 15))),
+            (" 3 - 3 ", Some((3, 3))),
+            ("7-0", None),
+            ("0-16", None),
+            ("-1-7", None),
+            ("0", None),
+            ("0-7-9", None),
+            ("a-b", None),
+            ("", None),
+        ] {
+            assert_eq!(parse_block_range(value).ok(), expected, "{:?}", value);
+        }
+
+        let error = parse_block_range("9-3").unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "invalid --block-range '9-3': expected START-END with 0 <= START <= END <= 15"
+        );
+    }
+
+    #[test]
+    fn color_lists() {
+        let colors = |value: &str, keywords: bool| parse_colors("--colors", value, keywords).ok();
+        let list = |colors: &[&str]| Some(colors.iter().map(|c| c.to_string()).collect::<Vec<_>>());
+
+        assert_eq!(colors("4 6 1", false), list(&["4", "6", "1"]));
+        assert_eq!(colors("4,6, 1", false), list(&["4", "6", "1"]));
+        assert_eq!(colors("0 255", false), list(&["0", "255"]));
+        assert_eq!(colors("Distro fg 2", true), list(&["distro", "fg", "2"]));
+        assert_eq!(colors("distro", false), None);
+        assert_eq!(colors("256", false), None);
+        assert_eq!(colors("-1", false), None);
+        assert_eq!(colors(" , ", false), None);
+
+        let error = parse_colors("--ascii-colors", "1 red", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid --ascii-colors '1 red': 'red' is not a number from 0 to 255, 'distro' or 'fg'"
+        );
+    }
+
+    #[test]
+    fn speed_type_rejects_unknown_values() {
+        assert!(parse(&["--speed-type", "scaling"]).is_ok());
+        assert!(parse(&["--speed-type", "bios_limit"]).is_ok());
+
+        let error = parse(&["--speed-type", "turbo"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn cpu_temp_rejects_unknown_values() {
+        assert!(parse(&["--cpu-temp", "F"]).is_ok());
+        assert!(parse(&["--cpu-temp", "off"]).is_ok());
+
+        let error = parse(&["--cpu-temp", "kelvin"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn bool_options_reject_unknown_values() {
+        let bools: Vec<String> = build_cli()
+            .get_arguments()
+            .filter(|arg| {
+                arg.get_value_names()
+                    .is_some_and(|names| names.iter().any(|name| name == "BOOL"))
+            })
+            .filter_map(|arg| arg.get_long().map(|long| format!("--{}", long)))
+            .collect();
+        assert!(bools.len() > 20, "{:?}", bools);
+
+        for flag in &bools {
+            for value in ["on", "OFF", "true", "No", "1", "0"] {
+                assert!(parse(&[flag, value]).is_ok(), "{} {}", flag, value);
+            }
+            let error = parse(&[flag, "yse"]).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidValue, "{}", flag);
+        }
+    }
+
+    #[test]
+    fn config_reference_lists_unset_options_and_presets() {
+        let reference = config_reference().unwrap();
+        for key in [
+            "info.show_virt",
+            "display.ascii_distro",
+            "display.background_color",
+            "behavior.config_file",
+            "behavior.notify_low_disk",
+        ] {
+            // Brand builds give the logo a default
+            let prefix = format!("  {} ", key);
+            assert!(
+                reference.lines().any(|line| line.starts_with(&prefix)),
+                "{} missing",
+                key
+            );
+        }
+        assert!(reference.contains("  behavior.notify_low_disk (unset)\n"));
+        assert!(reference.contains("  info.weather = false\n"));
+        assert!(reference.contains("  presets.<name> = "));
+    }
+
+    #[test]
+    fn output_formats_conflict_pairwise() {
+        let formats: &[&[&str]] = &[
+            &["--json"],
+            &["--yaml"],
+            &["--format", "jsonl"],
+            &["--accessible"],
+            &["--color-test"],
+            &["--serve"],
+            &["--tui"],
+        ];
+        for (i, first) in formats.iter().enumerate() {
+            assert!(parse(first).is_ok(), "{:?}", first);
+            for second in &formats[i + 1..] {
+                let args = [*first, *second].concat();
+                let error = parse(&args).unwrap_err();
+                assert_eq!(error.kind(), ErrorKind::ArgumentConflict, "{:?}", args);
+            }
+        }
+    }
+
+    #[test]
+    fn logo_only_conflicts_with_formats_and_stdout() {
+        for other in ["--json", "--stdout", "--accessible"] {
+            let error = parse(&["--logo", other]).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::ArgumentConflict, "{}", other);
+        }
+        assert!(parse(&["--logo", "--ascii", "arch"]).is_ok());
+        // Layout flags are ignored by formats rather than rejected
+        assert!(parse(&["--json", "--stdout"]).is_ok());
+    }
+}
@@ -1,48 +1,140 @@
 //! Configuration management for neofetch-rs
-//! 
+//!
 //! This module handles loading and managing configuration from files and command-line arguments.
 
-use anyhow::Result;
+use crate::paths::Paths;
+use anyhow::{bail, Context, Result};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[cfg(all(feature = "brand-arch", feature = "brand-fedora"))]
+compile_error!("Only one brand-* feature can be enabled at a time");
 
 /// Main configuration structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// Information display settings
     pub info: InfoConfig,
-    
+
     /// ASCII art and image settings
     pub display: DisplayConfig,
-    
+
     /// Output formatting settings
     pub format: FormatConfig,
-    
+
     /// Performance and behavior settings
     pub behavior: BehaviorConfig,
+
+    /// User-defined presets, selected with `--preset <name>`
+    pub presets: BTreeMap<String, toml::Table>,
+}
+
+/// Presets bundled with neofetch-rs, as TOML applied over the config
+///
+/// A user preset with the same name takes precedence.
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    ("default", ""),
+    (
+        "tiny",
+        r#"
+[info]
+order = ["title", "underline", "os", "kernel", "uptime", "memory"]
+"#,
+    ),
+    (
+        "full",
+        r#"
+[info]
+order = ["title", "underline", "os", "host", "kernel", "uptime", "datetime", "install_date",
+         "packages", "shell", "editor", "resolution", "de", "wm", "wm_theme", "theme",
+         "icons", "terminal", "terminal_font", "multiplexer", "cpu", "gpu", "gpu_driver",
+         "memory", "swap", "disk", "battery", "power_profile", "services", "users",
+         "local_ip", "locale", "song", "weather", "cols"]
+package_managers_extra = ["cargo", "pip", "npm", "gem"]
+cpu_temp = "C"
+cpu_usage = true
+gpu_memory = true
+refresh_rate = true
+memory_percent = true
+battery_show_profile = true
+services = true
+install_date = true
+disk = true
+editor = true
+multiplexer = true
+"#,
+    ),
+];
+
+/// Compiled-in defaults for a distro package, chosen by a `brand-*` feature
+#[derive(Debug, Clone, Copy)]
+pub struct Brand {
+    /// Logo shown regardless of the detected OS
+    pub ascii_distro: &'static str,
+    /// Line printed below the info, e.g. a MOTD footer
+    pub footer: &'static str,
+}
+
+/// The brand enabled at compile time, if any
+pub fn builtin_brand() -> Option<Brand> {
+    #[cfg(feature = "brand-arch")]
+    return Some(Brand {
+        ascii_distro: "arch",
+        footer: "Report packaging issues at https://aur.archlinux.org",
+    });
+
+    #[cfg(feature = "brand-fedora")]
+    return Some(Brand {
+        ascii_distro: "fedora",
+        footer: "Report packaging issues at https://bugzilla.redhat.com",
+    });
+
+    #[cfg(not(any(feature = "brand-arch", feature = "brand-fedora")))]
+    None
 }
 
 /// Information gathering configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InfoConfig {
     pub title_fqdn: bool,
     pub package_managers: PackageManagerDisplay,
+    /// Opt-in language package managers: cargo, pip, npm, gem
+    pub package_managers_extra: Vec<String>,
     pub os_arch: bool,
     pub cpu_cores: CpuCoreDisplay,
     pub cpu_speed: bool,
     pub speed_type: SpeedType,
     pub speed_shorthand: bool,
+    pub cpu_temp: CpuTemp,
+    /// Append the current CPU usage; sampling it takes about 200ms
+    pub cpu_usage: bool,
     pub distro_shorthand: DistroShorthand,
     pub kernel_shorthand: bool,
     pub uptime_shorthand: UptimeShorthand,
+    /// Show uptime as a duration or as the time since boot
+    pub uptime_style: UptimeStyle,
+    /// strftime format of the `datetime` line; empty or invalid hides it
+    pub datetime_format: String,
     pub cpu_brand: bool,
     pub gpu_brand: bool,
     pub gpu_type: GpuType,
+    /// Append dedicated video memory; asking nvidia-smi is slow
+    pub gpu_memory: bool,
     pub refresh_rate: bool,
     pub shell_path: bool,
     pub shell_version: bool,
+    /// Drop the `.UTF-8` codeset from the locale
+    pub locale_shorthand: bool,
+    /// List each user's terminals or sessions, e.g. `alice (tty1 pts/0)`
+    pub users_sessions: bool,
     pub memory_unit: MemoryUnit,
     pub memory_percent: bool,
+    pub swap: bool,
+    pub battery_show_profile: bool,
     pub disk_show: Vec<String>,
     pub disk_subtitle: DiskSubtitle,
     pub disk_percent: bool,
@@ -55,17 +147,95 @@ pub struct InfoConfig {
     pub underline_enabled: bool,
     pub underline_char: String,
     pub separator: String,
+    pub rewrite: Vec<RewriteRule>,
+    /// Info lines in display order; names may repeat
+    pub order: Vec<String>,
+    pub services: bool,
+    /// Show when the OS was installed
+    pub install_date: bool,
+    /// Show usage of the root filesystem
+    pub disk: bool,
+    /// Show `$VISUAL` or `$EDITOR` with its version
+    pub editor: bool,
+    /// Show the tmux, screen or zellij session we're running in
+    pub multiplexer: bool,
+    /// Show the container runtime, e.g. `Docker Container`, as the host
+    pub show_container: bool,
+    /// Tag the OS with `(container)` or the hypervisor, e.g. `(kvm)`;
+    /// unset tags containers only
+    pub show_virt: Option<bool>,
+    pub weather: bool,
+    /// wttr.in-compatible URL, `{location}` is replaced by `weather_location`
+    pub weather_url: String,
+    /// Place to report the weather for; empty lets the server go by IP
+    pub weather_location: String,
+    /// User-defined lines filled from shell commands
+    pub custom: Vec<CustomInfo>,
+    /// Labels by field name, e.g. `memory = "RAM"`; an empty label shows
+    /// the value alone
+    pub labels: BTreeMap<String, String>,
+}
+
+/// A user-defined info line, like `prin "Label" "$(command)"` in neofetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomInfo {
+    /// Referenced as `custom:<name>` in `info.order`; defaults to the label
+    pub name: String,
+    pub label: String,
+    /// Run through `sh -c` (`cmd /C` on Windows)
+    pub command: String,
+    /// Hide the line when the command takes longer than this
+    pub timeout_ms: u64,
+}
+
+impl Default for CustomInfo {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            label: String::new(),
+            command: String::new(),
+            timeout_ms: crate::utils::COMMAND_TIMEOUT.as_millis() as u64,
+        }
+    }
+}
+
+impl CustomInfo {
+    /// Name used in `info.order` and the JSON `custom` object
+    pub fn key(&self) -> &str {
+        if self.name.is_empty() {
+            &self.label
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// A regex rewrite applied to a field's value before it is displayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewriteRule {
+    pub field: String,
+    pub pattern: String,
+    pub replace: String,
+}
+
+/// A rewrite rule with its pattern compiled
+#[derive(Debug, Clone)]
+pub struct CompiledRewrite {
+    pub field: String,
+    pub regex: Regex,
+    pub replace: String,
 }
 
 /// Display and ASCII art configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DisplayConfig {
     pub image_backend: ImageBackend,
     pub image_source: ImageSource,
     pub ascii_distro: Option<String>,
     pub ascii_colors: Vec<String>,
     pub ascii_bold: bool,
-    pub image_loop: bool,
     pub thumbnail_dir: PathBuf,
     pub crop_mode: CropMode,
     pub crop_offset: CropOffset,
@@ -75,11 +245,16 @@ pub struct DisplayConfig {
     pub xoffset: i32,
     pub background_color: Option<String>,
     pub stdout: bool,
+    pub banner: String,
+    pub banner_as_logo: bool,
 }
 
 /// Output formatting configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FormatConfig {
+    pub no_color: bool,
+    pub footer: String,
     pub color_blocks: bool,
     pub block_range: (u8, u8),
     pub block_width: u8,
@@ -95,16 +270,46 @@ pub struct FormatConfig {
     pub memory_display: DisplayMode,
     pub battery_display: DisplayMode,
     pub disk_display: DisplayMode,
+    pub number_locale: NumberLocale,
 }
 
 /// Behavior and performance configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BehaviorConfig {
     pub cache_dir: PathBuf,
     pub config_file: Option<PathBuf>,
     pub stdout: bool,
     pub verbose: bool,
+    /// Don't print warnings or notices to stderr
+    pub quiet: bool,
+    /// Print how long gathering and rendering took to stderr
+    pub stats: bool,
     pub json: bool,
+    pub yaml: bool,
+    pub jsonl: bool,
+    pub accessible: bool,
+    pub color_test: bool,
+    /// Answer field queries from stdin instead of printing once
+    pub serve: bool,
+    /// Browse fields and edit the config in the interactive TUI
+    pub tui: bool,
+    pub no_cache: bool,
+    /// Scrub usernames, hostnames, IPs and serial numbers from the output
+    pub anonymize: bool,
+    /// Put a summary in the terminal window title after rendering
+    pub set_title: bool,
+    /// `{field}` template for the window title, which also takes the compact
+    /// `{hostname}`, `{uptime_short}` and `{memory_percent}`
+    pub title_template: String,
+    /// Notify when a disk is fuller than this many percent
+    pub notify_low_disk: Option<u8>,
+    /// How long cached package counts stay valid
+    pub cache_ttl_secs: u64,
+    /// How long to wait for package managers before skipping them
+    pub package_timeout_ms: u64,
+    /// How many external commands may run at once, 0 for no limit
+    pub max_concurrent_commands: usize,
 }
 
 // Enums for configuration options
@@ -130,6 +335,13 @@ pub enum SpeedType {
     Bios,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CpuTemp {
+    Off,
+    C,
+    F,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DistroShorthand {
     On,
@@ -144,6 +356,14 @@ pub enum UptimeShorthand {
     Off,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UptimeStyle {
+    /// `3 days, 4 hours, 12 mins`, shortened by `uptime_shorthand`
+    Units,
+    /// `up since Tuesday 08:12`, or the date after a week
+    Relative,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GpuType {
     All,
@@ -235,15 +455,15 @@ pub enum DisplayMode {
     Off,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            info: InfoConfig::default(),
-            display: DisplayConfig::default(),
-            format: FormatConfig::default(),
-            behavior: BehaviorConfig::default(),
-        }
-    }
+/// Which locale's conventions to use for decimals and digit grouping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NumberLocale {
+    /// Follow the detected system locale
+    Auto,
+    /// Plain `1843` and `3.5`
+    C,
+    /// A specific locale such as `de_DE`
+    Locale(String),
 }
 
 impl Default for InfoConfig {
@@ -251,22 +471,32 @@ impl Default for InfoConfig {
         Self {
             title_fqdn: false,
             package_managers: PackageManagerDisplay::On,
+            package_managers_extra: Vec::new(),
             os_arch: true,
             cpu_cores: CpuCoreDisplay::Logical,
             cpu_speed: true,
             speed_type: SpeedType::Bios,
             speed_shorthand: false,
+            cpu_temp: CpuTemp::Off,
+            cpu_usage: false,
             distro_shorthand: DistroShorthand::Off,
             kernel_shorthand: true,
             uptime_shorthand: UptimeShorthand::On,
+            uptime_style: UptimeStyle::Units,
+            datetime_format: "%a %d %b %Y %H:%M".to_string(),
             cpu_brand: true,
             gpu_brand: true,
             gpu_type: GpuType::All,
+            gpu_memory: false,
             refresh_rate: false,
             shell_path: false,
             shell_version: true,
+            locale_shorthand: false,
+            users_sessions: false,
             memory_unit: MemoryUnit::Mib,
             memory_percent: false,
+            swap: true,
+            battery_show_profile: false,
             disk_show: vec!["/".to_string()],
             disk_subtitle: DiskSubtitle::Mount,
             disk_percent: true,
@@ -279,6 +509,23 @@ impl Default for InfoConfig {
             underline_enabled: true,
             underline_char: "-".to_string(),
             separator: ":".to_string(),
+            rewrite: vec![],
+            order: DEFAULT_INFO_ORDER
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            services: false,
+            install_date: false,
+            disk: false,
+            editor: false,
+            multiplexer: false,
+            show_container: true,
+            show_virt: None,
+            weather: false,
+            weather_url: "https://wttr.in/{location}?format=3".to_string(),
+            weather_location: String::new(),
+            custom: vec![],
+            labels: BTreeMap::new(),
         }
     }
 }
@@ -288,11 +535,12 @@ impl Default for DisplayConfig {
         Self {
             image_backend: ImageBackend::Ascii,
             image_source: ImageSource::Auto,
-            ascii_distro: None,
+            // Per section, so a config file that sets other display keys
+            // keeps the brand's logo
+            ascii_distro: builtin_brand().map(|brand| brand.ascii_distro.to_string()),
             ascii_colors: vec!["distro".to_string()],
             ascii_bold: true,
-            image_loop: false,
-            thumbnail_dir: dirs::cache_dir().unwrap_or_default().join("neofetch"),
+            thumbnail_dir: Paths::standard().cache_dir,
             crop_mode: CropMode::Normal,
             crop_offset: CropOffset::Center,
             image_size: ImageSize::Auto,
@@ -301,6 +549,8 @@ impl Default for DisplayConfig {
             xoffset: 0,
             background_color: None,
             stdout: false,
+            banner: String::new(),
+            banner_as_logo: false,
         }
     }
 }
@@ -308,13 +558,17 @@ impl Default for DisplayConfig {
 impl Default for FormatConfig {
     fn default() -> Self {
         Self {
+            no_color: false,
+            footer: builtin_brand()
+                .map(|brand| brand.footer.to_string())
+                .unwrap_or_default(),
             color_blocks: true,
             block_range: (0, 15),
             block_width: 3,
             block_height: 1,
             col_offset: "auto".to_string(),
             bar_char_elapsed: "━".to_string(),
-            bar_char_total: "━".to_string(),
+            bar_char_total: "─".to_string(),
             bar_border: true,
             bar_length: 15,
             bar_color_elapsed: "distro".to_string(),
@@ -323,6 +577,7 @@ impl Default for FormatConfig {
             memory_display: DisplayMode::Off,
             battery_display: DisplayMode::Off,
             disk_display: DisplayMode::Off,
+            number_locale: NumberLocale::Auto,
         }
     }
 }
@@ -330,44 +585,408 @@ impl Default for FormatConfig {
 impl Default for BehaviorConfig {
     fn default() -> Self {
         Self {
-            cache_dir: dirs::cache_dir().unwrap_or_default().join("neofetch"),
+            cache_dir: Paths::standard().cache_dir,
             config_file: None,
             stdout: false,
             verbose: false,
+            quiet: false,
+            stats: false,
             json: false,
+            yaml: false,
+            jsonl: false,
+            accessible: false,
+            color_test: false,
+            serve: false,
+            tui: false,
+            no_cache: false,
+            anonymize: false,
+            set_title: false,
+            title_template: crate::notify::DEFAULT_TITLE_TEMPLATE.to_string(),
+            notify_low_disk: None,
+            cache_ttl_secs: 600,
+            package_timeout_ms: 2000,
+            max_concurrent_commands: 4,
+        }
+    }
+}
+
+/// Default `info.order`, matching the original neofetch config
+///
+/// `cols` stands for the color blocks, which always go at the bottom.
+pub const DEFAULT_INFO_ORDER: &[&str] = &[
+    "title",
+    "underline",
+    "os",
+    "host",
+    "kernel",
+    "uptime",
+    "datetime",
+    "install_date",
+    "packages",
+    "shell",
+    "editor",
+    "resolution",
+    "de",
+    "wm",
+    "wm_theme",
+    "theme",
+    "icons",
+    "terminal",
+    "terminal_font",
+    "multiplexer",
+    "cpu",
+    "gpu",
+    "memory",
+    "swap",
+    "disk",
+    "battery",
+    "power_profile",
+    "services",
+    "users",
+    "locale",
+    "weather",
+    "cols",
+];
+
+/// Longest rewrite pattern accepted, in bytes
+const MAX_REWRITE_PATTERN_LEN: usize = 1024;
+
+/// Upper bound on the compiled size of a rewrite pattern
+const MAX_REWRITE_REGEX_SIZE: usize = 1 << 20;
+
+impl RewriteRule {
+    /// Compile the rule's pattern
+    ///
+    /// The regex crate guarantees linear-time matching, so only the size of
+    /// the pattern needs bounding.
+    pub fn compile(&self) -> Result<CompiledRewrite> {
+        if crate::system_info::canonical_field(&self.field).is_none() {
+            bail!("rewrite rule names unknown field '{}'", self.field);
+        }
+
+        if self.pattern.len() > MAX_REWRITE_PATTERN_LEN {
+            bail!(
+                "rewrite pattern for field '{}' is longer than {} bytes",
+                self.field,
+                MAX_REWRITE_PATTERN_LEN
+            );
         }
+
+        let regex = RegexBuilder::new(&self.pattern)
+            .size_limit(MAX_REWRITE_REGEX_SIZE)
+            .build()
+            .with_context(|| {
+                format!(
+                    "invalid rewrite pattern for field '{}': {}",
+                    self.field, self.pattern
+                )
+            })?;
+
+        Ok(CompiledRewrite {
+            field: self.field.clone(),
+            regex,
+            replace: self.replace.clone(),
+        })
+    }
+}
+
+impl InfoConfig {
+    /// Compile all rewrite rules, in order
+    pub fn compile_rewrites(&self) -> Result<Vec<CompiledRewrite>> {
+        self.rewrite.iter().map(RewriteRule::compile).collect()
     }
 }
 
 impl Config {
     /// Load configuration from file and merge with defaults
-    pub fn load() -> Result<Self> {
+    pub fn load(paths: &Paths) -> Result<Self> {
         let mut config = Self::default();
-        
+
         // Try to load user config file
-        if let Some(config_dir) = dirs::config_dir() {
-            let config_path = config_dir.join("neofetch").join("config.toml");
-            if config_path.exists() {
-                let config_str = std::fs::read_to_string(&config_path)?;
-                let user_config: Config = toml::from_str(&config_str)?;
-                config = user_config;
-            }
+        let config_path = paths.config_file();
+        if config_path.exists() {
+            let config_str = std::fs::read_to_string(&config_path)?;
+            let user_config: Config = toml::from_str(&config_str)?;
+            config = user_config;
         }
-        
+
+        config.apply_paths(paths);
         Ok(config)
     }
-    
+
     /// Save configuration to file
-    pub fn save(&self) -> Result<()> {
-        if let Some(config_dir) = dirs::config_dir() {
-            let neofetch_dir = config_dir.join("neofetch");
-            std::fs::create_dir_all(&neofetch_dir)?;
-            
-            let config_path = neofetch_dir.join("config.toml");
-            let config_str = toml::to_string_pretty(self)?;
-            std::fs::write(config_path, config_str)?;
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        std::fs::create_dir_all(&paths.config_dir)?;
+
+        let config_str = toml::to_string_pretty(self)?;
+        std::fs::write(paths.config_file(), config_str)?;
+
+        Ok(())
+    }
+
+    /// Write only `keys`, e.g. `info.order`, into the config file at `path`
+    ///
+    /// Everything else in the file, comments and formatting included, is
+    /// left as it is. Keys that are unset in this config are removed.
+    pub fn save_keys(&self, path: &Path, keys: &[&str]) -> Result<()> {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()))
+            }
+        };
+        let mut document: toml_edit::DocumentMut = source
+            .parse()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let values = toml::Value::try_from(self)?;
+
+        for key in keys {
+            let (section, name) = key
+                .split_once('.')
+                .with_context(|| format!("'{}' is not a section.key name", key))?;
+
+            let Some(value) = values.get(section).and_then(|table| table.get(name)) else {
+                if let Some(table) = document
+                    .get_mut(section)
+                    .and_then(toml_edit::Item::as_table_like_mut)
+                {
+                    table.remove(name);
+                }
+                continue;
+            };
+            let mut value: toml_edit::Value = value.to_string().parse()?;
+
+            let table = document
+                .entry(section)
+                .or_insert_with(toml_edit::table)
+                .as_table_like_mut()
+                .with_context(|| format!("[{}] in {} is not a table", section, path.display()))?;
+            // Keep comments around a value that's being replaced
+            if let Some(existing) = table.get(name).and_then(toml_edit::Item::as_value) {
+                *value.decor_mut() = existing.decor().clone();
+            }
+            table.insert(name, toml_edit::Item::Value(value));
         }
-        
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, document.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Apply a named preset on top of the current settings
+    ///
+    /// Only the keys the preset sets are changed.
+    pub fn apply_preset(&mut self, name: &str) -> Result<()> {
+        let preset = match self.presets.get(name) {
+            Some(preset) => preset.clone(),
+            None => match BUILTIN_PRESETS.iter().find(|(builtin, _)| *builtin == name) {
+                Some((_, source)) => toml::from_str(source)?,
+                None => bail!(
+                    "Unknown preset '{}' (available: {})",
+                    name,
+                    self.preset_names().join(", ")
+                ),
+            },
+        };
+
+        let mut value = toml::Value::try_from(&*self)?;
+        merge_toml(&mut value, toml::Value::Table(preset));
+        *self = value
+            .try_into()
+            .with_context(|| format!("invalid setting in preset '{}'", name))?;
         Ok(())
     }
+
+    /// Names of the built-in and user-defined presets
+    pub fn preset_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = BUILTIN_PRESETS.iter().map(|(name, _)| *name).collect();
+        for name in self.presets.keys() {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Point the cache directories at a portable root
+    ///
+    /// Portable mode must never write outside its root, so it wins over
+    /// directories set in the config file.
+    pub fn apply_paths(&mut self, paths: &Paths) {
+        if paths.portable {
+            self.behavior.cache_dir = paths.cache_dir.clone();
+            self.display.thumbnail_dir = paths.cache_dir.clone();
+        }
+    }
+}
+
+/// Recursively merge `overlay` into `base`; tables merge, other values replace
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config file that sets keys in the sections the brands touch
+    const USER_CONFIG: &str = "[display]\ngap = 5\n[format]\ncolor_blocks = false\n";
+
+    #[test]
+    #[cfg(not(any(feature = "brand-arch", feature = "brand-fedora")))]
+    fn unbranded_builds_have_no_brand_defaults() {
+        assert!(builtin_brand().is_none());
+        let config: Config = toml::from_str(USER_CONFIG).unwrap();
+        assert_eq!(config.display.ascii_distro, None);
+        assert_eq!(config.format.footer, "");
+    }
+
+    #[test]
+    #[cfg(feature = "brand-arch")]
+    fn arch_brand_pins_the_logo_and_footer() {
+        let config: Config = toml::from_str(USER_CONFIG).unwrap();
+        assert_eq!(config.display.ascii_distro.as_deref(), Some("arch"));
+        assert!(config.format.footer.contains("aur.archlinux.org"));
+        assert_eq!(config.display.gap, 5);
+
+        let config: Config = toml::from_str("[display]\nascii_distro = \"debian\"").unwrap();
+        assert_eq!(config.display.ascii_distro.as_deref(), Some("debian"));
+    }
+
+    #[test]
+    #[cfg(feature = "brand-fedora")]
+    fn fedora_brand_pins_the_logo_and_footer() {
+        let config: Config = toml::from_str(USER_CONFIG).unwrap();
+        assert_eq!(config.display.ascii_distro.as_deref(), Some("fedora"));
+        assert!(config.format.footer.contains("bugzilla.redhat.com"));
+        assert!(!config.format.color_blocks);
+
+        let config: Config = toml::from_str("[format]\nfooter = \"\"").unwrap();
+        assert_eq!(config.format.footer, "");
+    }
+
+    fn rule(field: &str, pattern: &str, replace: &str) -> RewriteRule {
+        RewriteRule {
+            field: field.to_string(),
+            pattern: pattern.to_string(),
+            replace: replace.to_string(),
+        }
+    }
+
+    fn rewrites(rules: Vec<RewriteRule>) -> InfoConfig {
+        InfoConfig {
+            rewrite: rules,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builtin_presets_set_the_order() {
+        let mut tiny = Config::default();
+        tiny.apply_preset("tiny").unwrap();
+        assert_eq!(
+            tiny.info.order,
+            ["title", "underline", "os", "kernel", "uptime", "memory"]
+        );
+
+        let mut full = Config::default();
+        full.apply_preset("full").unwrap();
+        for name in DEFAULT_INFO_ORDER {
+            assert!(
+                full.info.order.iter().any(|listed| listed == name),
+                "{}",
+                name
+            );
+        }
+        assert!(full
+            .info
+            .order
+            .iter()
+            .all(|name| crate::output::is_info_item(name) || name == "cols"));
+
+        let mut default = Config::default();
+        default.apply_preset("default").unwrap();
+        assert_eq!(default.info.order, Config::default().info.order);
+
+        let error = Config::default().apply_preset("huge").unwrap_err();
+        assert!(error.to_string().contains("available: default, tiny, full"));
+    }
+
+    #[test]
+    fn rewrites_naming_unknown_fields_are_rejected() {
+        let info = rewrites(vec![
+            rule("cpu", " with Radeon Graphics", ""),
+            rule("gpus", "a", "b"),
+        ]);
+
+        let error = info.compile_rewrites().unwrap_err().to_string();
+        assert!(error.contains("unknown field 'gpus'"), "{}", error);
+    }
+
+    #[test]
+    fn rewrites_accept_field_aliases() {
+        let info = rewrites(vec![
+            rule("distro", "Linux", "GNU/Linux"),
+            rule("term", "a", "b"),
+        ]);
+
+        assert_eq!(info.compile_rewrites().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn invalid_rewrite_patterns_are_rejected() {
+        let info = rewrites(vec![rule("cpu", "(unclosed", "")]);
+        assert!(info.compile_rewrites().is_err());
+
+        let info = rewrites(vec![rule(
+            "cpu",
+            &"a".repeat(MAX_REWRITE_PATTERN_LEN + 1),
+            "",
+        )]);
+        assert!(info.compile_rewrites().is_err());
+    }
+
+    #[test]
+    fn labels_survive_save_and_load() {
+        let root = tempfile::tempdir().unwrap();
+        let paths = Paths::portable(root.path());
+        let mut config = Config::default();
+        for (field, label) in [
+            ("memory", "RAM"),
+            ("de", "Desktop"),
+            ("distro", "System"),
+            ("shell", ""),
+            ("terminal_font", "Font \"mono\" = ok"),
+        ] {
+            config
+                .info
+                .labels
+                .insert(field.to_string(), label.to_string());
+        }
+
+        config.save(&paths).unwrap();
+        let saved = std::fs::read_to_string(paths.config_file()).unwrap();
+        assert!(saved.contains("[info.labels]\n"), "{}", saved);
+        assert!(saved.contains("\nshell = \"\"\n"), "{}", saved);
+
+        let loaded = Config::load(&paths).unwrap();
+        assert_eq!(loaded.info.labels, config.info.labels);
+        assert_eq!(loaded.info.order, config.info.order);
+    }
 }
@@ -0,0 +1,340 @@
+//! Compare a config file against the current defaults
+//!
+//! `neofetch config diff-defaults` uses this after an upgrade to show which
+//! options are new, which ones the user changed and which ones are no longer
+//! recognised.
+
+use crate::config::Config;
+use anyhow::Result;
+use serde::Serialize;
+
+/// Keys that were renamed, as `(old, new)` dotted paths
+const RENAMED_KEYS: &[(&str, &str)] = &[
+    ("display.backend", "display.image_backend"),
+    ("display.source", "display.image_source"),
+    ("info.fields", "info.order"),
+];
+
+/// Sections whose keys are user-defined names rather than options
+const FREEFORM_SECTIONS: &[&str] = &["presets"];
+
+/// Options that are unset by default, which serializing the defaults drops
+const UNSET_BY_DEFAULT: &[&str] = &[
+    "info.show_virt",
+    "display.ascii_distro",
+    "display.background_color",
+    "behavior.config_file",
+    "behavior.notify_low_disk",
+];
+
+/// How a config file differs from the defaults
+#[derive(Debug, Default, Serialize)]
+pub struct DefaultsDiff {
+    /// Options the file doesn't set, e.g. ones added since it was written
+    pub missing: Vec<MissingKey>,
+    /// Options the file sets to something other than the default
+    pub changed: Vec<ChangedKey>,
+    /// Keys neofetch-rs doesn't recognise
+    pub unknown: Vec<UnknownKey>,
+}
+
+/// An option left at its default
+#[derive(Debug, Serialize)]
+pub struct MissingKey {
+    pub key: String,
+    pub default: toml::Value,
+}
+
+/// An option set to a non-default value
+#[derive(Debug, Serialize)]
+pub struct ChangedKey {
+    pub key: String,
+    pub value: toml::Value,
+    /// `None` for options that are unset by default
+    pub default: Option<toml::Value>,
+}
+
+/// A key that is ignored when loading, with its new name if it was renamed
+#[derive(Debug, Serialize)]
+pub struct UnknownKey {
+    pub key: String,
+    pub renamed_to: Option<&'static str>,
+}
+
+/// Every config option with its default, `None` where it's unset
+///
+/// Sorted by key. Freeform sections such as `presets` aren't included.
+pub fn known_keys() -> Result<Vec<(String, Option<toml::Value>)>> {
+    let mut keys: Vec<(String, Option<toml::Value>)> =
+        flatten(&toml::Table::try_from(Config::default())?)
+            .into_iter()
+            .map(|(key, default)| (key, Some(default)))
+            .collect();
+    // Brand builds give some of these a default
+    for key in UNSET_BY_DEFAULT {
+        if !keys.iter().any(|(known, _)| known == key) {
+            keys.push((key.to_string(), None));
+        }
+    }
+    keys.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(keys)
+}
+
+/// Compare the TOML `source` of a config file against `Config::default()`
+pub fn diff_defaults(source: &str) -> Result<DefaultsDiff> {
+    let file = flatten(&toml::from_str(source)?);
+    let known = known_keys()?;
+    let mut diff = DefaultsDiff::default();
+
+    for (key, value) in &file {
+        if FREEFORM_SECTIONS.contains(&key.split('.').next().unwrap_or("")) {
+            continue;
+        }
+        match known.iter().find(|(known_key, _)| known_key == key) {
+            Some((_, Some(default))) if default == value => {}
+            Some((_, default)) => diff.changed.push(ChangedKey {
+                key: key.clone(),
+                value: value.clone(),
+                default: default.clone(),
+            }),
+            None => diff.unknown.push(UnknownKey {
+                renamed_to: RENAMED_KEYS
+                    .iter()
+                    .find(|(old, _)| old == key)
+                    .map(|(_, new)| *new),
+                key: key.clone(),
+            }),
+        }
+    }
+
+    for (key, default) in known {
+        let Some(default) = default else { continue };
+        if !file.iter().any(|(file_key, _)| *file_key == key) {
+            diff.missing.push(MissingKey { key, default });
+        }
+    }
+
+    Ok(diff)
+}
+
+impl DefaultsDiff {
+    /// Human-readable report, one section per category
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+
+        lines.push(format!("New or unset options ({}):", self.missing.len()));
+        for missing in &self.missing {
+            lines.push(format!("  {} = {}", missing.key, missing.default));
+        }
+
+        lines.push(format!("\nChanged from default ({}):", self.changed.len()));
+        for changed in &self.changed {
+            let default = changed
+                .default
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "unset".to_string());
+            lines.push(format!(
+                "  {} = {} (default: {})",
+                changed.key, changed.value, default
+            ));
+        }
+
+        lines.push(format!("\nUnknown keys ({}):", self.unknown.len()));
+        for unknown in &self.unknown {
+            match unknown.renamed_to {
+                Some(new) => lines.push(format!("  {} (renamed to {})", unknown.key, new)),
+                None => lines.push(format!("  {}", unknown.key)),
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Flatten a config table into `section.key` pairs
+///
+/// Only the first level below each section is split out; deeper tables
+/// such as `number_locale = { Locale = "de_DE" }` are compared as values.
+fn flatten(table: &toml::Table) -> Vec<(String, toml::Value)> {
+    let mut keys = Vec::new();
+    for (section, value) in table {
+        match value {
+            toml::Value::Table(section_keys) => {
+                for (key, value) in section_keys {
+                    keys.push((format!("{}.{}", section, key), value.clone()));
+                }
+            }
+            value => keys.push((section.clone(), value.clone())),
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BehaviorConfig, DisplayConfig, FormatConfig, InfoConfig};
+    use serde::de::{self, Deserialize, Visitor};
+
+    /// A deserializer that only records the field names a struct asks for
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> de::Deserializer<'de> for FieldNames<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("fields recorded"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    fn fields<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+        let mut fields: &'static [&'static str] = &[];
+        let _ = T::deserialize(FieldNames(&mut fields));
+        fields
+    }
+
+    #[test]
+    fn known_keys_cover_every_config_field() {
+        assert_eq!(
+            fields::<Config>(),
+            ["info", "display", "format", "behavior", "presets"]
+        );
+        let sections = [
+            ("info", fields::<InfoConfig>()),
+            ("display", fields::<DisplayConfig>()),
+            ("format", fields::<FormatConfig>()),
+            ("behavior", fields::<BehaviorConfig>()),
+        ];
+
+        let mut expected: Vec<String> = sections
+            .iter()
+            .flat_map(|(section, names)| {
+                names
+                    .iter()
+                    .map(move |name| format!("{}.{}", section, name))
+            })
+            .collect();
+        expected.sort();
+        let known: Vec<String> = known_keys()
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(known, expected);
+    }
+
+    #[test]
+    fn unset_options_are_changed_not_unknown() {
+        let diff = diff_defaults(
+            "[info]\nshow_virt = true\n[behavior]\nnotify_low_disk = 90\nbogus = 1\n\
+             [display]\nbackend = \"kitty\"\n[presets.work]\nanything = 1\n",
+        )
+        .unwrap();
+
+        let changed: Vec<(&str, Option<&toml::Value>)> = diff
+            .changed
+            .iter()
+            .map(|changed| (changed.key.as_str(), changed.default.as_ref()))
+            .collect();
+        assert_eq!(
+            changed,
+            [("behavior.notify_low_disk", None), ("info.show_virt", None)]
+        );
+
+        let unknown: Vec<(&str, Option<&str>)> = diff
+            .unknown
+            .iter()
+            .map(|unknown| (unknown.key.as_str(), unknown.renamed_to))
+            .collect();
+        assert_eq!(
+            unknown,
+            [
+                ("behavior.bogus", None),
+                ("display.backend", Some("display.image_backend"))
+            ]
+        );
+        assert!(!diff
+            .missing
+            .iter()
+            .any(|missing| missing.key == "info.show_virt"));
+    }
+
+    #[test]
+    fn old_config_file_is_diffed() {
+        let diff = diff_defaults(include_str!("../tests/fixtures/config/old.toml")).unwrap();
+
+        let changed: Vec<String> = diff
+            .changed
+            .iter()
+            .map(|changed| format!("{} = {}", changed.key, changed.value))
+            .collect();
+        assert_eq!(
+            changed,
+            [
+                "display.gap = 5",
+                "info.cpu_temp = \"C\"",
+                "info.package_managers = \"off\""
+            ]
+        );
+
+        let unknown: Vec<(&str, Option<&str>)> = diff
+            .unknown
+            .iter()
+            .map(|unknown| (unknown.key.as_str(), unknown.renamed_to))
+            .collect();
+        assert_eq!(
+            unknown,
+            [
+                ("behavior.legacy_output", None),
+                ("display.backend", Some("display.image_backend")),
+                ("display.source", Some("display.image_source")),
+                ("info.fields", Some("info.order")),
+            ]
+        );
+
+        // Everything with a default that the file doesn't set, and nothing else
+        let missing: Vec<&str> = diff.missing.iter().map(|key| key.key.as_str()).collect();
+        for key in [
+            "info.order",
+            "info.datetime_format",
+            "behavior.cache_ttl_secs",
+        ] {
+            assert!(missing.contains(&key), "{}", key);
+        }
+        for key in ["info.title_fqdn", "display.gap", "format.block_width"] {
+            assert!(!missing.contains(&key), "{}", key);
+        }
+        let with_defaults = known_keys()
+            .unwrap()
+            .iter()
+            .filter(|(_, default)| default.is_some())
+            .count();
+        assert_eq!(missing.len(), with_defaults - 7);
+
+        let text = diff.to_text();
+        assert!(text.contains("\nChanged from default (3):\n  display.gap = 5 (default: 3)\n"));
+        assert!(text.contains("\n  info.fields (renamed to info.order)"));
+
+        let json: serde_json::Value = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["changed"][0]["default"], 3);
+        assert_eq!(json["unknown"][0]["renamed_to"], serde_json::Value::Null);
+        assert_eq!(json["unknown"][3]["renamed_to"], "info.order");
+    }
+}
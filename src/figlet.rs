@@ -0,0 +1,191 @@
+//! FIGlet font support for neofetch-rs
+//!
+//! This module parses fonts in the FIGlet `.flf` format and renders text as
+//! large banners. Characters are laid out at full width, without smushing.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+
+/// Fonts compiled into the binary, by name
+const BUNDLED: &[(&str, &str)] = &[("segments", include_str!("fonts/segments.flf"))];
+
+/// Name of the font used when `--banner` is given without one
+pub const DEFAULT_FONT: &str = "segments";
+
+/// A parsed FIGlet font
+#[derive(Debug, Clone)]
+pub struct FigFont {
+    height: usize,
+    hardblank: char,
+    glyphs: HashMap<char, Vec<String>>,
+}
+
+impl FigFont {
+    /// Parse a font from the contents of a `.flf` file
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut lines = source.lines();
+        let header = lines.next().context("Empty FIGlet font")?;
+        let mut fields = header.split_whitespace();
+
+        let signature = fields.next().unwrap_or("");
+        let Some(hardblank) = signature
+            .strip_prefix("flf2a")
+            .and_then(|rest| rest.chars().next())
+        else {
+            bail!("Not a FIGlet font: bad header '{}'", header);
+        };
+
+        let numbers: Vec<usize> = fields
+            .map(|field| field.parse::<i64>().unwrap_or(0).max(0) as usize)
+            .collect();
+        let height = numbers.first().copied().unwrap_or(0);
+        if height == 0 {
+            bail!("FIGlet font header has no character height");
+        }
+        let comment_lines = numbers.get(4).copied().unwrap_or(0);
+
+        let mut lines = lines.skip(comment_lines);
+        let mut glyphs = HashMap::new();
+
+        // The required characters come in order, starting at the space
+        for code in 32u8..=126 {
+            match read_glyph(&mut lines, height) {
+                Some(glyph) => {
+                    glyphs.insert(code as char, glyph);
+                }
+                None => break,
+            }
+        }
+
+        // Code-tagged characters follow, each introduced by its code point
+        while let Some(tag) = lines.next() {
+            let Some(glyph) = read_glyph(&mut lines, height) else {
+                break;
+            };
+            if let Some(ch) = parse_code_tag(tag) {
+                glyphs.insert(ch, glyph);
+            }
+        }
+
+        if glyphs.is_empty() {
+            bail!("FIGlet font contains no characters");
+        }
+
+        Ok(Self {
+            height,
+            hardblank,
+            glyphs,
+        })
+    }
+
+    /// Load one of the fonts bundled with neofetch-rs
+    pub fn bundled(name: &str) -> Result<Self> {
+        let Some((_, source)) = BUNDLED.iter().find(|(font, _)| *font == name) else {
+            let available: Vec<&str> = BUNDLED.iter().map(|(font, _)| *font).collect();
+            bail!(
+                "Unknown banner font '{}' (available: {})",
+                name,
+                available.join(", ")
+            );
+        };
+        Self::parse(source)
+    }
+
+    /// Render text as banner lines, with trailing spaces removed
+    ///
+    /// Characters missing from the font render as `?`, or are skipped when
+    /// the font has no `?` either.
+    pub fn render(&self, text: &str) -> Vec<String> {
+        let mut rows = vec![String::new(); self.height];
+
+        for ch in text.chars() {
+            let Some(glyph) = self.glyphs.get(&ch).or_else(|| self.glyphs.get(&'?')) else {
+                continue;
+            };
+            for (row, line) in rows.iter_mut().zip(glyph) {
+                row.push_str(line);
+            }
+        }
+
+        rows.into_iter()
+            .map(|row| row.replace(self.hardblank, " ").trim_end().to_string())
+            .collect()
+    }
+}
+
+/// Read the next `height` lines as one glyph, removing the endmarks
+fn read_glyph<'a>(lines: &mut impl Iterator<Item = &'a str>, height: usize) -> Option<Vec<String>> {
+    let mut glyph = Vec::with_capacity(height);
+    for _ in 0..height {
+        let line = lines.next()?.trim_end();
+        let endmark = line.chars().last()?;
+        glyph.push(line.trim_end_matches(endmark).to_string());
+    }
+
+    // Pad every row to the glyph's width so characters stay aligned
+    let width = glyph
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0);
+    for row in &mut glyph {
+        let padding = width - row.chars().count();
+        row.push_str(&" ".repeat(padding));
+    }
+    Some(glyph)
+}
+
+/// Parse the code point from a code tag line such as `196  LATIN CAPITAL A`
+fn parse_code_tag(tag: &str) -> Option<char> {
+    let code = tag.split_whitespace().next()?;
+    let value = if let Some(hex) = code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()?
+    } else if code.len() > 1 && code.starts_with('0') {
+        u32::from_str_radix(&code[1..], 8).ok()?
+    } else {
+        code.parse().ok()?
+    };
+    char::from_u32(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(font: &FigFont, text: &str) -> String {
+        font.render(text).join("\n") + "\n"
+    }
+
+    #[test]
+    fn bundled_font_renders_like_the_fixture() {
+        let font = FigFont::bundled(DEFAULT_FONT).unwrap();
+        assert_eq!(
+            render(&font, "Neofetch 7.1"),
+            include_str!("../tests/fixtures/figlet/segments.txt")
+        );
+    }
+
+    #[test]
+    fn code_tags_hardblanks_and_missing_characters() {
+        let font = FigFont::parse(include_str!("../tests/fixtures/figlet/tiny.flf")).unwrap();
+        // `€` isn't in the font and shows as `?`
+        assert_eq!(
+            render(&font, "Hi @Ä☃ä€"),
+            include_str!("../tests/fixtures/figlet/tiny.txt")
+        );
+    }
+
+    #[test]
+    fn malformed_fonts_are_rejected() {
+        for (source, error) in [
+            ("", "Empty FIGlet font"),
+            ("tlf2a$ 2 1 4 0 0\n", "bad header"),
+            ("flf2a$ 0 0 4 0 0\n", "no character height"),
+            ("flf2a$ 2 1 4 0 0\nonly one row@\n", "no characters"),
+        ] {
+            let message = FigFont::parse(source).unwrap_err().to_string();
+            assert!(message.contains(error), "{:?}: {}", source, message);
+        }
+        assert!(FigFont::bundled("nope").is_err());
+    }
+}
@@ -1,45 +1,364 @@
 //! Neofetch-rs: A fast, highly customizable system info script written in Rust
-//! 
+//!
 //! This is a Rust rewrite of the popular neofetch system information tool.
 //! It provides detailed system information in a visually appealing format
 //! with ASCII art logos for various operating systems and distributions.
 
-pub mod config;
-pub mod system_info;
+pub mod anonymize;
 pub mod ascii_art;
-pub mod output;
+pub mod cache;
 pub mod cli;
+pub mod config;
+pub mod config_diff;
+pub mod figlet;
+pub mod notify;
+pub mod output;
+pub mod paths;
+pub mod serve;
+pub mod system_info;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod utils;
+pub mod whats_new;
 
 use anyhow::Result;
-use config::Config;
+use config::{CompiledRewrite, Config, ImageBackend};
+use std::io::Write;
+use std::time::Instant;
 use system_info::SystemInfo;
 
 /// Main application structure
 pub struct Neofetch {
     config: Config,
+    rewrites: Vec<CompiledRewrite>,
     system_info: SystemInfo,
+    warnings: Vec<String>,
+    /// `--stats` timings, printed after the warnings
+    stats: Vec<String>,
 }
 
 impl Neofetch {
     /// Create a new Neofetch instance with the given configuration
     pub fn new(config: Config) -> Result<Self> {
+        let rewrites = config.info.compile_rewrites()?;
         let system_info = SystemInfo::new()?;
         Ok(Self {
             config,
+            rewrites,
             system_info,
+            warnings: Vec::new(),
+            stats: Vec::new(),
         })
     }
 
     /// Run the neofetch application
     pub fn run(&mut self) -> Result<()> {
+        if self.config.behavior.color_test {
+            let pattern = output::generate_color_test(utils::get_terminal_width());
+            return self.flush(&pattern);
+        }
+
+        if !matches!(
+            self.config.display.image_backend,
+            ImageBackend::Ascii | ImageBackend::Off
+        ) {
+            self.warn(format!(
+                "image backend '{}' is not supported yet, falling back to ascii",
+                format!("{:?}", self.config.display.image_backend).to_lowercase()
+            ));
+        }
+
+        if self.config.behavior.verbose {
+            let unknown: Vec<String> = self
+                .config
+                .info
+                .order
+                .iter()
+                .filter(|name| match name.strip_prefix("custom:") {
+                    Some(key) => !self.config.info.custom.iter().any(|c| c.key() == key),
+                    None => !output::is_info_item(name),
+                })
+                .cloned()
+                .collect();
+            for name in unknown {
+                self.warn(format!("info.order: unknown item '{}', skipped", name));
+            }
+        }
+
         // Gather system information
+        utils::set_max_concurrent_commands(self.config.behavior.max_concurrent_commands);
+        let started = Instant::now();
         self.system_info.gather_all(&self.config)?;
-        
+        let gathered = started.elapsed();
+        let queued = utils::command_queue_wait();
+        if self.config.behavior.verbose {
+            self.warn(format!(
+                "commands waited {}ms in total for a free slot",
+                queued.as_millis()
+            ));
+            self.warn_skipped_packages();
+        }
+        self.system_info.apply_rewrites(&self.rewrites);
+        if self.config.behavior.anonymize {
+            anonymize::anonymize(
+                &mut self.system_info,
+                &utils::get_username(),
+                &utils::get_hostname(),
+            );
+        }
+
+        if self.config.behavior.serve {
+            let stdin = std::io::stdin().lock();
+            let stdout = std::io::stdout().lock();
+            return serve::Server::new(&mut self.system_info, &self.config, &self.rewrites)
+                .run(stdin, stdout);
+        }
+
+        #[cfg(feature = "tui")]
+        if self.config.behavior.tui {
+            let config_path = self
+                .config
+                .behavior
+                .config_file
+                .clone()
+                .unwrap_or_else(|| paths::Paths::standard().config_file());
+            return tui::run(&self.system_info, self.config.clone(), config_path);
+        }
+
         // Generate and display output
-        let output = output::generate_output(&self.system_info, &self.config)?;
-        println!("{}", output);
-        
+        let rendering = Instant::now();
+        let mut output = output::generate_output(&self.system_info, &self.config)?;
+        output.push('\n');
+        if self.config.behavior.stats {
+            self.stats.push(format!(
+                "gather {}ms, commands queued {}ms in total (max_concurrent_commands = {})",
+                gathered.as_millis(),
+                queued.as_millis(),
+                self.config.behavior.max_concurrent_commands
+            ));
+            self.stats.push(format!(
+                "render {}ms, total {}ms",
+                rendering.elapsed().as_millis(),
+                started.elapsed().as_millis()
+            ));
+        }
+        self.flush(&output)?;
+        self.signal_terminal()?;
+        self.whats_new();
         Ok(())
     }
+
+    /// Mention what's new once after an upgrade
+    ///
+    /// Only runs after the output is written, and only when a person is
+    /// likely to read stderr.
+    fn whats_new(&self) {
+        use std::io::IsTerminal;
+
+        let behavior = &self.config.behavior;
+        if behavior.quiet || behavior.no_cache || !std::io::stderr().is_terminal() {
+            return;
+        }
+        if let Some(notice) = whats_new::check(&behavior.cache_dir) {
+            eprintln!("{}", notice);
+        }
+    }
+
+    /// Set the window title and raise low disk notifications, if enabled
+    ///
+    /// Escape sequences would only garble a pipe or file, so nothing is
+    /// written unless stdout is a terminal.
+    fn signal_terminal(&self) -> Result<()> {
+        use std::io::IsTerminal;
+
+        let behavior = &self.config.behavior;
+        if !std::io::stdout().is_terminal()
+            || (!behavior.set_title && behavior.notify_low_disk.is_none())
+        {
+            return Ok(());
+        }
+
+        let mut sequences = String::new();
+        if behavior.set_title {
+            let title = notify::render_title(&behavior.title_template, &self.system_info);
+            sequences.push_str(&notify::title_sequence(&title));
+        }
+        if let Some(threshold) = behavior.notify_low_disk {
+            let full = notify::full_disks(threshold);
+            if !full.is_empty() {
+                if let Some(sequence) = notify::notify_low_disk(&notify::low_disk_message(&full)) {
+                    sequences.push_str(&sequence);
+                }
+            }
+        }
+
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(sequences.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Queue a warning for each package manager that timed out
+    fn warn_skipped_packages(&mut self) {
+        let timeout = self.config.behavior.package_timeout_ms;
+        for manager in std::mem::take(&mut self.system_info.skipped_package_managers) {
+            self.warn(format!(
+                "skipped {} packages, no answer within {}ms",
+                manager, timeout
+            ));
+        }
+    }
+
+    /// Queue a warning to be printed once the output has been written
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// Write the rendered output in a single call, then print deferred warnings
+    ///
+    /// Warnings go to stderr, which usually shares the terminal with stdout,
+    /// so holding them back keeps them from landing in the middle of the logo.
+    fn flush(&mut self, output: &str) -> Result<()> {
+        self.flush_to(output, &mut std::io::stdout(), &mut std::io::stderr())
+    }
+
+    fn flush_to(
+        &mut self,
+        output: &str,
+        stdout: &mut impl Write,
+        stderr: &mut impl Write,
+    ) -> Result<()> {
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()?;
+
+        if self.config.behavior.quiet {
+            self.warnings.clear();
+        }
+        for warning in self.warnings.drain(..) {
+            writeln!(stderr, "neofetch: warning: {}", warning)?;
+        }
+        // Asked for explicitly, so --quiet doesn't hide them
+        for stat in self.stats.drain(..) {
+            writeln!(stderr, "neofetch: {}", stat)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A stream that records each line written to it, and to which stream
+    struct Recorder {
+        stream: &'static str,
+        log: Rc<RefCell<Vec<(&'static str, String)>>>,
+    }
+
+    impl Write for Recorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let text = String::from_utf8_lossy(buf);
+            let mut log = self.log.borrow_mut();
+            match log.last_mut() {
+                // One line can take several writes
+                Some((stream, line)) if *stream == self.stream && !line.ends_with('\n') => {
+                    line.push_str(&text)
+                }
+                _ => log.push((self.stream, text.into_owned())),
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn neofetch(config: Config) -> Neofetch {
+        Neofetch {
+            config,
+            rewrites: Vec::new(),
+            system_info: SystemInfo::sample().unwrap(),
+            warnings: Vec::new(),
+            stats: Vec::new(),
+        }
+    }
+
+    fn flush(neofetch: &mut Neofetch, output: &str) -> Vec<(&'static str, String)> {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut stdout = Recorder {
+            stream: "stdout",
+            log: log.clone(),
+        };
+        let mut stderr = Recorder {
+            stream: "stderr",
+            log: log.clone(),
+        };
+        neofetch.flush_to(output, &mut stdout, &mut stderr).unwrap();
+        log.take()
+    }
+
+    #[test]
+    fn warnings_are_printed_after_the_output() {
+        let mut neofetch = neofetch(Config::default());
+        neofetch.warn("first");
+        neofetch.warn(String::from("second"));
+
+        assert_eq!(
+            flush(&mut neofetch, "logo and info\n"),
+            [
+                ("stdout", "logo and info\n".to_string()),
+                ("stderr", "neofetch: warning: first\n".to_string()),
+                ("stderr", "neofetch: warning: second\n".to_string()),
+            ]
+        );
+
+        // Each warning is printed once
+        assert_eq!(
+            flush(&mut neofetch, "again\n"),
+            [("stdout", "again\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn skipped_package_managers_are_deferred_warnings() {
+        let mut config = Config::default();
+        config.behavior.package_timeout_ms = 300;
+        let mut neofetch = neofetch(config);
+        neofetch.system_info.skipped_package_managers = vec!["snap"];
+
+        neofetch.warn_skipped_packages();
+        assert!(neofetch.system_info.skipped_package_managers.is_empty());
+        assert_eq!(
+            flush(&mut neofetch, "logo and info\n"),
+            [
+                ("stdout", "logo and info\n".to_string()),
+                (
+                    "stderr",
+                    "neofetch: warning: skipped snap packages, no answer within 300ms\n"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn quiet_drops_warnings_but_not_stats() {
+        let mut config = Config::default();
+        config.behavior.quiet = true;
+        let mut neofetch = neofetch(config);
+        neofetch.warn("unsupported");
+        neofetch.stats.push("render 1ms".to_string());
+
+        assert_eq!(
+            flush(&mut neofetch, "out\n"),
+            [
+                ("stdout", "out\n".to_string()),
+                ("stderr", "neofetch: render 1ms\n".to_string()),
+            ]
+        );
+        assert!(neofetch.warnings.is_empty());
+    }
 }
@@ -1,14 +1,30 @@
 use anyhow::Result;
-use neofetch_rs::{cli, Neofetch};
+use neofetch_rs::{cli, utils, Neofetch};
 
 fn main() -> Result<()> {
     // Parse command-line arguments
-    let config = cli::parse_args()?;
+    let mut config = cli::parse_args()?;
+
+    // NO_COLOR (https://no-color.org) counts when set to anything non-empty
+    if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        config.format.no_color = true;
+    }
+
+    // Screen readers would read escape codes out loud
+    if config.format.no_color || config.behavior.accessible {
+        colored::control::set_override(false);
+    }
+
+    // Consoles that can't interpret escape codes get plain output
+    if !utils::enable_ansi_support() {
+        colored::control::set_override(false);
+        config.format.color_blocks = false;
+    }
 
     // Handle special cases
     if config.behavior.verbose {
-        println!("Neofetch-rs v7.1.0");
-        println!("Configuration loaded successfully");
+        eprintln!("Neofetch-rs v{}", env!("CARGO_PKG_VERSION"));
+        eprintln!("Configuration loaded successfully");
     }
 
     // Create and run neofetch
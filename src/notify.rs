@@ -0,0 +1,292 @@
+//! Terminal title and notification escape sequences
+//!
+//! `--set-title` and `--notify-low-disk` talk to the terminal itself rather
+//! than printing info, so they only do anything when stdout is a terminal.
+
+use crate::system_info::SystemInfo;
+
+/// Default template for `--set-title`, e.g. `laptop · up 3d · 47% mem`
+pub const DEFAULT_TITLE_TEMPLATE: &str = "{hostname} · up {uptime_short} · {memory_percent} mem";
+
+/// OSC 2 sequence setting the terminal window title
+pub fn title_sequence(title: &str) -> String {
+    format!("\x1b]2;{}\x07", strip_control(title))
+}
+
+/// OSC 9 sequence showing a desktop notification (iTerm2, kitty, WezTerm...)
+pub fn notification_sequence(message: &str) -> String {
+    format!("\x1b]9;{}\x07", strip_control(message))
+}
+
+/// Render the window title from a template
+///
+/// Besides the field placeholders there are compact ones for the small
+/// space of a title bar: `{hostname}` (the title without the user),
+/// `{uptime_short}` (the largest unit, e.g. `3d`) and `{memory_percent}`.
+pub fn render_title(template: &str, system_info: &SystemInfo) -> String {
+    let title = &system_info.title;
+    let hostname = title
+        .split_once('@')
+        .map_or(title.as_str(), |(_, host)| host);
+    let uptime = system_info
+        .uptime_seconds
+        .map(compact_uptime)
+        .unwrap_or_else(|| "Unknown".to_string());
+    let memory = match system_info.memory_total_bytes {
+        0 => "Unknown".to_string(),
+        total => format!(
+            "{:.0}%",
+            system_info.memory_used_bytes as f64 / total as f64 * 100.0
+        ),
+    };
+
+    crate::output::expand_placeholders_with(template, |name| match name {
+        "hostname" => Some(hostname),
+        "uptime_short" => Some(&uptime),
+        "memory_percent" => Some(&memory),
+        name => system_info.get_field(name),
+    })
+}
+
+/// The largest unit of an uptime, e.g. `3d`, `4h` or `12m`
+fn compact_uptime(seconds: u64) -> String {
+    match seconds {
+        0..=3599 => format!("{}m", seconds / 60),
+        3600..=86399 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+/// File systems that are full by design or don't hold user data, such as
+/// snaps (squashfs) and loop-mounted ISOs (iso9660)
+const IGNORED_FILE_SYSTEMS: &[&str] = &[
+    "squashfs", "iso9660", "udf", "erofs", "cramfs", "romfs", "tmpfs", "devtmpfs", "ramfs",
+    "devfs", "proc", "sysfs", "efivarfs", "autofs",
+];
+
+/// A mounted file system as far as low disk warnings care
+struct Mount {
+    point: String,
+    file_system: String,
+    used: u64,
+    total: u64,
+}
+
+/// Mount points filled beyond `threshold` percent, with their usage
+///
+/// Read-only and pseudo file systems are skipped: nothing can fill them
+/// further, and a mounted ISO is always 100% full.
+pub fn full_disks(threshold: u8) -> Vec<(String, u8)> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let mounts: Vec<Mount> = disks
+        .list()
+        .iter()
+        .map(|disk| Mount {
+            point: disk.mount_point().display().to_string(),
+            file_system: disk.file_system().to_string_lossy().into_owned(),
+            used: disk.total_space().saturating_sub(disk.available_space()),
+            total: disk.total_space(),
+        })
+        .collect();
+    let read_only = read_only_mounts(&std::fs::read_to_string("/proc/mounts").unwrap_or_default());
+
+    pick_full_disks(&mounts, &read_only, threshold)
+}
+
+/// The mounts from `full_disks` that are writable and beyond `threshold`
+fn pick_full_disks(mounts: &[Mount], read_only: &[String], threshold: u8) -> Vec<(String, u8)> {
+    let mut full: Vec<(String, u8)> = Vec::new();
+
+    for mount in mounts {
+        if mount.total == 0
+            || IGNORED_FILE_SYSTEMS.contains(&mount.file_system.as_str())
+            || read_only.contains(&mount.point)
+        {
+            continue;
+        }
+        let percent = (mount.used as f64 / mount.total as f64 * 100.0).round() as u8;
+        if percent > threshold && !full.iter().any(|(seen, _)| *seen == mount.point) {
+            full.push((mount.point.clone(), percent));
+        }
+    }
+
+    full
+}
+
+/// Mount points mounted read-only, from `/proc/mounts`
+///
+/// Spaces and other special characters in mount points are octal escapes
+/// there, e.g. `/media/My\040Disk`.
+fn read_only_mounts(mounts: &str) -> Vec<String> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let point = fields.nth(1)?;
+            let options = fields.nth(1)?;
+            options
+                .split(',')
+                .any(|option| option == "ro")
+                .then(|| unescape_mount_point(point))
+        })
+        .collect()
+}
+
+/// Undo the `\ooo` octal escapes of `/proc/mounts`
+fn unescape_mount_point(point: &str) -> String {
+    let mut bytes = Vec::with_capacity(point.len());
+    let mut rest = point.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let octal = tail
+            .get(..3)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match octal {
+            Some(escaped) if byte == b'\\' => {
+                bytes.push(escaped);
+                rest = &tail[3..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Message for a low disk space notification, e.g. `/home is 93% full`
+pub fn low_disk_message(disks: &[(String, u8)]) -> String {
+    disks
+        .iter()
+        .map(|(mount, percent)| format!("{} is {}% full", mount, percent))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Send a low disk space notification
+///
+/// In a graphical session with `notify-send` a desktop notification is
+/// sent; otherwise the OSC 9 sequence is returned for the caller to write.
+pub fn notify_low_disk(message: &str) -> Option<String> {
+    let graphical =
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some();
+    if graphical && crate::utils::command_exists("notify-send") {
+        let sent = crate::utils::output_with_timeout(
+            std::process::Command::new("notify-send").args(["Low disk space", message]),
+            crate::utils::COMMAND_TIMEOUT,
+        );
+        if sent.map(|output| output.status.success()).unwrap_or(false) {
+            return None;
+        }
+    }
+    Some(notification_sequence(message))
+}
+
+/// Drop control characters, which would end the sequence early
+fn strip_control(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mount(point: &str, file_system: &str, used: u64, total: u64) -> Mount {
+        Mount {
+            point: point.to_string(),
+            file_system: file_system.to_string(),
+            used,
+            total,
+        }
+    }
+
+    #[test]
+    fn sequences_are_byte_exact() {
+        assert_eq!(
+            title_sequence("laptop · up 3d · 46% mem").as_bytes(),
+            b"\x1b]2;laptop \xc2\xb7 up 3d \xc2\xb7 46% mem\x07"
+        );
+        assert_eq!(
+            notification_sequence("/home is 93% full").as_bytes(),
+            b"\x1b]9;/home is 93% full\x07"
+        );
+        // A BEL or ESC inside the text would end the sequence early
+        assert_eq!(
+            title_sequence("evil\x07\x1b]0;pwned\n"),
+            "\x1b]2;evil]0;pwned\x07"
+        );
+        assert_eq!(title_sequence(""), "\x1b]2;\x07");
+    }
+
+    #[test]
+    fn title_uses_the_placeholders() {
+        let mut system_info = SystemInfo::sample().unwrap();
+        assert_eq!(
+            title_sequence(&render_title(DEFAULT_TITLE_TEMPLATE, &system_info)),
+            "\x1b]2;laptop · up 3d · 47% mem\x07"
+        );
+        assert_eq!(
+            render_title("{title} · up {uptime} · {memory}", &system_info),
+            "user@laptop · up 3 days, 4 hours, 12 mins · 7421MiB / 15887MiB"
+        );
+
+        system_info.uptime_seconds = None;
+        system_info.memory_total_bytes = 0;
+        assert_eq!(
+            render_title(DEFAULT_TITLE_TEMPLATE, &system_info),
+            "laptop · up Unknown · Unknown mem"
+        );
+    }
+
+    #[test]
+    fn uptime_in_its_largest_unit() {
+        for (seconds, expected) in [
+            (0, "0m"),
+            (59, "0m"),
+            (12 * 60, "12m"),
+            (3599, "59m"),
+            (3600, "1h"),
+            (23 * 3600 + 59 * 60, "23h"),
+            (86400, "1d"),
+            (((3 * 24 + 4) * 60 + 12) * 60, "3d"),
+            (400 * 86400, "400d"),
+        ] {
+            assert_eq!(compact_uptime(seconds), expected, "{}", seconds);
+        }
+    }
+
+    #[test]
+    fn read_only_and_pseudo_file_systems_are_never_full() {
+        let mounts = [
+            mount("/", "ext4", 95, 100),
+            mount("/home", "btrfs", 50, 100),
+            mount("/snap/core22/1380", "squashfs", 77, 77),
+            mount("/media/Ubuntu 24.04", "iso9660", 6, 6),
+            mount("/run/user/1000", "tmpfs", 9, 10),
+            mount("/boot/efi", "vfat", 99, 100),
+            mount("/mnt/backup", "ext4", 100, 100),
+            mount("/mnt/empty", "ext4", 0, 0),
+            mount("/", "ext4", 95, 100),
+        ];
+        let read_only = read_only_mounts(
+            "/dev/sda1 / ext4 rw,relatime 0 0\n\
+             /dev/sda3 /mnt/backup ext4 ro,relatime 0 0\n\
+             /dev/sr0 /media/Ubuntu\\04024.04 iso9660 ro,nosuid 0 0\n\
+             garbage\n",
+        );
+
+        assert_eq!(read_only, ["/mnt/backup", "/media/Ubuntu 24.04"]);
+        assert_eq!(
+            pick_full_disks(&mounts, &read_only, 90),
+            [("/".to_string(), 95), ("/boot/efi".to_string(), 99)]
+        );
+        assert_eq!(pick_full_disks(&mounts, &read_only, 99), []);
+    }
+
+    #[test]
+    fn low_disk_message_lists_every_disk() {
+        let disks = [("/".to_string(), 95), ("/home".to_string(), 91)];
+        assert_eq!(low_disk_message(&disks), "/ is 95% full, /home is 91% full");
+    }
+}
@@ -3,290 +3,576 @@
 //! This module handles the formatting and display of system information alongside ASCII art.
 
 use crate::ascii_art::AsciiArt;
-use crate::config::Config;
-use crate::system_info::SystemInfo;
-use crate::utils;
+use crate::config::{Config, DisplayMode, FormatConfig, ImageSource, NumberLocale};
+use crate::figlet::FigFont;
+use crate::system_info::{canonical_field, SystemInfo};
+use crate::utils::{self, NumberFormat};
 use anyhow::Result;
 use colored::*;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Information item structure
 #[derive(Debug, Clone)]
 pub struct InfoItem {
+    /// Name from `info.order`, e.g. `memory` or `custom:<name>`
+    pub field: String,
     pub label: String,
     pub value: String,
     pub show: bool,
 }
 
+impl InfoItem {
+    /// Whether this is the title or its underline, which have no label
+    fn is_heading(&self) -> bool {
+        matches!(self.field.as_str(), "title" | "underline")
+    }
+}
+
 /// Generate the complete output combining ASCII art and system information
 pub fn generate_output(system_info: &SystemInfo, config: &Config) -> Result<String> {
-    let ascii_art = AsciiArt::new();
-    let info_items = get_info_items(system_info, config);
-
-    // Get the OS name for ASCII art selection
-    let os_name = system_info.get_field("os").unwrap_or("linux");
+    // Structured output needs neither the logo nor the info items
+    if config.behavior.json {
+        return generate_json_output(system_info);
+    }
 
-    // Get ASCII logo
-    let default_logo = vec!["".to_string()];
-    let logo = ascii_art.get_logo(os_name).unwrap_or(&default_logo);
-    let colored_logo = ascii_art.colorize_logo(os_name, logo);
+    if config.behavior.yaml {
+        return generate_yaml_output(system_info);
+    }
 
-    // Calculate dimensions
-    let logo_width = ascii_art.get_logo_width(os_name);
-    let logo_height = colored_logo.len();
+    if config.behavior.jsonl {
+        return generate_jsonl_output(system_info, config);
+    }
 
-    // Generate output
-    let mut output = String::new();
+    let info_items = get_info_items(system_info, config);
 
-    if config.behavior.json {
-        return generate_json_output(system_info);
+    if config.behavior.accessible {
+        return Ok(generate_accessible_output(&info_items, system_info));
     }
 
-    if config.display.stdout {
-        return generate_stdout_output(&info_items, system_info, config);
+    // Without a known terminal size, side-by-side layout can only go wrong
+    match utils::detect_terminal_width() {
+        Some(width) if !config.display.stdout => {
+            generate_layout_output(&info_items, system_info, config, width)
+        }
+        _ => generate_stdout_output(&info_items, system_info, config),
     }
+}
+
+/// Render the logo and info side by side for a terminal `terminal_width` wide
+///
+/// This is the normal output without the terminal size detection, e.g. for
+/// benchmarks.
+pub fn render_layout(
+    system_info: &SystemInfo,
+    config: &Config,
+    terminal_width: usize,
+) -> Result<String> {
+    let info_items = get_info_items(system_info, config);
+    generate_layout_output(&info_items, system_info, config, terminal_width)
+}
+
+/// Render the logo and info side by side
+fn generate_layout_output(
+    info_items: &[InfoItem],
+    system_info: &SystemInfo,
+    config: &Config,
+    terminal_width: usize,
+) -> Result<String> {
+    let ascii_art = AsciiArt::shared();
+
+    // Get the OS name for ASCII art selection
+    let os_name = match &config.display.ascii_distro {
+        Some(distro) => distro.as_str(),
+        None => ascii_art.resolve_logo_name(
+            &system_info.distro_id,
+            &system_info.distro_id_like,
+            system_info.get_field("os").unwrap_or("linux"),
+        ),
+    };
+
+    // Render the title as a banner, cut to fit the terminal
+    let banner = if config.display.banner.is_empty() {
+        None
+    } else {
+        let font = FigFont::bundled(&config.display.banner)?;
+        let title = system_info.get_field("title").unwrap_or("");
+        Some(
+            font.render(title)
+                .into_iter()
+                .map(|line| line.chars().take(terminal_width).collect::<String>())
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    // Get ASCII logo, preferring a banner or a user supplied file
+    let custom_logo = match &config.display.image_source {
+        _ if config.display.banner_as_logo => banner.clone(),
+        ImageSource::Path(path) => Some(AsciiArt::load_logo_file(path)?),
+        _ => None,
+    };
+    let default_logo = vec!["".to_string()];
+    let logo = custom_logo
+        .as_ref()
+        .or_else(|| ascii_art.get_logo(os_name))
+        .unwrap_or(&default_logo);
+    let colored_logo = ascii_art.colorize_logo(os_name, logo, &config.display.ascii_colors);
+
+    // Measure each logo line once, on the uncolored art
+    let logo_line_widths: Vec<usize> = logo
+        .iter()
+        .map(|line| strip_ansi_for_length(line))
+        .collect();
+    let logo_width = logo_line_widths.iter().copied().max().unwrap_or(0);
+    let logo_height = colored_logo.len();
 
     // Filter out items that shouldn't be shown
     let visible_items: Vec<&InfoItem> = info_items.iter().filter(|item| item.show).collect();
 
+    // Pad labels to the longest one so the separators line up
+    let label_width = visible_items
+        .iter()
+        .map(|item| strip_ansi_for_length(&item.label))
+        .max()
+        .unwrap_or(0);
+
     // Calculate available width for info text
-    let terminal_width = utils::get_terminal_width();
-    let ascii_and_gap_width = logo_width + config.display.gap as usize;
-    let available_info_width = if terminal_width > ascii_and_gap_width + 10 {
-        terminal_width - ascii_and_gap_width - 5 // Small margin for safety
+    let gap = config.display.gap.max(0) as usize;
+    let ascii_and_gap_width = logo_width + gap;
+    let available_info_width = if terminal_width > ascii_and_gap_width.saturating_add(10) {
+        terminal_width.saturating_sub(ascii_and_gap_width + 5) // Small margin for safety
     } else {
         40 // Fallback minimum
     };
 
-    // Combine ASCII art with system information
+    // Combine ASCII art with system information into a single buffer,
+    // sized for the widest possible line plus room for escape codes
     let max_lines = std::cmp::max(logo_height, visible_items.len());
-    let mut info_index = 0;
+    let mut output = String::with_capacity(max_lines * (terminal_width.max(80) + 64));
+
+    if let Some(banner) = banner.filter(|_| !config.display.banner_as_logo) {
+        for line in ascii_art.colorize_logo(os_name, &banner, &config.display.ascii_colors) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
 
     for i in 0..max_lines {
-        let mut line = String::new();
-
-        // Add ASCII art line
-        if i < logo_height {
-            line.push_str(&colored_logo[i]);
-            // Pad to consistent width
-            let actual_width = ascii_art.strip_ansi_codes(&colored_logo[i]).chars().count();
-            if actual_width < logo_width {
-                line.push_str(&" ".repeat(logo_width - actual_width));
-            }
+        // Add ASCII art line, padded to a consistent width
+        if let Some((logo_line, line_width)) = colored_logo.get(i).zip(logo_line_widths.get(i)) {
+            output.push_str(logo_line);
+            push_spaces(&mut output, logo_width - line_width);
         } else {
-            // Add padding to maintain alignment
-            line.push_str(&" ".repeat(logo_width));
+            push_spaces(&mut output, logo_width);
         }
 
         // Add gap between ASCII art and info
-        line.push_str(&" ".repeat(config.display.gap as usize));
+        push_spaces(&mut output, gap);
 
         // Add system information line
-        if info_index < visible_items.len() {
-            let info_item = visible_items[info_index];
-            let formatted_info = if info_item.label.is_empty() {
+        if let Some(info_item) = visible_items.get(i) {
+            if info_item.is_heading() {
                 // Special cases like title, underline, colors
-                format_special_item_with_width(info_item, config, available_info_width)
+                push_special_item(&mut output, info_item, config, available_info_width);
             } else {
-                format_info_item_with_width(info_item, config, available_info_width)
-            };
-            line.push_str(&formatted_info);
-            info_index += 1;
+                push_info_item(
+                    &mut output,
+                    info_item,
+                    config,
+                    label_width,
+                    available_info_width,
+                );
+            }
         }
 
-        output.push_str(&line);
         output.push('\n');
     }
 
     // Add color blocks at the bottom if enabled
-    if config.format.color_blocks {
+    if show_color_blocks(config) {
         let colors = system_info.get_field("colors").unwrap_or("");
-        if !colors.is_empty() {
-            let color_lines: Vec<&str> = colors.split('\n').collect();
-            for color_line in color_lines {
-                if !color_line.is_empty() {
-                    // Add padding to align with the info section
-                    output.push_str(&" ".repeat(logo_width + config.display.gap as usize));
-                    output.push_str(color_line);
-                    output.push('\n');
-                }
-            }
+        for color_line in colors.split('\n').filter(|line| !line.is_empty()) {
+            // Add padding to align with the info section
+            push_spaces(&mut output, ascii_and_gap_width);
+            output.push_str(color_line);
+            output.push('\n');
         }
     }
 
+    if !config.format.footer.is_empty() {
+        output.push('\n');
+        push_spaces(&mut output, ascii_and_gap_width);
+        output.push_str(&config.format.footer);
+        output.push('\n');
+    }
+
     Ok(output)
 }
 
-/// Get the list of information items to display
-fn get_info_items(system_info: &SystemInfo, config: &Config) -> Vec<InfoItem> {
-    let mut items = Vec::new();
-
-    // Default info items (matching the original neofetch config)
-    items.push(InfoItem {
-        label: "".to_string(),
-        value: system_info.get_field("title").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "".to_string(),
-        value: generate_underline(system_info.get_field("title").unwrap_or(""), config),
-        show: config.info.underline_enabled,
-    });
-
-    items.push(InfoItem {
-        label: "OS".to_string(),
-        value: system_info.get_field("os").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Host".to_string(),
-        value: system_info.get_field("host").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Kernel".to_string(),
-        value: system_info.get_field("kernel").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Uptime".to_string(),
-        value: system_info.get_field("uptime").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Packages".to_string(),
-        value: system_info.get_field("packages").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Shell".to_string(),
-        value: system_info.get_field("shell").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Resolution".to_string(),
-        value: system_info
-            .get_field("resolution")
-            .unwrap_or("")
-            .to_string(),
-        show: !system_info.get_field("resolution").unwrap_or("").is_empty()
-            && system_info.get_field("resolution").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "DE".to_string(),
-        value: system_info.get_field("de").unwrap_or("").to_string(),
-        show: !system_info.get_field("de").unwrap_or("").is_empty()
-            && system_info.get_field("de").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "WM".to_string(),
-        value: system_info.get_field("wm").unwrap_or("").to_string(),
-        show: !system_info.get_field("wm").unwrap_or("").is_empty()
-            && system_info.get_field("wm").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "WM Theme".to_string(),
-        value: system_info.get_field("wm_theme").unwrap_or("").to_string(),
-        show: !system_info.get_field("wm_theme").unwrap_or("").is_empty()
-            && system_info.get_field("wm_theme").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "Theme".to_string(),
-        value: system_info.get_field("theme").unwrap_or("").to_string(),
-        show: !system_info.get_field("theme").unwrap_or("").is_empty()
-            && system_info.get_field("theme").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "Icons".to_string(),
-        value: system_info.get_field("icons").unwrap_or("").to_string(),
-        show: !system_info.get_field("icons").unwrap_or("").is_empty()
-            && system_info.get_field("icons").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "Terminal".to_string(),
-        value: system_info.get_field("terminal").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "Terminal Font".to_string(),
-        value: system_info
-            .get_field("terminal_font")
-            .unwrap_or("")
-            .to_string(),
-        show: !system_info
-            .get_field("terminal_font")
-            .unwrap_or("")
-            .is_empty()
-            && system_info.get_field("terminal_font").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "CPU".to_string(),
-        value: system_info.get_field("cpu").unwrap_or("").to_string(),
-        show: true,
-    });
-
-    items.push(InfoItem {
-        label: "GPU".to_string(),
-        value: system_info.get_field("gpu").unwrap_or("").to_string(),
-        show: !system_info.get_field("gpu").unwrap_or("").is_empty()
-            && system_info.get_field("gpu").unwrap_or("") != "Unknown",
-    });
-
-    items.push(InfoItem {
-        label: "Memory".to_string(),
-        value: system_info.get_field("memory").unwrap_or("").to_string(),
-        show: true,
-    });
+/// Append `count` spaces without allocating an intermediate string
+fn push_spaces(output: &mut String, count: usize) {
+    for _ in 0..count {
+        output.push(' ');
+    }
+}
+
+/// Get the list of information items to display, in `info.order`
+pub fn get_info_items(system_info: &SystemInfo, config: &Config) -> Vec<InfoItem> {
+    let numbers = number_format(system_info, config);
+
+    let mut items: Vec<InfoItem> = config
+        .info
+        .order
+        .iter()
+        .filter_map(|name| match name.strip_prefix("custom:") {
+            Some(key) => custom_item(key, system_info, config),
+            None => info_item(name, system_info, config, &numbers),
+        })
+        .collect();
+
+    // Custom lines left out of the order go after the rest
+    for custom in &config.info.custom {
+        let listed = config
+            .info
+            .order
+            .iter()
+            .any(|name| name.strip_prefix("custom:") == Some(custom.key()));
+        if !listed {
+            items.extend(custom_item(custom.key(), system_info, config));
+        }
+    }
 
     items
 }
 
-/// Format a regular information item with specific width
-fn format_info_item_with_width(item: &InfoItem, config: &Config, max_width: usize) -> String {
-    if item.value.is_empty() || item.value == "Unknown" {
-        return String::new();
+/// Whether `name` can be listed in `info.order`
+pub fn is_info_item(name: &str) -> bool {
+    matches!(name, "title" | "underline") || canonical_field(name).is_some()
+}
+
+/// Whether to print the color blocks below the info
+///
+/// They're listed in `info.order` as `cols` but always go at the bottom.
+fn show_color_blocks(config: &Config) -> bool {
+    config.format.color_blocks
+        && config
+            .info
+            .order
+            .iter()
+            .any(|name| canonical_field(name) == Some("colors"))
+}
+
+/// Build the info line for an `info.custom` entry by key
+///
+/// Entries whose command failed are hidden.
+fn custom_item(key: &str, system_info: &SystemInfo, config: &Config) -> Option<InfoItem> {
+    let custom = config
+        .info
+        .custom
+        .iter()
+        .find(|custom| custom.key() == key)?;
+    let value = system_info
+        .custom
+        .iter()
+        .find(|(custom_key, _)| custom_key == key)
+        .map(|(_, value)| value.clone());
+    Some(InfoItem {
+        field: format!("custom:{}", key),
+        label: custom.label.clone(),
+        show: value.is_some(),
+        value: value.unwrap_or_default(),
+    })
+}
+
+/// Build the info line for a name from `info.order`
+///
+/// Unknown names give `None`, and so does `cols`: the color blocks are
+/// printed separately.
+fn info_item(
+    name: &str,
+    system_info: &SystemInfo,
+    config: &Config,
+    numbers: &NumberFormat,
+) -> Option<InfoItem> {
+    let title = system_info.get_field("title").unwrap_or("");
+    match name {
+        "title" => {
+            return Some(InfoItem {
+                field: "title".to_string(),
+                label: "".to_string(),
+                value: title.to_string(),
+                show: true,
+            })
+        }
+        "underline" => {
+            return Some(InfoItem {
+                field: "underline".to_string(),
+                label: "".to_string(),
+                value: generate_underline(title, config),
+                show: config.info.underline_enabled,
+            })
+        }
+        _ => {}
     }
 
-    // Apply colors like original neofetch
-    let colored_label = if config.info.bold {
-        item.label.bold().cyan().to_string()
-    } else {
-        item.label.cyan().to_string()
+    let field = canonical_field(name).filter(|field| *field != "colors")?;
+    let value = system_info.get_field(field).unwrap_or("").to_string();
+    let known = !value.is_empty() && value != "Unknown";
+
+    let (value, show) = match field {
+        // Always shown, like the original neofetch
+        "os" | "kernel" | "uptime" | "shell" | "terminal" => (value, true),
+        "packages" => (numbers.localize(&value), true),
+        "cpu" => (
+            with_bar(
+                value,
+                system_info.cpu_usage.map(f64::from),
+                &config.format.cpu_display,
+                config,
+            ),
+            true,
+        ),
+        "memory" => (
+            with_bar(
+                numbers.localize(&value),
+                percent_of(
+                    system_info.memory_used_bytes,
+                    system_info.memory_total_bytes,
+                ),
+                &config.format.memory_display,
+                config,
+            ),
+            true,
+        ),
+        "swap" => (numbers.localize(&value), known),
+        "disk" => (
+            with_bar(
+                numbers.localize(&value),
+                percent_of(system_info.disk_used_bytes, system_info.disk_total_bytes),
+                &config.format.disk_display,
+                config,
+            ),
+            config.info.disk && known,
+        ),
+        "battery" => (
+            with_bar(
+                value,
+                system_info.battery_percent.map(f64::from),
+                &config.format.battery_display,
+                config,
+            ),
+            known,
+        ),
+        // Hidden when it has already been appended to the battery row
+        "power_profile" => (
+            value,
+            known
+                && !(config.info.battery_show_profile
+                    && system_info.get_field("battery").unwrap_or("") != "Unknown"),
+        ),
+        "services" => {
+            let failed = system_info.services_failed && system_info.services_count > Some(0);
+            (
+                if failed {
+                    value.red().to_string()
+                } else {
+                    value
+                },
+                known,
+            )
+        }
+        // Only worth showing when someone besides us is logged in
+        "users" => {
+            let others = !only_current_user(&value);
+            (value, known && others)
+        }
+        _ => (value, known),
     };
 
-    let colored_separator = config.info.separator.white().to_string();
-    let colored_value = item.value.white().to_string();
+    // Labels may be keyed by an alias, e.g. `distro`
+    let label = config
+        .info
+        .labels
+        .iter()
+        .find(|(name, _)| canonical_field(name) == Some(field))
+        .map(|(_, label)| label.clone())
+        .unwrap_or_else(|| field_label(field));
 
-    let formatted = format!("{}{} {}", colored_label, colored_separator, colored_value);
+    Some(InfoItem {
+        field: field.to_string(),
+        label,
+        value,
+        show,
+    })
+}
 
-    // Truncate if too long to prevent wrapping
-    truncate_text(&formatted, max_width)
+/// Whether every entry of a users list, sessions aside, is us
+fn only_current_user(users: &str) -> bool {
+    let username = whoami::username();
+    users
+        .split(", ")
+        .all(|entry| entry.split(" (").next() == Some(username.as_str()))
+}
+
+/// Share of `total` that is `used`, in percent
+fn percent_of(used: u64, total: u64) -> Option<f64> {
+    (total > 0).then(|| used as f64 / total as f64 * 100.0)
 }
 
-/// Truncate text to fit within specified width (accounting for ANSI escape codes)
-fn truncate_text(text: &str, max_width: usize) -> String {
-    // Calculate visible length (excluding ANSI escape codes)
-    let visible_len = strip_ansi_for_length(text);
+/// Combine a value with its usage bar according to the display mode
+///
+/// Without a known percentage the value is left alone. Accessible output
+/// gets the percentage in words instead of a bar, and `--stdout` gets a
+/// bar without colors.
+fn with_bar(value: String, percent: Option<f64>, mode: &DisplayMode, config: &Config) -> String {
+    let percent = match percent {
+        Some(percent) if value != "Unknown" => percent,
+        _ => return value,
+    };
+    let format = &config.format;
+    let colored = !(config.display.stdout || config.behavior.accessible || format.no_color);
+    let render_bar = |percent| render_bar(percent, format, colored);
+    match mode {
+        DisplayMode::Off => value,
+        _ if config.behavior.accessible => {
+            let used = format!("{:.0}% used", percent);
+            match mode {
+                DisplayMode::Bar => used,
+                _ if value.contains('%') => value,
+                _ => format!("{} ({})", value, used),
+            }
+        }
+        DisplayMode::Bar => render_bar(percent),
+        DisplayMode::Infobar => format!("{} {}", value, render_bar(percent)),
+        DisplayMode::Barinfo => format!("{} {}", render_bar(percent), value),
+    }
+}
 
-    if visible_len <= max_width {
-        text.to_string()
+/// Render a percentage as a neofetch bar, e.g. `[━━━━━━━━━━─────]`
+///
+/// The bar colors are only applied with `colored`.
+pub fn render_bar(percent: f64, format: &FormatConfig, colored: bool) -> String {
+    let length = format.bar_length as usize;
+    let elapsed = ((percent.clamp(0.0, 100.0) / 100.0) * length as f64).round() as usize;
+    let part = |bar_char: &str, count: usize, color: &str| {
+        let part = bar_char.repeat(count);
+        if colored {
+            color_bar_part(&part, color)
+        } else {
+            part
+        }
+    };
+
+    let bar = format!(
+        "{}{}",
+        part(&format.bar_char_elapsed, elapsed, &format.bar_color_elapsed),
+        part(
+            &format.bar_char_total,
+            length - elapsed,
+            &format.bar_color_total
+        )
+    );
+    if format.bar_border {
+        format!("[{}]", bar)
     } else {
-        // Truncate while preserving ANSI codes
-        truncate_with_ansi(text, max_width)
+        bar
+    }
+}
+
+/// Color part of a bar: `distro` matches the labels, `fg` leaves it alone
+fn color_bar_part(part: &str, color: &str) -> String {
+    if part.is_empty() {
+        return String::new();
+    }
+    let color = match color {
+        "distro" => Color::Cyan,
+        "fg" => return part.to_string(),
+        _ => match color.parse::<u8>() {
+            Ok(index) => match ansi_color(index) {
+                Some(color) => color,
+                None => return part.to_string(),
+            },
+            Err(_) => match color.parse::<Color>() {
+                Ok(color) => color,
+                Err(_) => return part.to_string(),
+            },
+        },
+    };
+    part.color(color).to_string()
+}
+
+/// One of the 16 standard terminal colors by index
+fn ansi_color(index: u8) -> Option<Color> {
+    Some(match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::White,
+        8 => Color::BrightBlack,
+        9 => Color::BrightRed,
+        10 => Color::BrightGreen,
+        11 => Color::BrightYellow,
+        12 => Color::BrightBlue,
+        13 => Color::BrightMagenta,
+        14 => Color::BrightCyan,
+        15 => Color::BrightWhite,
+        _ => return None,
+    })
+}
+
+/// Resolve the number format for displayed values
+///
+/// Structured output never goes through this and stays canonical.
+fn number_format(system_info: &SystemInfo, config: &Config) -> NumberFormat {
+    match &config.format.number_locale {
+        NumberLocale::Auto => {
+            NumberFormat::for_locale(system_info.get_field("locale").unwrap_or(""))
+        }
+        NumberLocale::C => NumberFormat::C,
+        NumberLocale::Locale(locale) => NumberFormat::for_locale(locale),
+    }
+}
+
+/// Append an info line, label padded to `label_width`, cut to `max_width`
+fn push_info_item(
+    output: &mut String,
+    item: &InfoItem,
+    config: &Config,
+    label_width: usize,
+    max_width: usize,
+) {
+    if item.value.is_empty() || item.value == "Unknown" {
+        return;
+    }
+
+    // Apply colors like original neofetch
+    let start = output.len();
+    if !item.label.is_empty() {
+        let label_style = if config.info.bold { "1;36" } else { "36" };
+        utils::push_styled(output, &item.label, label_style);
+        push_spaces(
+            output,
+            label_width.saturating_sub(strip_ansi_for_length(&item.label)),
+        );
+        utils::push_styled(output, &config.info.separator, "37");
+        output.push(' ');
+    }
+    utils::push_styled(output, &item.value, "37");
+
+    // Truncate if too long to prevent wrapping
+    truncate_tail(output, start, max_width);
+}
+
+/// Cut what was appended to `output` since `start` to `max_width` columns
+///
+/// Lines that fit, nearly all of them, are left alone without copying.
+fn truncate_tail(output: &mut String, start: usize, max_width: usize) {
+    if strip_ansi_for_length(&output[start..]) > max_width {
+        let line = output.split_off(start);
+        output.push_str(&truncate_with_ansi(&line, max_width));
     }
 }
 
@@ -295,9 +581,7 @@ fn truncate_with_ansi(text: &str, max_width: usize) -> String {
     let mut result = String::new();
     let mut visible_count = 0;
     let mut in_escape = false;
-    let mut chars = text.chars();
-
-    while let Some(ch) = chars.next() {
+    for ch in text.chars() {
         if ch == '\x1b' {
             in_escape = true;
             result.push(ch);
@@ -307,12 +591,14 @@ fn truncate_with_ansi(text: &str, max_width: usize) -> String {
                 in_escape = false;
             }
         } else {
-            if visible_count >= max_width.saturating_sub(3) {
+            // Wide characters take two columns and must not straddle the cut
+            let width = ch.width().unwrap_or(0);
+            if visible_count + width > max_width.saturating_sub(3) {
                 result.push_str("...");
                 break;
             }
             result.push(ch);
-            visible_count += 1;
+            visible_count += width;
         }
     }
 
@@ -320,6 +606,9 @@ fn truncate_with_ansi(text: &str, max_width: usize) -> String {
 }
 
 /// Calculate the visible length of text (excluding ANSI escape codes)
+///
+/// Counted in terminal columns, so CJK characters and most emoji count
+/// twice and combining marks not at all.
 fn strip_ansi_for_length(text: &str) -> usize {
     let mut length = 0;
     let mut in_escape = false;
@@ -330,36 +619,56 @@ fn strip_ansi_for_length(text: &str) -> usize {
         } else if in_escape && ch == 'm' {
             in_escape = false;
         } else if !in_escape {
-            length += 1;
+            length += ch.width().unwrap_or(0);
         }
     }
 
     length
 }
 
-/// Format special items like title, underline, colors with specific width
-fn format_special_item_with_width(item: &InfoItem, config: &Config, max_width: usize) -> String {
-    if item.label.is_empty() {
-        // This could be title, underline, or colors
-        if item.value.contains('\x1b') {
-            // Already contains ANSI escape codes (like colors)
-            item.value.clone()
-        } else if item.value.chars().all(|c| c == '-' || c == '=' || c == '_') {
-            // This is an underline
-            let colored_underline = item.value.cyan().to_string();
-            truncate_text(&colored_underline, max_width)
-        } else {
-            // This is likely the title
-            let colored_title = if config.info.bold {
-                item.value.bold().green().to_string()
-            } else {
-                item.value.green().to_string()
-            };
-            truncate_text(&colored_title, max_width)
+/// Remove ANSI escape codes from text
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_escape = false;
+
+    for ch in text.chars() {
+        if ch == '\x1b' {
+            in_escape = true;
+        } else if in_escape && ch == 'm' {
+            in_escape = false;
+        } else if !in_escape {
+            result.push(ch);
         }
-    } else {
-        format_info_item_with_width(item, config, max_width)
     }
+
+    result
+}
+
+/// Append a special item like title, underline, colors, cut to `max_width`
+fn push_special_item(output: &mut String, item: &InfoItem, config: &Config, max_width: usize) {
+    if !item.label.is_empty() {
+        return push_info_item(output, item, config, 0, max_width);
+    }
+
+    // This could be title, underline, or colors
+    if item.value.contains('\x1b') {
+        // Already contains ANSI escape codes (like colors)
+        output.push_str(&item.value);
+        return;
+    }
+
+    let style = if item.value.chars().all(|c| c == '-' || c == '=' || c == '_') {
+        // This is an underline
+        "36"
+    } else if config.info.bold {
+        // This is likely the title
+        "1;32"
+    } else {
+        "32"
+    };
+    let start = output.len();
+    utils::push_styled(output, &item.value, style);
+    truncate_tail(output, start, max_width);
 }
 
 /// Generate underline for the title
@@ -368,57 +677,213 @@ fn generate_underline(title: &str, config: &Config) -> String {
         return String::new();
     }
 
-    let length = title.chars().count();
+    let length = title.width();
     config.info.underline_char.repeat(length)
 }
 
 /// Generate JSON output
 fn generate_json_output(system_info: &SystemInfo) -> Result<String> {
-    let mut json_obj = serde_json::Map::new();
+    Ok(serde_json::to_string_pretty(system_info)?)
+}
 
-    json_obj.insert(
-        "title".to_string(),
-        serde_json::Value::String(system_info.get_field("title").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "os".to_string(),
-        serde_json::Value::String(system_info.get_field("os").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "host".to_string(),
-        serde_json::Value::String(system_info.get_field("host").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "kernel".to_string(),
-        serde_json::Value::String(system_info.get_field("kernel").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "uptime".to_string(),
-        serde_json::Value::String(system_info.get_field("uptime").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "packages".to_string(),
-        serde_json::Value::String(system_info.get_field("packages").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "shell".to_string(),
-        serde_json::Value::String(system_info.get_field("shell").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "cpu".to_string(),
-        serde_json::Value::String(system_info.get_field("cpu").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "gpu".to_string(),
-        serde_json::Value::String(system_info.get_field("gpu").unwrap_or("").to_string()),
-    );
-    json_obj.insert(
-        "memory".to_string(),
-        serde_json::Value::String(system_info.get_field("memory").unwrap_or("").to_string()),
+/// Generate YAML output with the same fields as the JSON output
+///
+/// The output is a plain mapping of scalars and small maps, so it is written
+/// by hand from the JSON fields, in the same order.
+fn generate_yaml_output(system_info: &SystemInfo) -> Result<String> {
+    let json = serde_json::to_string(system_info)?;
+    let OrderedMap(entries) = serde_json::from_str(&json)?;
+
+    let mut output = String::new();
+    push_yaml_mapping(
+        &mut output,
+        entries.iter().map(|(key, value)| (key, value)),
+        0,
     );
 
-    let json_value = serde_json::Value::Object(json_obj);
-    Ok(serde_json::to_string_pretty(&json_value)?)
+    // The caller terminates the last line
+    output.pop();
+    Ok(output)
+}
+
+/// A JSON object's entries in document order
+struct OrderedMap(Vec<(String, serde_json::Value)>);
+
+impl<'de> serde::Deserialize<'de> for OrderedMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Entries;
+
+        impl<'de> serde::de::Visitor<'de> for Entries {
+            type Value = OrderedMap;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<OrderedMap, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedMap(entries))
+            }
+        }
+
+        deserializer.deserialize_map(Entries)
+    }
+}
+
+/// Append `key: value` lines, nested maps indented by two spaces per level
+fn push_yaml_mapping<'a>(
+    output: &mut String,
+    entries: impl IntoIterator<Item = (&'a String, &'a serde_json::Value)>,
+    depth: usize,
+) {
+    for (key, value) in entries {
+        push_spaces(output, depth * 2);
+        output.push_str(&yaml_scalar(key));
+        output.push(':');
+        match value {
+            serde_json::Value::Object(map) if !map.is_empty() => {
+                output.push('\n');
+                push_yaml_mapping(output, map, depth + 1);
+            }
+            value => {
+                output.push(' ');
+                output.push_str(&yaml_value(value));
+                output.push('\n');
+            }
+        }
+    }
+}
+
+/// A JSON value as a single-line YAML value
+fn yaml_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => yaml_scalar(text),
+        serde_json::Value::Object(_) => "{}".to_string(),
+        serde_json::Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(yaml_value).collect::<Vec<_>>().join(", ")
+        ),
+        // null, booleans and numbers read the same in YAML
+        value => value.to_string(),
+    }
+}
+
+/// Words a YAML 1.1 or 1.2 parser would not read as a string
+const YAML_RESERVED: &[&str] = &[
+    "null", "~", "true", "false", "yes", "no", "on", "off", "y", "n",
+];
+
+/// A string as a YAML scalar, plain when that reads back as the same string
+///
+/// Anything that could be taken for another type or holds YAML syntax is
+/// double quoted; JSON string escapes are valid YAML.
+fn yaml_scalar(text: &str) -> String {
+    let plain = text
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_alphanumeric() || "(/".contains(first))
+        && !text.ends_with(' ')
+        && !text
+            .chars()
+            .any(|c| c.is_control() || ":#'\"`{}[]|>&*!\\".contains(c))
+        && !YAML_RESERVED.contains(&text.to_lowercase().as_str())
+        && text.parse::<f64>().is_err()
+        && !text.starts_with("0x")
+        && !text.starts_with("0o");
+
+    if plain {
+        text.to_string()
+    } else {
+        serde_json::Value::from(text).to_string()
+    }
+}
+
+/// Generate one JSON object per field, one per line, for log scrapers
+///
+/// Lists the same lines as the normal output, in `info.order` and with the
+/// configured labels, but with raw values. Each line parses on its own.
+/// Undetected fields are left out, or listed with an `error` key in verbose
+/// mode.
+fn generate_jsonl_output(system_info: &SystemInfo, config: &Config) -> Result<String> {
+    let mut output = String::new();
+
+    for item in get_info_items(system_info, config) {
+        if item.field == "underline" {
+            continue;
+        }
+        // The displayed value may be colored or localized
+        let value = match system_info.get_field(&item.field) {
+            Some(value) => value,
+            None => item.value.as_str(),
+        };
+        let label = match item.field.as_str() {
+            "title" => field_label("title"),
+            _ => item.label.clone(),
+        };
+
+        let line = if !item.show || value.is_empty() || value == "Unknown" {
+            if !config.behavior.verbose {
+                continue;
+            }
+            serde_json::json!({
+                "field": item.field,
+                "label": label,
+                "error": "not detected",
+            })
+        } else {
+            serde_json::json!({
+                "field": item.field,
+                "label": label,
+                "value": value,
+                "numeric": field_numeric(system_info, &item.field, value),
+            })
+        };
+        output.push_str(&serde_json::to_string(&line)?);
+        output.push('\n');
+    }
+
+    // The caller terminates the last line
+    output.pop();
+    Ok(output)
+}
+
+/// Human-readable label for a field name, e.g. `wm_theme` -> `WM Theme`
+fn field_label(field: &str) -> String {
+    match field {
+        "install_date" => return "Installed".to_string(),
+        "datetime" => return "Date".to_string(),
+        _ => {}
+    }
+
+    field
+        .split('_')
+        .map(|word| match word {
+            "os" | "de" | "wm" | "cpu" | "gpu" | "ip" => word.to_uppercase(),
+            _ => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Raw number behind a field, when it has one
+fn field_numeric(system_info: &SystemInfo, field: &str, value: &str) -> Option<f64> {
+    match field {
+        "memory" => Some(system_info.memory_used_bytes as f64),
+        "services" => system_info.services_count.map(|count| count as f64),
+        _ => value.trim().parse().ok(),
+    }
 }
 
 /// Generate stdout-only output (no ASCII art)
@@ -441,7 +906,7 @@ fn generate_stdout_output(
     }
 
     // Add color blocks if enabled
-    if config.format.color_blocks {
+    if show_color_blocks(config) {
         let colors = system_info.get_field("colors").unwrap_or("");
         if !colors.is_empty() {
             output.push('\n');
@@ -450,5 +915,564 @@ fn generate_stdout_output(
         }
     }
 
+    if !config.format.footer.is_empty() {
+        output.push('\n');
+        output.push_str(&config.format.footer);
+        output.push('\n');
+    }
+
     Ok(output)
 }
+
+/// Fields read out first in accessible mode, after the title
+const ACCESSIBLE_PRIORITY: &[&str] = &["os", "memory", "disk", "battery"];
+
+/// Generate screen-reader friendly output
+///
+/// One `label: value.` sentence per line with no logo, colors or
+/// abbreviations, the most important fields first.
+fn generate_accessible_output(info_items: &[InfoItem], system_info: &SystemInfo) -> String {
+    let mut output = String::new();
+
+    // Escape codes from colored values or custom commands would be read
+    // out as garbage
+    let title = strip_ansi(system_info.get_field("title").unwrap_or(""));
+    if !title.is_empty() {
+        output.push_str(&format!("Title: {}.\n", title));
+    }
+
+    let visible: Vec<&InfoItem> = info_items
+        .iter()
+        .filter(|item| {
+            item.show && !item.is_heading() && !item.value.is_empty() && item.value != "Unknown"
+        })
+        .collect();
+
+    let prioritized = ACCESSIBLE_PRIORITY
+        .iter()
+        .filter_map(|field| visible.iter().find(|item| item.field == *field));
+    let remaining = visible
+        .iter()
+        .filter(|item| !ACCESSIBLE_PRIORITY.contains(&item.field.as_str()));
+
+    for item in prioritized.chain(remaining) {
+        let value = expand_abbreviations(&strip_ansi(&item.value));
+        let value = value.trim_end_matches('.');
+        if item.label.is_empty() {
+            output.push_str(&format!("{}.\n", value));
+        } else {
+            output.push_str(&format!("{}: {}.\n", item.label, value));
+        }
+    }
+
+    output
+}
+
+/// Spell out unit abbreviations so screen readers pronounce them as words
+///
+/// Percentages are spelled out too, e.g. `(47%)` becomes `(47 percent)`.
+fn expand_abbreviations(value: &str) -> String {
+    let value = value.replace('%', " percent");
+    const EXPANSIONS: &[(&str, &str)] = &[
+        ("KiB", " kibibytes"),
+        ("MiB", " mebibytes"),
+        ("GiB", " gibibytes"),
+        ("TiB", " tebibytes"),
+        ("mins", "minutes"),
+        ("min", "minute"),
+    ];
+
+    value
+        .split(' ')
+        .map(|word| {
+            let (core, trailing) = match word.strip_suffix(',') {
+                Some(core) => (core, ","),
+                None => (word, ""),
+            };
+            for (short, long) in EXPANSIONS {
+                if core == *short {
+                    return format!("{}{}", long.trim_start(), trailing);
+                }
+                if let Some(number) = core.strip_suffix(short) {
+                    if !number.is_empty() && long.starts_with(' ') {
+                        return format!("{}{}{}", number, long, trailing);
+                    }
+                }
+            }
+            word.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Color depth the terminal claims to support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+/// Detect the terminal's color depth from `COLORTERM` and `TERM`
+pub fn detect_color_support() -> ColorSupport {
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorSupport::TrueColor;
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    if term.contains("256color") || std::env::var("WT_SESSION").is_ok() {
+        return ColorSupport::Ansi256;
+    }
+
+    ColorSupport::Basic
+}
+
+/// Generate the terminal color test pattern for the detected color depth
+pub fn generate_color_test(width: usize) -> String {
+    generate_color_test_for(width, detect_color_support())
+}
+
+/// Generate the terminal color test pattern
+///
+/// Shows the 16 base colors as foreground and background, the 256-color
+/// cube and grayscale ramp, and a truecolor gradient. Sections the terminal
+/// can't display are replaced by a note.
+pub fn generate_color_test_for(width: usize, support: ColorSupport) -> String {
+    let mut output = String::new();
+
+    // 16 base colors, each cell three columns wide; narrow terminals get
+    // the normal and bright halves on separate lines
+    output.push_str("16 colors\n");
+    let split_halves = width < 3 + 16 * 3;
+    for (label, normal, bright) in [("fg", 30, 90), ("bg", 40, 100)] {
+        output.push_str(label);
+        output.push(' ');
+        for (half, base) in [normal, bright].into_iter().enumerate() {
+            if half == 1 && split_halves {
+                output.push_str("\x1b[0m\n   ");
+            }
+            for i in 0..8 {
+                output.push_str(&format!("\x1b[{}m{:>2} ", base + i, half * 8 + i));
+            }
+        }
+        output.push_str("\x1b[0m\n");
+    }
+
+    // 256-color cube: six 36-cell rows, then the 24-step grayscale ramp
+    output.push_str("\n256 colors\n");
+    if support >= ColorSupport::Ansi256 {
+        let cell = if width >= 80 { "  " } else { " " };
+        for row in 0..6 {
+            for i in 0..36 {
+                output.push_str(&format!("\x1b[48;5;{}m{}", 16 + row * 36 + i, cell));
+            }
+            output.push_str("\x1b[0m\n");
+        }
+        for i in 232..=255 {
+            output.push_str(&format!("\x1b[48;5;{}m{}", i, cell));
+        }
+        output.push_str("\x1b[0m\n");
+    } else {
+        output.push_str("(skipped: terminal does not report 256-color support)\n");
+    }
+
+    // Truecolor gradient bar, red through green to blue
+    output.push_str("\ntruecolor\n");
+    if support >= ColorSupport::TrueColor {
+        let cells = width.saturating_sub(2).clamp(1, 60);
+        for i in 0..cells {
+            let position = i as f64 / cells.max(2).saturating_sub(1) as f64;
+            let (r, g, b) = if position < 0.5 {
+                let t = position * 2.0;
+                (255.0 * (1.0 - t), 255.0 * t, 0.0)
+            } else {
+                let t = (position - 0.5) * 2.0;
+                (0.0, 255.0 * (1.0 - t), 255.0 * t)
+            };
+            output.push_str(&format!("\x1b[48;2;{:.0};{:.0};{:.0}m ", r, g, b));
+        }
+        output.push_str("\x1b[0m\n");
+    } else {
+        output.push_str("(skipped: set COLORTERM=truecolor if your terminal supports it)\n");
+    }
+
+    output
+}
+
+/// Fill `{field}` placeholders in a template with gathered values
+///
+/// Any name accepted by [`SystemInfo::get_field`] works, e.g.
+/// `{title} · up {uptime}`. Unknown placeholders are left as they are.
+pub fn expand_placeholders(template: &str, system_info: &SystemInfo) -> String {
+    expand_placeholders_with(template, |name| system_info.get_field(name))
+}
+
+/// Fill `{name}` placeholders in a template with the values from `lookup`
+pub fn expand_placeholders_with<'a>(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<&'a str>,
+) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after
+            .find('}')
+            .and_then(|end| Some((end, lookup(&after[..end])?)))
+        {
+            Some((end, value)) => {
+                expanded.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                expanded.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accessible_config() -> Config {
+        let mut config = Config::default();
+        config.behavior.accessible = true;
+        config.info.disk = true;
+        config.format.memory_display = DisplayMode::Infobar;
+        config.format.disk_display = DisplayMode::Bar;
+        config.format.cpu_display = DisplayMode::Barinfo;
+        config
+    }
+
+    /// Fixed config for golden output, the same in every brand build
+    fn golden_config() -> Config {
+        let mut config = Config::default();
+        config.display.ascii_distro = Some("arch".to_string());
+        config.format.footer = String::new();
+        config
+    }
+
+    #[test]
+    fn sample_output_matches_the_golden_files() {
+        // Colors depend on the terminal running the tests
+        colored::control::set_override(false);
+        let system_info = SystemInfo::sample().unwrap();
+
+        let mut stdout_config = golden_config();
+        stdout_config.display.stdout = true;
+        let mut json_config = golden_config();
+        json_config.behavior.json = true;
+
+        for (output, golden) in [
+            (
+                generate_output(&system_info, &stdout_config).unwrap(),
+                include_str!("../tests/fixtures/golden/stdout.txt"),
+            ),
+            (
+                render_layout(&system_info, &golden_config(), 100).unwrap(),
+                include_str!("../tests/fixtures/golden/layout.txt"),
+            ),
+            (
+                generate_output(&system_info, &json_config).unwrap(),
+                include_str!("../tests/fixtures/golden/sample.json"),
+            ),
+        ] {
+            assert_eq!(output.trim_end(), golden.trim_end());
+        }
+    }
+
+    #[test]
+    fn accessible_output_has_no_escape_codes() {
+        let mut system_info = SystemInfo::sample().unwrap();
+        *system_info.get_field_mut("shell").unwrap() = "\x1b[31mbash\x1b[0m 5.2.26".to_string();
+
+        let output = generate_output(&system_info, &accessible_config()).unwrap();
+
+        assert!(!output.contains('\x1b'), "{:?}", output);
+        assert!(output.contains("Shell: bash 5.2.26."));
+    }
+
+    #[test]
+    fn accessible_output_reads_bars_as_words() {
+        let system_info = SystemInfo::sample().unwrap();
+        let output = generate_output(&system_info, &accessible_config()).unwrap();
+
+        assert!(output.contains("Memory: 7421 mebibytes / 15887 mebibytes (47 percent used)."));
+        assert!(output.contains("Disk: 24 percent used."));
+        assert!(!output.contains('%'));
+    }
+
+    #[test]
+    fn bars_round_to_the_nearest_cell() {
+        let mut format = FormatConfig::default();
+        assert_eq!(render_bar(0.0, &format, false), "[───────────────]");
+        assert_eq!(render_bar(100.0, &format, false), "[━━━━━━━━━━━━━━━]");
+        assert_eq!(render_bar(-5.0, &format, false), "[───────────────]");
+        assert_eq!(render_bar(250.0, &format, false), "[━━━━━━━━━━━━━━━]");
+
+        format.bar_length = 10;
+        format.bar_border = false;
+        format.bar_char_elapsed = "#".to_string();
+        format.bar_char_total = ".".to_string();
+        for (percent, expected) in [
+            (4.9, ".........."),
+            (5.0, "#........."),
+            (44.9, "####......"),
+            (45.0, "#####....."),
+            (94.9, "#########."),
+            (95.0, "##########"),
+        ] {
+            assert_eq!(render_bar(percent, &format, false), expected, "{}", percent);
+        }
+
+        format.bar_length = 0;
+        assert_eq!(render_bar(50.0, &format, false), "");
+    }
+
+    #[test]
+    fn stdout_bars_are_plain() {
+        colored::control::set_override(false);
+        let mut system_info = SystemInfo::sample().unwrap();
+        system_info.battery = "87% [Discharging]".to_string();
+        system_info.battery_percent = Some(87);
+        let mut config = golden_config();
+        config.display.stdout = true;
+        config.info.disk = true;
+        config.format.memory_display = DisplayMode::Infobar;
+        config.format.disk_display = DisplayMode::Bar;
+        config.format.battery_display = DisplayMode::Barinfo;
+        config.format.bar_color_elapsed = "1".to_string();
+
+        let output = generate_output(&system_info, &config).unwrap();
+
+        assert!(!output.contains('\x1b'), "{:?}", output);
+        assert!(output.contains("\nMemory: 7421MiB / 15887MiB [━━━━━━━────────]\n"));
+        assert!(output.contains("\nDisk: [━━━━───────────]\n"));
+        assert!(output.contains("\nBattery: [━━━━━━━━━━━━━──] 87% [Discharging]\n"));
+    }
+
+    #[test]
+    fn zero_failed_services_are_still_shown() {
+        let mut system_info = SystemInfo::sample().unwrap();
+        system_info.services = "0 failed".to_string();
+        system_info.services_count = Some(0);
+        system_info.services_failed = true;
+        let mut config = Config::default();
+        config.display.stdout = true;
+        config.info.order.push("services".to_string());
+
+        let output = generate_output(&system_info, &config).unwrap();
+        assert!(output.contains("Services: 0 failed"), "{}", output);
+    }
+
+    #[test]
+    fn underline_follows_the_title() {
+        let mut config = Config::default();
+        config.display.stdout = true;
+        for title in ["ci@build01", "ci@build01.ci.example.org", "ユーザー@laptop"] {
+            let mut system_info = SystemInfo::sample().unwrap();
+            system_info.title = title.to_string();
+
+            let output = generate_output(&system_info, &config).unwrap();
+            let mut lines = output.lines();
+            assert_eq!(lines.next(), Some(title));
+            let underline = "-".repeat(strip_ansi_for_length(title));
+            assert_eq!(lines.next(), Some(underline.as_str()), "{}", title);
+        }
+    }
+
+    #[test]
+    fn custom_order_is_respected() {
+        let system_info = SystemInfo::sample().unwrap();
+        let mut config = golden_config();
+        config.info.order = ["memory", "os", "title", "os", "bogus", "kernel"]
+            .map(String::from)
+            .to_vec();
+
+        // Each line is on its own row, below the one before it
+        let rows = |output: &str| -> Vec<usize> {
+            let lines: Vec<&str> = output.lines().collect();
+            let mut next = 0;
+            [
+                "7421MiB",
+                "Arch Linux",
+                "user@laptop",
+                "Arch Linux",
+                "6.8.1-arch1-1",
+            ]
+            .iter()
+            .map(|value| {
+                let row = next + lines[next..].iter().position(|line| line.contains(value))?;
+                next = row + 1;
+                Some(row)
+            })
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_else(|| panic!("{}", output))
+        };
+
+        let layout = render_layout(&system_info, &config, 100).unwrap();
+        assert_eq!(rows(&layout), [0, 1, 2, 3, 4]);
+
+        config.display.stdout = true;
+        let stdout = generate_output(&system_info, &config).unwrap();
+        assert_eq!(rows(&stdout), [0, 1, 2, 3, 4]);
+        assert_eq!(stdout.lines().count(), 5, "{}", stdout);
+    }
+
+    #[test]
+    fn jsonl_follows_the_configured_order_and_labels() {
+        let system_info = SystemInfo::sample().unwrap();
+        let mut config = Config::default();
+        config.behavior.jsonl = true;
+        config.info.order = ["title", "underline", "memory", "os", "song", "disk"]
+            .map(String::from)
+            .to_vec();
+        config
+            .info
+            .labels
+            .insert("memory".to_string(), "RAM".to_string());
+
+        let parse = |output: String| -> Vec<serde_json::Value> {
+            output
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        };
+
+        // The sample has no song, and disk is off by default
+        let lines = parse(generate_output(&system_info, &config).unwrap());
+        let fields: Vec<&str> = lines
+            .iter()
+            .map(|line| line["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["title", "memory", "os"]);
+        assert_eq!(lines[0]["value"], "user@laptop");
+        assert_eq!(lines[1]["label"], "RAM");
+        assert_eq!(lines[1]["value"], "7421MiB / 15887MiB");
+        assert_eq!(lines[2]["label"], "OS");
+
+        config.behavior.verbose = true;
+        let lines = parse(generate_output(&system_info, &config).unwrap());
+        let errors: Vec<&str> = lines
+            .iter()
+            .filter(|line| line["error"] == "not detected")
+            .map(|line| line["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(errors, ["song", "disk"]);
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(strip_ansi_for_length("ThinkPad"), 8);
+        assert_eq!(strip_ansi_for_length("\x1b[1m日本語\x1b[0m"), 6);
+        assert_eq!(strip_ansi_for_length("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn truncation_does_not_split_wide_characters() {
+        let truncate = |text: &str, max_width| {
+            let mut output = format!("logo {}", text);
+            truncate_tail(&mut output, 5, max_width);
+            output
+        };
+
+        assert_eq!(truncate("日本語のホスト名", 10), "logo 日本語...");
+        assert_eq!(truncate("日本語のホスト名", 16), "logo 日本語のホスト名");
+        assert_eq!(truncate("abcdefghijkl", 10), "logo abcdefg...");
+    }
+
+    /// The 16-color section exactly as a terminal receives it
+    const SIXTEEN_COLORS: &str = concat!(
+        "16 colors\n",
+        "fg \x1b[30m 0 \x1b[31m 1 \x1b[32m 2 \x1b[33m 3 \x1b[34m 4 \x1b[35m 5 \x1b[36m 6 \x1b[37m 7 ",
+        "\x1b[90m 8 \x1b[91m 9 \x1b[92m10 \x1b[93m11 \x1b[94m12 \x1b[95m13 \x1b[96m14 \x1b[97m15 ",
+        "\x1b[0m\n",
+        "bg \x1b[40m 0 \x1b[41m 1 \x1b[42m 2 \x1b[43m 3 \x1b[44m 4 \x1b[45m 5 \x1b[46m 6 \x1b[47m 7 ",
+        "\x1b[100m 8 \x1b[101m 9 \x1b[102m10 \x1b[103m11 \x1b[104m12 \x1b[105m13 \x1b[106m14 \x1b[107m15 ",
+        "\x1b[0m\n",
+    );
+
+    #[test]
+    fn color_test_sixteen_color_snapshot() {
+        let pattern = generate_color_test_for(80, ColorSupport::Basic);
+        assert!(pattern.starts_with(SIXTEEN_COLORS), "{:?}", pattern);
+        assert!(pattern.contains("(skipped: terminal does not report 256-color support)"));
+        assert!(pattern.contains("(skipped: set COLORTERM=truecolor"));
+    }
+
+    #[test]
+    fn color_test_splits_halves_on_narrow_terminals() {
+        let pattern = generate_color_test_for(40, ColorSupport::Basic);
+        let first = pattern.lines().nth(1).unwrap();
+        assert!(first.ends_with("\x1b[37m 7 \x1b[0m"), "{:?}", first);
+        assert!(pattern
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with("   \x1b[90m 8 "));
+    }
+
+    #[test]
+    fn color_test_full_pattern_fits_in_5000_bytes() {
+        let pattern = generate_color_test_for(80, ColorSupport::TrueColor);
+        assert!(pattern.contains("\x1b[48;5;16m"));
+        assert!(pattern.contains("\x1b[48;2;255;0;0m"));
+        assert!(pattern.len() < 5000, "{} bytes", pattern.len());
+    }
+
+    #[test]
+    fn yaml_scalars_are_quoted_only_when_needed() {
+        let cases = [
+            ("Arch Linux x86_64", "Arch Linux x86_64"),
+            ("3 days, 4 hours, 12 mins", "3 days, 4 hours, 12 mins"),
+            ("7421MiB / 15887MiB (47%)", "7421MiB / 15887MiB (47%)"),
+            ("", "\"\""),
+            ("7.1", "\"7.1\""),
+            ("1e3", "\"1e3\""),
+            ("0x1f", "\"0x1f\""),
+            ("yes", "\"yes\""),
+            ("Null", "\"Null\""),
+            ("- item", "\"- item\""),
+            ("Fri 16 Oct 2026 09:48", "\"Fri 16 Oct 2026 09:48\""),
+            ("key: value", "\"key: value\""),
+            ("tab\there", "\"tab\\there\""),
+            ("say \"hi\"", "\"say \\\"hi\\\"\""),
+            ("trailing ", "\"trailing \""),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(yaml_scalar(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn yaml_output_mirrors_the_json_fields() {
+        let system_info = SystemInfo::sample().unwrap();
+        let yaml = generate_yaml_output(&system_info).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&generate_json_output(&system_info).unwrap()).unwrap();
+
+        assert!(yaml.starts_with("title: user@laptop\nos: Arch Linux x86_64\n"));
+        assert!(yaml.contains("\nmemory_used_bytes: 7781482496\n"));
+        assert!(yaml.contains("\ncpu_usage: null\n"));
+        assert!(yaml.contains("\npackage_counts:\n  flatpak: 12\n  pacman: 1843\n"));
+        assert!(yaml.ends_with("\ncustom: {}"));
+
+        let keys: Vec<&str> = yaml
+            .lines()
+            .filter(|line| !line.starts_with(' '))
+            .map(|line| line.split(':').next().unwrap())
+            .collect();
+        assert_eq!(keys.len(), json.as_object().unwrap().len());
+        for key in keys {
+            assert!(json.get(key).is_some(), "{}", key);
+        }
+    }
+}
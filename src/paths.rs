@@ -0,0 +1,63 @@
+//! Filesystem locations used by neofetch-rs
+//!
+//! Every config and cache path is resolved here once, so `--portable` (or
+//! `NEOFETCH_HOME`) can redirect all of them to a single directory.
+
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming a portable root, like `--portable`
+pub const HOME_ENV: &str = "NEOFETCH_HOME";
+
+/// Name of the config file inside `config_dir`
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Resolved config and cache directories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    /// Directory holding `config.toml`
+    pub config_dir: PathBuf,
+    /// Directory for cached field values and thumbnails
+    pub cache_dir: PathBuf,
+    /// Whether the paths come from a portable root
+    pub portable: bool,
+}
+
+impl Paths {
+    /// Resolve paths from `--portable`, then `NEOFETCH_HOME`, then the
+    /// platform's usual directories
+    pub fn resolve(portable: Option<&Path>) -> Self {
+        let root = portable.map(Path::to_path_buf).or_else(|| {
+            std::env::var_os(HOME_ENV)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        });
+
+        match root {
+            Some(root) => Self::portable(&root),
+            None => Self::standard(),
+        }
+    }
+
+    /// Paths under a portable root directory
+    pub fn portable(root: &Path) -> Self {
+        Self {
+            config_dir: root.join("config"),
+            cache_dir: root.join("cache"),
+            portable: true,
+        }
+    }
+
+    /// The platform's config and cache directories, e.g. XDG on Linux
+    pub fn standard() -> Self {
+        Self {
+            config_dir: dirs::config_dir().unwrap_or_default().join("neofetch"),
+            cache_dir: dirs::cache_dir().unwrap_or_default().join("neofetch"),
+            portable: false,
+        }
+    }
+
+    /// Path of the user config file
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join(CONFIG_FILE)
+    }
+}
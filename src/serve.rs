@@ -0,0 +1,205 @@
+//! Answer field queries from stdin, for status bars and shell prompts
+//!
+//! `--serve` gathers everything once and then keeps answering, so repeated
+//! queries don't pay for a fresh start each time. Fields that change while
+//! running are gathered again once their value is older than its TTL in
+//! [`VOLATILE_FIELDS`].
+
+use crate::config::{CompiledRewrite, Config};
+use crate::system_info::{canonical_field, SystemInfo, VOLATILE_FIELDS};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::time::Instant;
+
+/// Protocol description shown by `--help`
+pub const PROTOCOL: &str = "\
+Answer queries from stdin, one per line, until EOF.
+
+A plain field name such as `memory` is answered with its value on one line.
+A JSON request such as {\"field\": \"memory\", \"refresh\": true} is answered
+with {\"field\": \"memory\", \"value\": \"...\"}; `refresh` gathers the field
+again even when its value is still fresh. Only fields that change while
+running are gathered again (uptime, datetime, memory, swap, cpu, cpu_temp,
+song, battery, power_profile, resolution, disk, local_ip and users); for
+the others `refresh` is ignored and the value from startup is returned.
+Unknown fields get `error: ...` or {\"field\": ..., \"error\": ...}.
+Empty lines are ignored.";
+
+/// A JSON request line
+#[derive(Debug, Deserialize)]
+struct Request {
+    field: String,
+    /// Gather again even if fresh; ignored outside [`VOLATILE_FIELDS`]
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// Gathered information kept warm between queries
+pub struct Server<'a> {
+    system_info: &'a mut SystemInfo,
+    config: &'a Config,
+    rewrites: &'a [CompiledRewrite],
+    /// When each volatile field was last gathered
+    gathered_at: HashMap<&'static str, Instant>,
+}
+
+impl<'a> Server<'a> {
+    /// Serve an already gathered, rewritten and anonymized `system_info`
+    pub fn new(
+        system_info: &'a mut SystemInfo,
+        config: &'a Config,
+        rewrites: &'a [CompiledRewrite],
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            system_info,
+            config,
+            rewrites,
+            gathered_at: VOLATILE_FIELDS
+                .iter()
+                .map(|(field, _)| (*field, now))
+                .collect(),
+        }
+    }
+
+    /// Answer every request line from `input` until EOF
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let reply = if line.starts_with('{') {
+                self.answer_json(line)
+            } else {
+                match self.answer(line, false)? {
+                    // One reply per line, so multi-line values are joined
+                    Some(value) => value.replace('\n', " "),
+                    None => format!("error: unknown field '{}'", line),
+                }
+            };
+
+            // Reply right away, the client is waiting on this line
+            writeln!(output, "{}", reply)?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Reply to a JSON request, as a single JSON line
+    fn answer_json(&mut self, line: &str) -> String {
+        let reply = match serde_json::from_str::<Request>(line) {
+            Ok(request) => match self.answer(&request.field, request.refresh) {
+                Ok(Some(value)) => serde_json::json!({
+                    "field": request.field,
+                    "value": if value == "Unknown" { "" } else { &value },
+                }),
+                Ok(None) => serde_json::json!({
+                    "field": request.field,
+                    "error": "unknown field",
+                }),
+                Err(err) => serde_json::json!({
+                    "field": request.field,
+                    "error": err.to_string(),
+                }),
+            },
+            Err(err) => serde_json::json!({ "error": format!("invalid request: {}", err) }),
+        };
+        reply.to_string()
+    }
+
+    /// Current value of `field`, gathering it again when it's stale
+    ///
+    /// `None` for fields that don't exist.
+    fn answer(&mut self, field: &str, refresh: bool) -> Result<Option<String>> {
+        let field = match canonical_field(field) {
+            Some(field) => field,
+            None => return Ok(None),
+        };
+
+        let stale = VOLATILE_FIELDS
+            .iter()
+            .find(|(volatile, _)| *volatile == field)
+            .map(|(volatile, ttl)| match self.gathered_at.get(volatile) {
+                Some(at) => refresh || at.elapsed() >= *ttl,
+                None => true,
+            })
+            .unwrap_or(false);
+
+        let refreshed = if stale {
+            self.system_info.refresh_field(field, self.config)?
+        } else {
+            &[]
+        };
+        for refreshed_field in refreshed {
+            self.gathered_at.insert(refreshed_field, Instant::now());
+        }
+
+        if !refreshed.is_empty() {
+            // Only the refreshed values are new, the rest must not be
+            // rewritten twice
+            for rewrite in self.rewrites {
+                let target = match canonical_field(&rewrite.field) {
+                    Some(target) if refreshed.contains(&target) => target,
+                    _ => continue,
+                };
+                if let Some(value) = self.system_info.get_field_mut(target) {
+                    *value = rewrite
+                        .regex
+                        .replace_all(value, rewrite.replace.as_str())
+                        .into_owned();
+                }
+            }
+            if self.config.behavior.anonymize {
+                crate::anonymize::anonymize(
+                    self.system_info,
+                    &crate::utils::get_username(),
+                    &crate::utils::get_hostname(),
+                );
+            }
+        }
+
+        Ok(self.system_info.get_field(field).map(str::to_string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_answered_line_by_line() {
+        let mut system_info = SystemInfo::sample().unwrap();
+        let config = Config::default();
+        let input = "kernel\n\n{\"field\": \"kernel\", \"refresh\": true}\n\
+                     {\"field\": \"model\"}\n{\"field\": \"theme\"}\nbogus\n{\"field\": \"bogus\"}\n{\"refresh\": true}\n";
+        let mut output = Vec::new();
+
+        Server::new(&mut system_info, &config, &[])
+            .run(input.as_bytes(), &mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 7, "{}", output);
+        assert_eq!(lines[0], "6.8.1-arch1-1");
+        // Not volatile, so `refresh` keeps the startup value
+        assert_eq!(lines[1], r#"{"field":"kernel","value":"6.8.1-arch1-1"}"#);
+        assert_eq!(
+            lines[2],
+            r#"{"field":"model","value":"ThinkPad X1 Carbon Gen 9"}"#
+        );
+        assert_eq!(lines[3], r#"{"field":"theme","value":""}"#);
+        assert_eq!(lines[4], "error: unknown field 'bogus'");
+        assert_eq!(lines[5], r#"{"error":"unknown field","field":"bogus"}"#);
+        assert!(
+            lines[6].starts_with(r#"{"error":"invalid request: missing field `field`"#),
+            "{}",
+            lines[6]
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! FreeBSD, OpenBSD, NetBSD and DragonFly system information
+//!
+//! sysinfo already covers the basics on the BSDs, so nothing is overridden
+//! yet; BSD-specific probes belong here.
+
+use super::PlatformGather;
+
+/// BSD implementation of [`PlatformGather`]
+pub(super) struct Bsd;
+
+impl PlatformGather for Bsd {}
@@ -0,0 +1,1071 @@
+//! Platform-independent helpers for system information gathering
+//!
+//! Formatting, package probe plumbing and other code shared by every
+//! platform module lives here.
+
+use crate::config::{CpuTemp, MemoryUnit, PackageManagerDisplay};
+use sysinfo::System;
+
+/// How long a fetched weather report stays valid
+pub(super) const WEATHER_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// How long to wait for the weather endpoint
+pub(super) const WEATHER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Characters left as they are in the location, RFC 3986's unreserved set
+const LOCATION_UNRESERVED: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Fill `{location}` in the weather URL, percent-encoded
+pub(super) fn weather_url(url: &str, location: &str) -> String {
+    let location = percent_encoding::utf8_percent_encode(location, LOCATION_UNRESERVED);
+    url.replace("{location}", &location.to_string())
+}
+
+/// Fetch a one-line weather report with curl or wget
+///
+/// `None` when neither is installed or the request fails or takes longer
+/// than `timeout`.
+pub(super) fn fetch_weather(url: &str, timeout: std::time::Duration) -> Option<String> {
+    let seconds = format!("{:.1}", timeout.as_secs_f64());
+    // wget's --timeout applies per phase, so bound the whole run too
+    let deadline = timeout + std::time::Duration::from_secs(1);
+    let response = if crate::utils::command_exists("curl") {
+        crate::utils::execute_command_with_timeout(
+            "curl",
+            &["-fsS", "--max-time", &seconds, url],
+            deadline,
+        )
+    } else if crate::utils::command_exists("wget") {
+        let timeout_arg = format!("--timeout={}", seconds);
+        crate::utils::execute_command_with_timeout("wget", &["-qO-", &timeout_arg, url], deadline)
+    } else {
+        return None;
+    };
+
+    // Keep only the first line; anything multi-line is an error page
+    let response = response.ok()?;
+    let line = response.lines().next()?.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// A package count probe: the manager's name and the work to run
+pub(super) type PackageProbe = (&'static str, Box<dyn FnOnce() -> Option<usize> + Send>);
+
+/// Run package probes concurrently
+///
+/// Probes that haven't answered once `timeout` has passed are skipped
+/// (snapd in particular can take seconds) and returned by name after the
+/// counts. Their threads are left to finish on their own. Counts come back
+/// in probe order.
+pub(super) fn run_package_probes(
+    probes: Vec<PackageProbe>,
+    timeout: std::time::Duration,
+) -> (Vec<(usize, &'static str)>, Vec<&'static str>) {
+    let managers: Vec<&'static str> = probes.iter().map(|(manager, _)| *manager).collect();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    for (index, (_, probe)) in probes.into_iter().enumerate() {
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send((index, probe()));
+        });
+    }
+    drop(sender);
+
+    let deadline = std::time::Instant::now() + timeout;
+    let mut counts = vec![None; managers.len()];
+    let mut answered = vec![false; managers.len()];
+
+    while let Some(remaining) = deadline.checked_duration_since(std::time::Instant::now()) {
+        match receiver.recv_timeout(remaining) {
+            Ok((index, count)) => {
+                counts[index] = count;
+                answered[index] = true;
+            }
+            Err(_) => break,
+        }
+    }
+
+    let skipped = managers
+        .iter()
+        .zip(&answered)
+        .filter(|(_, answered)| !**answered)
+        .map(|(manager, _)| *manager)
+        .collect();
+    let counts = managers
+        .into_iter()
+        .zip(counts)
+        .filter_map(|(manager, count)| count.map(|count| (count, manager)))
+        .collect();
+    (counts, skipped)
+}
+
+/// Probe for an opt-in language package manager from `package_managers_extra`
+///
+/// These are slow to answer, which is why they're never queried by default.
+pub(super) fn extra_package_probe(
+    name: &str,
+    timeout: std::time::Duration,
+) -> Option<PackageProbe> {
+    let probe: PackageProbe = match name {
+        "cargo" => ("cargo", Box::new(count_cargo_binaries)),
+        "pip" => (
+            "pip",
+            Box::new(move || {
+                count_package_lines("pip3", &["list", "--format=freeze"], 0, timeout).or_else(
+                    || count_package_lines("pip", &["list", "--format=freeze"], 0, timeout),
+                )
+            }),
+        ),
+        // The first parseable line is the global prefix itself
+        "npm" => (
+            "npm",
+            Box::new(move || {
+                count_package_lines("npm", &["ls", "-g", "--depth=0", "--parseable"], 1, timeout)
+            }),
+        ),
+        "gem" => (
+            "gem",
+            Box::new(move || {
+                count_package_lines("gem", &["list", "--no-versions", "--local"], 0, timeout)
+            }),
+        ),
+        _ => return None,
+    };
+    Some(probe)
+}
+
+/// Count the binaries installed with `cargo install`
+fn count_cargo_binaries() -> Option<usize> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))?;
+    let count = std::fs::read_dir(cargo_home.join("bin")).ok()?.count();
+    (count > 0).then_some(count)
+}
+
+/// Count the non-empty lines a package manager prints, minus `header`
+///
+/// The command is only run when it's installed. Inside Flatpak host binaries
+/// aren't on our PATH, so the check is left to `flatpak-spawn`.
+pub(super) fn count_package_lines(
+    command: &str,
+    args: &[&str],
+    header: usize,
+    timeout: std::time::Duration,
+) -> Option<usize> {
+    let sandboxed = crate::utils::detect_sandbox() == Some(crate::utils::Sandbox::Flatpak);
+    if !sandboxed && !crate::utils::command_exists(command) {
+        return None;
+    }
+
+    let output =
+        crate::utils::output_with_timeout(crate::utils::host_command(command).args(args), timeout)
+            .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let count = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+        .saturating_sub(header);
+    (count > 0).then_some(count)
+}
+
+/// Version from the first line of `program version_arg`, e.g. `3.4`
+pub(super) fn command_version(program: &str, version_arg: &str) -> Option<String> {
+    let output = crate::utils::output_with_timeout(
+        crate::utils::command(program).arg(version_arg),
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let output = String::from_utf8_lossy(&output.stdout);
+    crate::utils::parse_version_from_output(output.lines().next().unwrap_or(""))
+}
+
+/// Logged-in users from `who`, or `Unknown` when nobody is logged in
+pub(super) fn who_users(sessions: bool) -> String {
+    let who = crate::utils::execute_command_with_timeout("who", &[], crate::utils::COMMAND_TIMEOUT)
+        .unwrap_or_default();
+    format_users(&parse_who_users(&who), sessions)
+}
+
+/// Join users and their sessions, e.g. `alice (tty1 pts/0), bob`
+///
+/// Sessions are only listed with `sessions`. Headless servers often have
+/// nobody logged in at all, which gives `Unknown`.
+pub(super) fn format_users(users: &[(String, Vec<String>)], sessions: bool) -> String {
+    if users.is_empty() {
+        return "Unknown".to_string();
+    }
+    users
+        .iter()
+        .map(|(name, terminals)| {
+            if sessions && !terminals.is_empty() {
+                format!("{} ({})", name, terminals.join(" "))
+            } else {
+                name.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Placeholder strings firmware vendors leave in DMI fields
+const HOST_PLACEHOLDERS: &[&str] = &[
+    "To Be Filled By O.E.M.",
+    "Default string",
+    "Not Applicable",
+    "Not Specified",
+    "System Product Name",
+    "System Version",
+    "System manufacturer",
+    "Type1ProductConfigId",
+    "All Series",
+    "INVALID",
+    "Undefined",
+    "None",
+    "OEM",
+    "O.E.M.",
+    "x.x",
+    "0123456789",
+];
+
+/// Trim a host or model string, dropping known placeholder values
+pub(super) fn clean_host_part(value: &str) -> Option<String> {
+    let value = value.trim();
+    let placeholder = HOST_PLACEHOLDERS
+        .iter()
+        .any(|junk| junk.eq_ignore_ascii_case(value));
+    (!value.is_empty() && !placeholder).then(|| value.to_string())
+}
+
+/// Join the non-empty parts with single spaces
+pub(super) fn join_nonempty(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Choose the hostname to show in the title
+///
+/// An empty or `localhost` system hostname says nothing about the machine,
+/// so `$HOSTNAME` from the shell is preferred when it has something better.
+pub(super) fn pick_hostname(system: Option<String>, env: Option<String>) -> String {
+    let is_useful = |name: &String| !name.trim().is_empty() && name.trim() != "localhost";
+
+    system
+        .clone()
+        .filter(is_useful)
+        .or_else(|| env.filter(is_useful))
+        .or(system.filter(|name| !name.trim().is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Pick the locale from the environment, or `fallback` when none is set
+///
+/// Standard precedence; an empty variable counts as unset. Some setups,
+/// macOS terminals in particular, only set `LC_CTYPE`.
+pub(super) fn pick_locale(
+    env: impl Fn(&str) -> Option<String>,
+    fallback: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG", "LC_CTYPE"]
+        .iter()
+        .filter_map(|var| env(var))
+        .find(|value| !value.is_empty())
+        .or_else(fallback)
+}
+
+/// Drop a trailing UTF-8 codeset, e.g. `en_US.UTF-8` -> `en_US`
+pub(super) fn strip_utf8_suffix(locale: &str) -> &str {
+    [".UTF-8", ".utf8", ".utf-8", ".UTF8"]
+        .iter()
+        .find_map(|suffix| locale.strip_suffix(suffix))
+        .unwrap_or(locale)
+}
+
+/// Unique user names from `who` output with their terminals, in login order
+fn parse_who_users(output: &str) -> Vec<(String, Vec<String>)> {
+    let mut users: Vec<(String, Vec<String>)> = Vec::new();
+    for line in output.lines() {
+        let mut columns = line.split_whitespace();
+        let (name, terminal) = match (columns.next(), columns.next()) {
+            (Some(name), terminal) => (name, terminal),
+            (None, _) => continue,
+        };
+        add_user_session(&mut users, name, terminal);
+    }
+    users
+}
+
+/// Record a session, adding the user the first time they're seen
+pub(super) fn add_user_session(
+    users: &mut Vec<(String, Vec<String>)>,
+    name: &str,
+    session: Option<&str>,
+) {
+    let index = match users.iter().position(|(user, _)| user == name) {
+        Some(index) => index,
+        None => {
+            users.push((name.to_string(), Vec::new()));
+            users.len() - 1
+        }
+    };
+    if let Some(session) = session {
+        if !users[index].1.iter().any(|seen| seen == session) {
+            users[index].1.push(session.to_string());
+        }
+    }
+}
+
+/// Shells recognised when inspecting the parent process
+const KNOWN_SHELLS: &[&str] = &[
+    "bash",
+    "zsh",
+    "fish",
+    "sh",
+    "dash",
+    "ksh",
+    "mksh",
+    "tcsh",
+    "csh",
+    "nu",
+    "elvish",
+    "xonsh",
+    "ion",
+    "oil",
+    "osh",
+    "yash",
+    "pwsh",
+    "powershell",
+    "cmd",
+];
+
+/// Programs that run us for a shell, looked through to find the shell
+const SHELL_WRAPPERS: &[&str] = &["sudo", "doas", "env", "nice", "nohup", "time", "timeout"];
+
+/// A process as seen by the process tree walks
+struct ProcessEntry {
+    name: String,
+    exe: Option<String>,
+    parent: Option<sysinfo::Pid>,
+}
+
+/// Look up `pid` for the process tree walks
+fn lookup_process(system: &mut System, pid: sysinfo::Pid) -> Option<ProcessEntry> {
+    system.refresh_process(pid);
+    let process = system.process(pid)?;
+    Some(ProcessEntry {
+        name: process.name().to_string(),
+        exe: process.exe().map(|path| path.display().to_string()),
+        parent: process.parent(),
+    })
+}
+
+/// The shell a process name stands for, if it's a known shell
+///
+/// Login shells are named with a leading dash, e.g. `-bash`, and Windows
+/// names carry `.exe`.
+fn shell_name(process_name: &str) -> Option<&str> {
+    let name = process_name.trim_start_matches('-');
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    KNOWN_SHELLS.contains(&name).then_some(name)
+}
+
+/// Get the path of the shell that launched us
+pub(super) fn parent_shell() -> Option<String> {
+    let mut system = System::new();
+    let pid = sysinfo::get_current_pid().ok()?;
+    find_parent_shell(pid, |pid| lookup_process(&mut system, pid))
+}
+
+/// Walk up from `pid` to the first shell, looking through `sudo`, `env`
+/// and the like
+///
+/// Any other ancestor means we weren't started from a shell.
+fn find_parent_shell(
+    pid: sysinfo::Pid,
+    mut lookup: impl FnMut(sysinfo::Pid) -> Option<ProcessEntry>,
+) -> Option<String> {
+    let mut pid = lookup(pid)?.parent?;
+
+    // The depth limit guards against parent loops
+    for _ in 0..8 {
+        let process = lookup(pid)?;
+        if let Some(name) = shell_name(&process.name) {
+            return Some(process.exe.unwrap_or_else(|| name.to_string()));
+        }
+        let name = process.name.strip_suffix(".exe").unwrap_or(&process.name);
+        if !SHELL_WRAPPERS.contains(&name) {
+            return None;
+        }
+        pid = process.parent?;
+    }
+    None
+}
+
+/// Terminal emulators by process name, with the name to show
+///
+/// Linux truncates process names to 15 bytes, hence `gnome-terminal-`.
+const KNOWN_TERMINALS: &[(&str, &str)] = &[
+    ("alacritty", "Alacritty"),
+    ("kitty", "kitty"),
+    ("gnome-terminal-", "GNOME Terminal"),
+    ("gnome-terminal", "GNOME Terminal"),
+    ("kgx", "GNOME Console"),
+    ("konsole", "Konsole"),
+    ("yakuake", "Yakuake"),
+    ("wezterm-gui", "WezTerm"),
+    ("wezterm", "WezTerm"),
+    ("foot", "foot"),
+    ("footclient", "foot"),
+    ("ghostty", "Ghostty"),
+    ("xterm", "xterm"),
+    ("urxvt", "urxvt"),
+    ("urxvtd", "urxvt"),
+    ("st", "st"),
+    ("tilix", "Tilix"),
+    ("terminator", "Terminator"),
+    ("xfce4-terminal", "Xfce Terminal"),
+    ("mate-terminal", "MATE Terminal"),
+    ("lxterminal", "LXTerminal"),
+    ("qterminal", "QTerminal"),
+    ("terminology", "Terminology"),
+    ("sakura", "Sakura"),
+    ("guake", "Guake"),
+    ("tilda", "Tilda"),
+    ("contour", "Contour"),
+    ("rio", "Rio"),
+    ("warp", "Warp"),
+    ("tabby", "Tabby"),
+    ("hyper", "Hyper"),
+    ("iterm2", "iTerm2"),
+    ("terminal", "Apple Terminal"),
+    ("windowsterminal", "Windows Terminal"),
+    ("conhost", "Windows Console"),
+];
+
+/// Processes that end the search: the terminal is on another machine or
+/// there is none
+const TERMINAL_SEARCH_STOPS: &[&str] = &["sshd", "login", "init", "systemd", "launchd"];
+
+/// Find the terminal emulator by walking up the process tree
+///
+/// Inside tmux our ancestors lead to the tmux server, so the walk starts
+/// from the attached client instead, when there is one.
+pub(super) fn terminal_from_process_tree() -> Option<String> {
+    let mut system = System::new();
+    let pid = std::env::var_os("TMUX")
+        .and_then(|_| tmux_client_pid())
+        .or_else(|| sysinfo::get_current_pid().ok())?;
+    find_terminal(pid, |pid| lookup_process(&mut system, pid))
+}
+
+/// The first known terminal emulator among `pid` and its ancestors
+fn find_terminal(
+    mut pid: sysinfo::Pid,
+    mut lookup: impl FnMut(sysinfo::Pid) -> Option<ProcessEntry>,
+) -> Option<String> {
+    // The depth limit guards against parent loops
+    for _ in 0..32 {
+        let process = lookup(pid)?;
+        let name = &process.name;
+        let name = name.strip_suffix(".exe").unwrap_or(name).to_lowercase();
+
+        if TERMINAL_SEARCH_STOPS.contains(&name.as_str()) {
+            return None;
+        }
+        if let Some((_, terminal)) = KNOWN_TERMINALS.iter().find(|(process, _)| *process == name) {
+            return Some(terminal.to_string());
+        }
+        pid = process.parent?;
+    }
+    None
+}
+
+/// PID of the tmux client attached to our session
+fn tmux_client_pid() -> Option<sysinfo::Pid> {
+    let output = crate::utils::execute_command_with_timeout(
+        "tmux",
+        &["display-message", "-p", "#{client_pid}"],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    parse_tmux_client_pid(&output)
+}
+
+/// The PID printed by `tmux display-message -p '#{client_pid}'`
+fn parse_tmux_client_pid(output: &str) -> Option<sysinfo::Pid> {
+    output.trim().parse::<usize>().ok().map(sysinfo::Pid::from)
+}
+
+/// Build the `user@hostname` title
+///
+/// The short hostname (without domain) matches original neofetch; `fqdn`
+/// keeps the fully qualified name instead.
+pub(super) fn format_title(username: &str, hostname: &str, fqdn: bool) -> String {
+    let hostname = if fqdn {
+        hostname
+    } else {
+        hostname.split('.').next().unwrap_or(hostname)
+    };
+    format!("{}@{}", username, hostname)
+}
+
+/// Format package counts according to the package manager display mode
+///
+/// `On` gives `1423 (apt), 12 (flatpak)`, `Off` keeps the per-manager
+/// counts without names and `Tiny` shows only the total.
+pub(super) fn format_packages(
+    counts: &[(usize, String)],
+    display: &PackageManagerDisplay,
+) -> String {
+    if counts.is_empty() {
+        return "Unknown".to_string();
+    }
+
+    match display {
+        PackageManagerDisplay::On => counts
+            .iter()
+            .map(|(count, manager)| format!("{} ({})", count, manager))
+            .collect::<Vec<_>>()
+            .join(", "),
+        PackageManagerDisplay::Off => counts
+            .iter()
+            .map(|(count, _)| count.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        PackageManagerDisplay::Tiny => counts
+            .iter()
+            .map(|(count, _)| count)
+            .sum::<usize>()
+            .to_string(),
+    }
+}
+
+/// Format used and total memory, e.g. `7421MiB / 15887MiB (47%)`
+pub(super) fn format_memory(used: u64, total: u64, unit: &MemoryUnit, percent: bool) -> String {
+    let unit = match unit {
+        MemoryUnit::Kib => "kib",
+        MemoryUnit::Mib => "mib",
+        MemoryUnit::Gib => "gib",
+    };
+
+    let mut memory = format!(
+        "{} / {}",
+        crate::utils::bytes_to_human_readable(used, unit),
+        crate::utils::bytes_to_human_readable(total, unit)
+    );
+
+    if percent && total > 0 {
+        let percentage = (used as f64 / total as f64 * 100.0).round();
+        memory.push_str(&format!(" ({:.0}%)", percentage));
+    }
+
+    memory
+}
+
+/// Format video memory for the GPU line, e.g. `8 GiB` or `512 MiB`
+pub(super) fn format_gpu_memory(bytes: u64) -> String {
+    const GIB: u64 = 1024 * 1024 * 1024;
+    if bytes >= GIB {
+        format!("{:.0} GiB", bytes as f64 / GIB as f64)
+    } else {
+        format!("{} MiB", bytes / (1024 * 1024))
+    }
+}
+
+/// Format a Celsius temperature in the requested unit, e.g. `45°C`
+pub(super) fn format_temperature(celsius: f64, unit: &CpuTemp) -> String {
+    match unit {
+        CpuTemp::F => format!("{:.0}°F", celsius * 9.0 / 5.0 + 32.0),
+        _ => format!("{:.0}°C", celsius),
+    }
+}
+
+/// Format a CPU frequency as a ` @ 3.600GHz` suffix
+///
+/// With `shorthand` trailing zeros are dropped (` @ 3.6GHz`).
+pub(super) fn format_cpu_speed(mhz: f64, shorthand: bool) -> String {
+    let ghz = format!("{:.3}", mhz / 1000.0);
+    let ghz = if shorthand {
+        ghz.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &ghz
+    };
+    format!(" @ {}GHz", ghz)
+}
+
+/// How a color block cell is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BlockStyle {
+    /// Spaces on a colored background
+    Background,
+    /// Full-block glyphs in a colored foreground
+    Foreground,
+}
+
+impl BlockStyle {
+    /// Character the cell is made of
+    pub(super) fn cell(self) -> &'static str {
+        match self {
+            BlockStyle::Background => " ",
+            BlockStyle::Foreground => "█",
+        }
+    }
+
+    /// Escape code selecting `color` (0-255) for this style
+    pub(super) fn code(self, color: u8) -> String {
+        match (self, color) {
+            (BlockStyle::Background, 0..=7) => format!("\x1b[4{}m", color),
+            (BlockStyle::Background, 8..=15) => format!("\x1b[10{}m", color - 8),
+            (BlockStyle::Background, _) => format!("\x1b[48;5;{}m", color),
+            (BlockStyle::Foreground, 0..=7) => format!("\x1b[3{}m", color),
+            (BlockStyle::Foreground, 8..=15) => format!("\x1b[9{}m", color - 8),
+            (BlockStyle::Foreground, _) => format!("\x1b[38;5;{}m", color),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn a_slow_package_manager_is_skipped() {
+        let probes: Vec<PackageProbe> = vec![
+            ("pacman", Box::new(|| Some(1843))),
+            (
+                "snap",
+                Box::new(|| {
+                    // Stands in for a snapd that takes seconds to answer
+                    count_package_lines(
+                        "sh",
+                        &["-c", "sleep 5; printf 'core\\nfirefox\\n'"],
+                        0,
+                        std::time::Duration::from_secs(10),
+                    )
+                }),
+            ),
+            ("flatpak", Box::new(|| Some(12))),
+            ("nix", Box::new(|| None)),
+        ];
+
+        let started = std::time::Instant::now();
+        let (counts, skipped) = run_package_probes(probes, std::time::Duration::from_millis(300));
+
+        assert_eq!(counts, [(1843, "pacman"), (12, "flatpak")]);
+        assert_eq!(skipped, ["snap"]);
+        assert!(
+            started.elapsed() < std::time::Duration::from_secs(2),
+            "{:?}",
+            started.elapsed()
+        );
+    }
+
+    #[test]
+    fn gpu_memory_units() {
+        const MIB: u64 = 1024 * 1024;
+        for (bytes, expected) in [
+            (512 * MIB, "512 MiB"),
+            (1024 * MIB - 1, "1023 MiB"),
+            (1024 * MIB, "1 GiB"),
+            (8176 * MIB, "8 GiB"),
+            (12 * 1024 * MIB, "12 GiB"),
+            (24564 * MIB, "24 GiB"),
+        ] {
+            assert_eq!(format_gpu_memory(bytes), expected, "{}", bytes);
+        }
+    }
+
+    #[test]
+    fn shell_names_from_process_names() {
+        for (process, expected) in [
+            ("bash", Some("bash")),
+            ("-bash", Some("bash")),
+            ("-zsh", Some("zsh")),
+            ("fish", Some("fish")),
+            ("pwsh.exe", Some("pwsh")),
+            ("cmd.exe", Some("cmd")),
+            ("nu", Some("nu")),
+            ("sudo", None),
+            ("gnome-terminal-", None),
+            ("bash5", None),
+            ("", None),
+        ] {
+            assert_eq!(shell_name(process), expected, "{:?}", process);
+        }
+    }
+
+    /// A process tree lookup over `(pid, name, exe, parent)` rows
+    fn process_table(
+        rows: &'static [(usize, &str, Option<&str>, usize)],
+    ) -> impl Fn(sysinfo::Pid) -> Option<ProcessEntry> {
+        move |pid| {
+            rows.iter()
+                .find(|(row, ..)| sysinfo::Pid::from(*row) == pid)
+                .map(|(_, name, exe, parent)| ProcessEntry {
+                    name: name.to_string(),
+                    exe: exe.map(str::to_string),
+                    parent: (*parent != 0).then(|| sysinfo::Pid::from(*parent)),
+                })
+        }
+    }
+
+    #[test]
+    fn shells_from_the_process_tree() {
+        let shell = |rows| find_parent_shell(sysinfo::Pid::from(100), process_table(rows));
+
+        assert_eq!(
+            shell(&[
+                (100, "neofetch", None, 50),
+                (50, "-zsh", Some("/usr/bin/zsh"), 1),
+            ])
+            .as_deref(),
+            Some("/usr/bin/zsh")
+        );
+        // Without the executable path the name will do
+        assert_eq!(
+            shell(&[(100, "neofetch", None, 50), (50, "-bash", None, 1)]).as_deref(),
+            Some("bash")
+        );
+        assert_eq!(
+            shell(&[
+                (100, "neofetch", None, 70),
+                (70, "env", Some("/usr/bin/env"), 60),
+                (60, "sudo", Some("/usr/bin/sudo"), 50),
+                (50, "fish", Some("/usr/bin/fish"), 1),
+            ])
+            .as_deref(),
+            Some("/usr/bin/fish")
+        );
+        // Started by something other than a shell
+        assert_eq!(
+            shell(&[
+                (100, "neofetch", None, 60),
+                (60, "python3", None, 50),
+                (50, "bash", None, 1),
+            ]),
+            None
+        );
+        assert_eq!(
+            shell(&[(100, "neofetch", None, 60), (60, "sudo", None, 0)]),
+            None
+        );
+        assert_eq!(shell(&[(100, "neofetch", None, 0)]), None);
+        assert_eq!(shell(&[]), None);
+    }
+
+    #[test]
+    fn terminals_from_the_process_tree() {
+        let terminal =
+            |pid: usize, rows| find_terminal(sysinfo::Pid::from(pid), process_table(rows));
+
+        // Linux truncates the name to 15 bytes
+        assert_eq!(
+            terminal(
+                100,
+                &[
+                    (100, "neofetch", None, 90),
+                    (90, "bash", None, 80),
+                    (80, "gnome-terminal-", None, 20),
+                    (20, "systemd", None, 1),
+                ]
+            )
+            .as_deref(),
+            Some("GNOME Terminal")
+        );
+
+        // Our ancestors end at the tmux server; its client leads to the
+        // terminal
+        let tmux = &[
+            (100, "neofetch", None, 90),
+            (90, "zsh", None, 80),
+            (80, "tmux: server", None, 20),
+            (20, "systemd", None, 1),
+            (300, "tmux: client", None, 290),
+            (290, "zsh", None, 280),
+            (280, "alacritty", None, 20),
+        ];
+        assert_eq!(terminal(100, tmux), None);
+        let client = parse_tmux_client_pid("300\n").unwrap();
+        assert_eq!(
+            find_terminal(client, process_table(tmux)).as_deref(),
+            Some("Alacritty")
+        );
+        assert_eq!(parse_tmux_client_pid(""), None);
+
+        // Over ssh the terminal is on another machine
+        assert_eq!(
+            terminal(
+                100,
+                &[
+                    (100, "neofetch", None, 90),
+                    (90, "bash", None, 80),
+                    (80, "sshd", None, 70),
+                    (70, "sshd", None, 1),
+                    (1, "xterm", None, 0),
+                ]
+            ),
+            None
+        );
+
+        // No known terminal before the tree runs out
+        assert_eq!(
+            terminal(
+                100,
+                &[
+                    (100, "neofetch", None, 90),
+                    (90, "bash", None, 80),
+                    (80, "code", None, 0),
+                ]
+            ),
+            None
+        );
+        assert_eq!(terminal(100, &[]), None);
+    }
+
+    #[test]
+    fn users_from_who() {
+        let users = |list: &[(&str, &[&str])]| -> Vec<(String, Vec<String>)> {
+            list.iter()
+                .map(|(name, sessions)| {
+                    let sessions = sessions.iter().map(|s| s.to_string()).collect();
+                    (name.to_string(), sessions)
+                })
+                .collect()
+        };
+
+        let linux = parse_who_users(include_str!("../../tests/fixtures/who/linux.txt"));
+        assert_eq!(
+            linux,
+            users(&[
+                ("alice", &["seat0", "tty2", "pts/1"]),
+                ("bob", &["pts/0", "pts/3"]),
+                ("carol", &["pts/2"]),
+            ])
+        );
+        assert_eq!(format_users(&linux, false), "alice, bob, carol");
+        assert_eq!(
+            format_users(&linux, true),
+            "alice (seat0 tty2 pts/1), bob (pts/0 pts/3), carol (pts/2)"
+        );
+
+        let macos = parse_who_users(include_str!("../../tests/fixtures/who/macos.txt"));
+        assert_eq!(
+            macos,
+            users(&[("alice", &["console", "ttys000"]), ("bob", &["ttys001"])])
+        );
+
+        for empty in ["", "\n", "  \n\n"] {
+            let nobody = parse_who_users(empty);
+            assert!(nobody.is_empty(), "{:?}", empty);
+            assert_eq!(format_users(&nobody, true), "Unknown");
+        }
+    }
+
+    #[test]
+    fn title_keeps_the_domain_only_with_fqdn() {
+        assert_eq!(
+            format_title("user", "laptop.corp.example.com", true),
+            "user@laptop.corp.example.com"
+        );
+        assert_eq!(
+            format_title("user", "laptop.corp.example.com", false),
+            "user@laptop"
+        );
+    }
+
+    #[test]
+    fn locale_precedence() {
+        let fallback = || Some("C.UTF-8".to_string());
+        for (vars, expected) in [
+            (&[][..], "C.UTF-8"),
+            (&[("LANG", "de_DE.UTF-8")], "de_DE.UTF-8"),
+            (&[("LC_CTYPE", "UTF-8")], "UTF-8"),
+            (
+                &[("LANG", "de_DE.UTF-8"), ("LC_CTYPE", "UTF-8")],
+                "de_DE.UTF-8",
+            ),
+            (
+                &[("LC_ALL", "fr_FR.UTF-8"), ("LANG", "de_DE.UTF-8")],
+                "fr_FR.UTF-8",
+            ),
+            (
+                &[("LC_MESSAGES", "en_GB.UTF-8"), ("LANG", "de_DE.UTF-8")],
+                "en_GB.UTF-8",
+            ),
+            (
+                &[
+                    ("LC_ALL", "fr_FR.UTF-8"),
+                    ("LC_MESSAGES", "en_GB.UTF-8"),
+                    ("LANG", "de_DE.UTF-8"),
+                    ("LC_CTYPE", "UTF-8"),
+                ],
+                "fr_FR.UTF-8",
+            ),
+            // Empty means unset, as for the C library
+            (&[("LC_ALL", ""), ("LANG", "de_DE.UTF-8")], "de_DE.UTF-8"),
+            (&[("LC_ALL", ""), ("LANG", "")], "C.UTF-8"),
+        ] {
+            let env = |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            };
+            assert_eq!(
+                pick_locale(env, fallback).as_deref(),
+                Some(expected),
+                "{:?}",
+                vars
+            );
+        }
+
+        assert_eq!(pick_locale(|_| Some(String::new()), || None), None);
+    }
+
+    #[test]
+    fn hostname_falls_back_to_the_environment() {
+        let some = |name: &str| Some(name.to_string());
+        for (system, env, expected) in [
+            (some("laptop"), some("other"), "laptop"),
+            (some("laptop"), None, "laptop"),
+            (some(""), some("laptop"), "laptop"),
+            (some("  "), some("laptop"), "laptop"),
+            (None, some("laptop"), "laptop"),
+            (some("localhost"), some("laptop"), "laptop"),
+            // localhost is still better than nothing
+            (some("localhost"), None, "localhost"),
+            (some("localhost"), some("localhost"), "localhost"),
+            (some("localhost"), some(""), "localhost"),
+            (some(""), some(""), "unknown"),
+            (None, None, "unknown"),
+        ] {
+            assert_eq!(
+                pick_hostname(system.clone(), env.clone()),
+                expected,
+                "{:?} / {:?}",
+                system,
+                env
+            );
+        }
+    }
+
+    #[test]
+    fn title_uses_the_picked_hostname() {
+        let hostname = pick_hostname(
+            Some("localhost".into()),
+            Some("build01.ci.example.org".into()),
+        );
+        assert_eq!(format_title("ci", &hostname, false), "ci@build01");
+        assert_eq!(
+            format_title("ci", &hostname, true),
+            "ci@build01.ci.example.org"
+        );
+    }
+
+    #[test]
+    fn temperature_converts_to_fahrenheit() {
+        assert_eq!(format_temperature(45.0, &CpuTemp::C), "45°C");
+        assert_eq!(format_temperature(45.0, &CpuTemp::F), "113°F");
+        assert_eq!(format_temperature(0.0, &CpuTemp::F), "32°F");
+        assert_eq!(format_temperature(-40.0, &CpuTemp::F), "-40°F");
+        assert_eq!(format_temperature(37.8, &CpuTemp::F), "100°F");
+    }
+
+    #[test]
+    fn block_codes_for_windows_terminal_and_conhost() {
+        assert_eq!(BlockStyle::Background.code(1), "\x1b[41m");
+        assert_eq!(BlockStyle::Background.code(9), "\x1b[101m");
+        assert_eq!(BlockStyle::Background.code(200), "\x1b[48;5;200m");
+        assert_eq!(BlockStyle::Background.cell(), " ");
+
+        assert_eq!(BlockStyle::Foreground.code(1), "\x1b[31m");
+        assert_eq!(BlockStyle::Foreground.code(9), "\x1b[91m");
+        assert_eq!(BlockStyle::Foreground.code(200), "\x1b[38;5;200m");
+        assert_eq!(BlockStyle::Foreground.cell(), "█");
+    }
+
+    #[test]
+    fn packages_in_each_display_mode() {
+        let counts = |list: &[(usize, &str)]| -> Vec<(usize, String)> {
+            list.iter()
+                .map(|(count, manager)| (*count, manager.to_string()))
+                .collect()
+        };
+        let one = counts(&[(1423, "dpkg")]);
+        let several = counts(&[(1423, "dpkg"), (12, "flatpak"), (4, "snap")]);
+
+        for (counts, display, expected) in [
+            (&one, PackageManagerDisplay::On, "1423 (dpkg)"),
+            (&one, PackageManagerDisplay::Tiny, "1423"),
+            (&one, PackageManagerDisplay::Off, "1423"),
+            (
+                &several,
+                PackageManagerDisplay::On,
+                "1423 (dpkg), 12 (flatpak), 4 (snap)",
+            ),
+            (&several, PackageManagerDisplay::Tiny, "1439"),
+            (&several, PackageManagerDisplay::Off, "1423, 12, 4"),
+            (&vec![], PackageManagerDisplay::On, "Unknown"),
+            (&vec![], PackageManagerDisplay::Tiny, "Unknown"),
+            (&vec![], PackageManagerDisplay::Off, "Unknown"),
+        ] {
+            assert_eq!(
+                format_packages(counts, &display),
+                expected,
+                "{:?} {:?}",
+                counts,
+                display
+            );
+        }
+    }
+
+    #[test]
+    fn memory_in_each_unit() {
+        const MIB: u64 = 1024 * 1024;
+        let (used, total) = (7421 * MIB, 15887 * MIB);
+
+        assert_eq!(
+            format_memory(used, total, &MemoryUnit::Kib, false),
+            "7599104KiB / 16268288KiB"
+        );
+        assert_eq!(
+            format_memory(used, total, &MemoryUnit::Mib, false),
+            "7421MiB / 15887MiB"
+        );
+        assert_eq!(
+            format_memory(used, total, &MemoryUnit::Gib, false),
+            "7.25GiB / 15.51GiB"
+        );
+    }
+
+    #[test]
+    fn memory_percentage_is_rounded() {
+        const MIB: u64 = 1024 * 1024;
+
+        assert_eq!(
+            format_memory(7421 * MIB, 15887 * MIB, &MemoryUnit::Mib, true),
+            "7421MiB / 15887MiB (47%)"
+        );
+        assert_eq!(
+            format_memory(2 * MIB, 3 * MIB, &MemoryUnit::Mib, true),
+            "2MiB / 3MiB (67%)"
+        );
+        assert_eq!(format_memory(0, 0, &MemoryUnit::Mib, true), "0MiB / 0MiB");
+    }
+}
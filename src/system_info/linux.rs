@@ -0,0 +1,2029 @@
+//! Linux system information
+//!
+//! Mostly read straight from `/proc`, `/sys` and os-release, with display
+//! and package tools queried where the kernel can't tell.
+
+use super::common::{clean_host_part, count_package_lines, PackageProbe};
+use super::{OsInfo, PlatformGather, Services};
+use crate::config::SpeedType;
+use std::time::Duration;
+
+/// The OS version from os-release, with the codename if VERSION lacks it
+///
+/// VERSION usually carries the codename already, e.g. "12 (bookworm)" or
+/// "22.04.4 LTS (Jammy Jellyfish)", so it's only appended when VERSION
+/// neither mentions it (in any case) nor has a parenthetical of its own.
+fn release_version(os_release: &std::collections::HashMap<String, String>) -> String {
+    let version = os_release
+        .get("VERSION")
+        .or_else(|| os_release.get("VERSION_ID"))
+        .cloned()
+        .unwrap_or_default();
+    let codename = match os_release.get("VERSION_CODENAME") {
+        Some(codename) if !codename.is_empty() => codename,
+        _ => return version,
+    };
+
+    if version.contains('(') || version.to_lowercase().contains(&codename.to_lowercase()) {
+        version
+    } else if version.is_empty() {
+        codename.clone()
+    } else {
+        format!("{} ({})", version, codename)
+    }
+}
+
+/// Linux implementation of [`PlatformGather`]
+pub(super) struct Linux;
+
+impl PlatformGather for Linux {
+    fn os(os: &mut OsInfo) {
+        let os_release = crate::utils::read_os_release();
+        os.distro_id = os_release.get("ID").cloned().unwrap_or_default();
+        os.distro_id_like = os_release.get("ID_LIKE").cloned().unwrap_or_default();
+
+        if let Some(os_name) = os_release.get("NAME") {
+            os.name = os_name.clone();
+            os.version_id = os_release.get("VERSION_ID").cloned().unwrap_or_default();
+            os.version = release_version(&os_release);
+        } else {
+            // No os-release NAME, PRETTY_NAME or lsb-release already carry the version
+            let pretty_name = crate::utils::detect_os();
+            if pretty_name != "Linux" {
+                os.name = pretty_name;
+                os.version.clear();
+                os.version_id.clear();
+            }
+        }
+    }
+
+    fn host() -> Option<String> {
+        // WSL has no DMI of its own; ask Windows for the machine model
+        if crate::utils::is_wsl() {
+            if let Some(host) = wsl_windows_host() {
+                return Some(host);
+            }
+        }
+
+        host_from(std::path::Path::new("/"))
+    }
+
+    fn package_probes(timeout: Duration) -> Vec<PackageProbe> {
+        LINUX_PACKAGE_MANAGERS
+            .iter()
+            .map(|(manager, command, args, header)| -> PackageProbe {
+                (
+                    manager,
+                    Box::new(move || count_package_lines(command, args, *header, timeout)),
+                )
+            })
+            .collect()
+    }
+
+    /// apk, Portage and the Nix user profile need more than a table entry
+    fn package_counts(timeout: Duration) -> Vec<(usize, &'static str)> {
+        let mut package_managers = Vec::new();
+
+        // Reading apk's database is quicker than spawning apk
+        if let Ok(installed) = std::fs::read_to_string("/lib/apk/db/installed") {
+            let count = count_apk_packages(&installed);
+            if count > 0 {
+                package_managers.push((count, "apk"));
+            }
+        }
+
+        // Portage keeps one directory per installed package
+        if let Ok(categories) = std::fs::read_dir("/var/db/pkg") {
+            let count: usize = categories
+                .flatten()
+                .filter_map(|category| std::fs::read_dir(category.path()).ok())
+                .map(|packages| packages.count())
+                .sum();
+            if count > 0 {
+                package_managers.push((count, "emerge"));
+            }
+        }
+
+        // Nix user profile, alongside the system profile from the table
+        if let Some(profile) = dirs::home_dir().map(|home| home.join(".nix-profile")) {
+            if profile.exists() {
+                let profile = profile.to_string_lossy();
+                if let Some(count) = count_package_lines(
+                    "nix-store",
+                    &["-q", "--requisites", profile.as_ref()],
+                    0,
+                    timeout,
+                ) {
+                    package_managers.push((count, "nix-user"));
+                }
+            }
+        }
+
+        package_managers
+    }
+
+    fn resolution(refresh_rate: bool) -> Option<String> {
+        // Wayland compositors don't answer xrandr, so ask them directly
+        if is_wayland_session(env_var) {
+            if crate::utils::command_exists("wlr-randr") {
+                if let Ok(output) = crate::utils::execute_command_with_timeout(
+                    "wlr-randr",
+                    &[],
+                    crate::utils::COMMAND_TIMEOUT,
+                ) {
+                    let resolutions = parse_wlr_randr(&output, refresh_rate);
+                    if !resolutions.is_empty() {
+                        return Some(resolutions.join(", "));
+                    }
+                }
+            }
+
+            if crate::utils::command_exists("swaymsg") {
+                if let Ok(output) = crate::utils::execute_command_with_timeout(
+                    "swaymsg",
+                    &["-t", "get_outputs", "-r"],
+                    crate::utils::COMMAND_TIMEOUT,
+                ) {
+                    let resolutions = parse_swaymsg_outputs(&output, refresh_rate);
+                    if !resolutions.is_empty() {
+                        return Some(resolutions.join(", "));
+                    }
+                }
+            }
+        }
+
+        // Try xrandr next
+        if let Ok(output) = crate::utils::output_with_timeout(
+            crate::utils::command("xrandr").args(["--query"]),
+            crate::utils::COMMAND_TIMEOUT,
+        ) {
+            if output.status.success() {
+                let output_str = String::from_utf8_lossy(&output.stdout);
+                let resolutions = parse_xrandr(&output_str, refresh_rate);
+
+                if !resolutions.is_empty() {
+                    return Some(resolutions.join(", "));
+                }
+            }
+        }
+
+        // The kernel's DRM connectors work without any display server tools
+        let resolutions = read_drm_modes(std::path::Path::new("/sys/class/drm"));
+        if !resolutions.is_empty() {
+            return Some(resolutions.join(", "));
+        }
+
+        None
+    }
+
+    fn de() -> String {
+        let raw = if let Ok(de) = std::env::var("XDG_CURRENT_DESKTOP") {
+            de
+        } else if let Ok(de) = std::env::var("DESKTOP_SESSION") {
+            de
+        } else if std::env::var("GNOME_DESKTOP_SESSION_ID").is_ok() {
+            "GNOME".to_string()
+        } else if std::env::var("KDE_FULL_SESSION").is_ok() {
+            "KDE".to_string()
+        } else {
+            return "Unknown".to_string();
+        };
+
+        let (name, version_command) = match normalize_desktop(&raw) {
+            Some(desktop) => desktop,
+            None => return "Unknown".to_string(),
+        };
+
+        // Only the detected desktop's version tool is ever run
+        let mut de = name;
+        if let Some(version) = version_command.and_then(desktop_version) {
+            de.push(' ');
+            de.push_str(&version);
+        }
+        match std::env::var("XDG_SESSION_TYPE").as_deref() {
+            Ok("wayland") => de.push_str(" (Wayland)"),
+            Ok("x11") => de.push_str(" (X11)"),
+            _ => {}
+        }
+        de
+    }
+
+    fn wm() -> String {
+        if is_wayland_session(env_var) {
+            if let Some(compositor) = detect_wayland_compositor(env_var, running_processes) {
+                return compositor.to_string();
+            }
+        }
+
+        if let Some(wm) = x11_wm_name() {
+            return wm;
+        }
+
+        wm_from_env(env_var)
+    }
+
+    fn wm_theme(wm: &str) -> Option<String> {
+        // Only the detected WM's settings are read
+        let wm = wm.to_lowercase();
+        let theme = if wm.contains("muffin") {
+            gsettings_string("org.cinnamon.desktop.wm.preferences", "theme")
+        } else if wm.contains("marco") {
+            gsettings_string("org.mate.Marco.general", "theme")
+        } else if wm.contains("mutter") || wm.contains("metacity") {
+            gsettings_string("org.gnome.desktop.wm.preferences", "theme")
+        } else if wm.contains("kwin") {
+            kwin_decoration_theme(&dirs::config_dir()?)
+        } else if wm.contains("xfwm") {
+            crate::utils::execute_command_with_timeout(
+                "xfconf-query",
+                &["-c", "xfwm4", "-p", "/general/theme"],
+                crate::utils::COMMAND_TIMEOUT,
+            )
+            .ok()
+        } else if wm.contains("openbox") {
+            openbox_theme(&dirs::config_dir()?)
+        } else {
+            None
+        };
+        theme.filter(|theme| !theme.is_empty())
+    }
+
+    fn cpu_name() -> Option<String> {
+        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+        let compatible = std::fs::read("/proc/device-tree/compatible").ok();
+        parse_cpuinfo_name(&cpuinfo, compatible.as_deref())
+    }
+
+    fn cpu_temp_celsius() -> Option<f64> {
+        read_hwmon_cpu_temp(std::path::Path::new("/sys/class/hwmon"))
+    }
+
+    fn cpu_frequency_mhz(speed_type: &SpeedType) -> Option<f64> {
+        let file = match speed_type {
+            SpeedType::Scaling => "scaling_cur_freq",
+            SpeedType::Base => "base_frequency",
+            SpeedType::Max => "cpuinfo_max_freq",
+            SpeedType::Bios => "bios_limit",
+        };
+
+        // cpufreq reports frequencies in kHz
+        std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu0/cpufreq/{}", file))
+            .ok()
+            .and_then(|content| content.trim().parse::<f64>().ok())
+            .filter(|khz| *khz > 0.0)
+            .map(|khz| khz / 1000.0)
+    }
+
+    fn gpu_memory_bytes() -> Option<u64> {
+        amdgpu_vram_bytes(std::path::Path::new("/sys/class/drm")).or_else(nvidia_vram_bytes)
+    }
+
+    /// sysinfo can count reclaimable cache as used, so compute it like neofetch
+    fn memory() -> Option<(u64, u64)> {
+        std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|content| parse_meminfo(&content))
+    }
+
+    fn battery() -> Option<(u8, Option<String>)> {
+        read_battery(std::path::Path::new("/sys/class/power_supply"))
+    }
+
+    fn power_profile() -> Option<String> {
+        power_profile_from(
+            std::path::Path::new("/sys/firmware/acpi/platform_profile"),
+            || {
+                crate::utils::command_exists("powerprofilesctl")
+                    .then(|| {
+                        crate::utils::execute_command_with_timeout(
+                            "powerprofilesctl",
+                            &["get"],
+                            crate::utils::COMMAND_TIMEOUT,
+                        )
+                        .ok()
+                    })
+                    .flatten()
+            },
+        )
+    }
+
+    fn services() -> Option<Services> {
+        if std::path::Path::new("/run/systemd/system").exists() {
+            let output = crate::utils::output_with_timeout(
+                crate::utils::command("systemctl").args(["--failed", "--no-legend", "--plain"]),
+                crate::utils::COMMAND_TIMEOUT,
+            )
+            .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            return Some(Services {
+                count: count_failed_units(&String::from_utf8_lossy(&output.stdout)),
+                failed: true,
+            });
+        }
+
+        // runit links enabled services into the runsvdir, OpenRC into a runlevel
+        [
+            "/var/service",
+            "/etc/runit/runsvdir/default",
+            "/etc/runlevels/default",
+        ]
+        .iter()
+        .find_map(|dir| std::fs::read_dir(dir).ok())
+        .map(|entries| Services {
+            count: entries.count(),
+            failed: false,
+        })
+    }
+
+    fn install_time() -> Option<u64> {
+        // The root filesystem's birth time (statx) is the most direct answer
+        let created = |path: &str| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.created())
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_secs())
+                // Images built without timestamps report the epoch
+                .filter(|secs| *secs > 0)
+        };
+        created("/")
+            .or_else(|| created("/var/log/installer"))
+            .or_else(|| {
+                pacman_log_start(std::path::Path::new("/var/log/pacman.log"), &chrono::Local)
+            })
+    }
+
+    fn locale() -> Option<String> {
+        std::fs::read_to_string("/etc/locale.conf")
+            .ok()
+            .and_then(|content| crate::utils::parse_os_release(&content).remove("LANG"))
+            .filter(|value| !value.is_empty())
+    }
+
+    fn uptime_seconds() -> Option<u64> {
+        let content = std::fs::read_to_string("/proc/uptime").ok()?;
+        let seconds = content.split_whitespace().next()?.parse::<f64>().ok()?;
+        Some(seconds as u64)
+    }
+}
+
+/// Linux package managers queried by a command: name, command, arguments
+/// and the number of header lines to skip, in display order
+///
+/// dnf and zypper read the rpm database, so their packages are counted
+/// once, as rpm.
+const LINUX_PACKAGE_MANAGERS: &[(&str, &str, &[&str], usize)] = &[
+    ("apt", "dpkg-query", &["-f", "${binary:Package}\n", "-W"], 0),
+    ("pacman", "pacman", &["-Qq"], 0),
+    ("rpm", "rpm", &["-qa"], 0),
+    ("xbps", "xbps-query", &["-l"], 0),
+    ("eopkg", "eopkg", &["list-installed"], 0),
+    ("guix", "guix", &["package", "--list-installed"], 0),
+    (
+        "nix-system",
+        "nix-store",
+        &["-q", "--requisites", "/run/current-system/sw"],
+        0,
+    ),
+    ("flatpak", "flatpak", &["list", "--app"], 0),
+    ("snap", "snap", &["list"], 1),
+];
+
+/// Desktop environments: `XDG_CURRENT_DESKTOP` entry (without any `X-`
+/// prefix), the name to show and the command that prints its version
+const DESKTOPS: &[(&str, &str, Option<&str>)] = &[
+    ("GNOME", "GNOME", Some("gnome-shell")),
+    ("KDE", "Plasma", Some("plasmashell")),
+    ("Plasma", "Plasma", Some("plasmashell")),
+    ("Cinnamon", "Cinnamon", Some("cinnamon")),
+    ("XFCE", "Xfce", Some("xfce4-session")),
+    ("MATE", "MATE", Some("mate-session")),
+    ("LXQt", "LXQt", Some("lxqt-session")),
+    ("LXDE", "LXDE", None),
+    ("Budgie", "Budgie", Some("budgie-desktop")),
+    ("Unity", "Unity", Some("unity")),
+    ("Pantheon", "Pantheon", None),
+    ("Deepin", "Deepin", None),
+    ("COSMIC", "COSMIC", None),
+    ("Enlightenment", "Enlightenment", Some("enlightenment")),
+];
+
+/// Turn a raw desktop string into a display name and its version command
+///
+/// `XDG_CURRENT_DESKTOP` is a colon-separated list such as `ubuntu:GNOME`
+/// or `X-Cinnamon`; the first known entry wins, otherwise the first entry
+/// is shown as is.
+fn normalize_desktop(raw: &str) -> Option<(String, Option<&'static str>)> {
+    let entries: Vec<&str> = raw
+        .split(':')
+        .map(|entry| entry.trim())
+        .map(|entry| entry.strip_prefix("X-").unwrap_or(entry))
+        .filter(|entry| !entry.is_empty())
+        .collect();
+
+    let known = entries.iter().find_map(|entry| {
+        DESKTOPS
+            .iter()
+            .find(|(key, _, _)| key.eq_ignore_ascii_case(entry))
+    });
+    match known {
+        Some((_, name, command)) => Some((name.to_string(), *command)),
+        None => entries.first().map(|entry| (entry.to_string(), None)),
+    }
+}
+
+/// Ask a desktop's shell or session binary for its version
+///
+/// e.g. `GNOME Shell 46.2` or `xfce4-session 4.18.1 (Xfce 4.18)`; the first
+/// word starting with a digit is the version.
+fn desktop_version(command: &str) -> Option<String> {
+    if !crate::utils::command_exists(command) {
+        return None;
+    }
+    let output = crate::utils::execute_command_with_timeout(
+        command,
+        &["--version"],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    first_version(&output)
+}
+
+/// The first word of `--version` output that starts with a digit
+fn first_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|word| word.trim_end_matches(',').to_string())
+}
+
+/// Wayland compositors: process name (as in `/proc/<pid>/comm`) and the
+/// name to show
+const WAYLAND_COMPOSITORS: &[(&str, &str)] = &[
+    ("Hyprland", "Hyprland"),
+    ("sway", "sway"),
+    ("river", "river"),
+    ("wayfire", "Wayfire"),
+    ("niri", "niri"),
+    ("labwc", "labwc"),
+    ("weston", "Weston"),
+    ("gnome-shell", "Mutter"),
+    ("kwin_wayland", "KWin"),
+];
+
+/// A set, UTF-8 environment variable
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Whether we're running inside a Wayland session
+fn is_wayland_session(env: impl Fn(&str) -> Option<String>) -> bool {
+    env("WAYLAND_DISPLAY").is_some() || env("XDG_SESSION_TYPE").as_deref() == Some("wayland")
+}
+
+/// Identify the Wayland compositor
+///
+/// Compositor-specific sockets are the most reliable sign, then
+/// `XDG_CURRENT_DESKTOP`, then the process list.
+fn detect_wayland_compositor(
+    env: impl Fn(&str) -> Option<String>,
+    processes: impl FnOnce() -> Vec<String>,
+) -> Option<&'static str> {
+    if env("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        return Some("Hyprland");
+    }
+    if env("SWAYSOCK").is_some() {
+        return Some("sway");
+    }
+
+    // Desktops are colon separated, e.g. "ubuntu:GNOME"
+    if let Some(desktops) = env("XDG_CURRENT_DESKTOP") {
+        for desktop in desktops.split(':') {
+            let compositor = match desktop.to_lowercase().as_str() {
+                "gnome" => Some("Mutter"),
+                "kde" => Some("KWin"),
+                desktop => WAYLAND_COMPOSITORS
+                    .iter()
+                    .find(|(process, _)| process.eq_ignore_ascii_case(desktop))
+                    .map(|(_, name)| *name),
+            };
+            if compositor.is_some() {
+                return compositor;
+            }
+        }
+    }
+
+    let processes = processes();
+    WAYLAND_COMPOSITORS
+        .iter()
+        .find(|(process, _)| processes.iter().any(|comm| comm == process))
+        .map(|(_, name)| *name)
+}
+
+/// Names of running processes, as in `/proc/<pid>/comm`
+fn running_processes() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+        })
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("comm")).ok())
+        .map(|comm| comm.trim().to_string())
+        .collect()
+}
+
+/// Guess the window manager from session variables when X can't tell us
+fn wm_from_env(env: impl Fn(&str) -> Option<String>) -> String {
+    if env("GNOME_DESKTOP_SESSION_ID").is_some() {
+        "Mutter".to_string()
+    } else if env("KDE_FULL_SESSION").is_some() {
+        "KWin".to_string()
+    } else if let Some(wm) = env("DESKTOP_SESSION") {
+        match wm.to_lowercase().as_str() {
+            "i3" => "i3".to_string(),
+            "awesome" => "awesome".to_string(),
+            "bspwm" => "bspwm".to_string(),
+            "openbox" => "Openbox".to_string(),
+            _ => wm,
+        }
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+/// Ask the X server which window manager is running
+///
+/// EWMH window managers set `_NET_SUPPORTING_WM_CHECK` on the root window
+/// to a window whose `_NET_WM_NAME` is their name. Needs `xprop` and an
+/// X connection; without either the caller falls back to env heuristics.
+fn x11_wm_name() -> Option<String> {
+    if std::env::var_os("DISPLAY").is_none() || !crate::utils::command_exists("xprop") {
+        return None;
+    }
+
+    let check = crate::utils::execute_command_with_timeout(
+        "xprop",
+        &["-root", "-notype", "_NET_SUPPORTING_WM_CHECK"],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    let id = parse_wm_check_window(&check)?;
+
+    let name = crate::utils::execute_command_with_timeout(
+        "xprop",
+        &["-id", id, "-notype", "-len", "100", "_NET_WM_NAME"],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    parse_wm_name(&name)
+}
+
+/// The window id from `_NET_SUPPORTING_WM_CHECK: window id # 0x1200003`
+fn parse_wm_check_window(xprop: &str) -> Option<&str> {
+    xprop
+        .split_whitespace()
+        .find(|word| word.starts_with("0x"))
+        .map(|id| id.trim_end_matches(','))
+}
+
+/// The name from `_NET_WM_NAME = "i3"`, with GNOME Shell shown as Mutter
+fn parse_wm_name(xprop: &str) -> Option<String> {
+    let name = xprop.split_once('=')?.1.trim().trim_matches('"').trim();
+    Some(match name {
+        "" => return None,
+        "GNOME Shell" => "Mutter".to_string(),
+        name => name.to_string(),
+    })
+}
+
+/// ARM core names by `CPU part`, for the cores `CPU implementer 0x41` makes
+const ARM_CORES: &[(u32, &str)] = &[
+    (0xd03, "Cortex-A53"),
+    (0xd04, "Cortex-A35"),
+    (0xd05, "Cortex-A55"),
+    (0xd07, "Cortex-A57"),
+    (0xd08, "Cortex-A72"),
+    (0xd09, "Cortex-A73"),
+    (0xd0a, "Cortex-A75"),
+    (0xd0b, "Cortex-A76"),
+    (0xd0c, "Neoverse-N1"),
+    (0xd0d, "Cortex-A77"),
+    (0xd40, "Neoverse-V1"),
+    (0xd41, "Cortex-A78"),
+    (0xd44, "Cortex-X1"),
+    (0xd46, "Cortex-A510"),
+    (0xd47, "Cortex-A710"),
+    (0xd48, "Cortex-X2"),
+    (0xd49, "Neoverse-N2"),
+    (0xd4f, "Neoverse-V2"),
+];
+
+/// Pick the CPU name out of `/proc/cpuinfo`
+///
+/// x86 has `model name`. On ARM that's a generic `ARMv7 Processor rev 4`
+/// if it's there at all, so the SoC from `Hardware` or the device tree's
+/// `compatible` list (e.g. `brcm,bcm2711`) wins, and failing that the core
+/// type from `CPU implementer`/`CPU part`.
+fn parse_cpuinfo_name(cpuinfo: &str, compatible: Option<&[u8]>) -> Option<String> {
+    let field = |key: &str| {
+        cpuinfo.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let value = value.trim();
+            (name.trim() == key && !value.is_empty()).then(|| value.to_string())
+        })
+    };
+
+    // Like original neofetch, the SoC beats ARM's generic model name
+    if let Some(hardware) = field("Hardware") {
+        return Some(hardware);
+    }
+    let model = field("model name");
+    if let Some(model) = model.as_ref().filter(|model| !model.starts_with("ARMv")) {
+        return Some(model.clone());
+    }
+
+    // NUL-separated, most specific first; the last entry is the SoC
+    let soc = compatible.and_then(|compatible| {
+        String::from_utf8_lossy(compatible)
+            .split('\0')
+            .rfind(|entry| !entry.is_empty())
+            .and_then(|entry| entry.split(',').nth(1))
+            .map(str::to_uppercase)
+    });
+    if soc.is_some() {
+        return soc;
+    }
+
+    let hex = |key: &str| {
+        field(key).and_then(|value| u32::from_str_radix(value.trim_start_matches("0x"), 16).ok())
+    };
+    let core = match (hex("CPU implementer"), hex("CPU part")) {
+        (Some(0x41), Some(part)) => ARM_CORES
+            .iter()
+            .find(|(id, _)| *id == part)
+            .map(|(_, core)| format!("ARM {}", core)),
+        _ => None,
+    };
+    core.or(model)
+}
+
+/// Manufacturer and model of the Windows machine, through WSL interop
+fn wsl_windows_host() -> Option<String> {
+    let output = crate::utils::execute_command_with_timeout(
+        "powershell.exe",
+        &[
+            "-NoProfile",
+            "-Command",
+            "$s = Get-CimInstance Win32_ComputerSystem; $s.Manufacturer; $s.Model",
+        ],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+
+    let mut lines = output.lines();
+    let manufacturer = lines.next().and_then(clean_host_part);
+    let model = lines.next().and_then(clean_host_part)?;
+    Some(match manufacturer {
+        Some(manufacturer) if !model.starts_with(&manufacturer) => {
+            format!("{} {}", manufacturer, model)
+        }
+        _ => model,
+    })
+}
+
+/// The machine model from DMI or the device tree under `root`
+fn host_from(root: &std::path::Path) -> Option<String> {
+    let dmi = |file: &str| {
+        std::fs::read_to_string(root.join("sys/devices/virtual/dmi/id").join(file))
+            .ok()
+            .and_then(|content| clean_host_part(&content))
+    };
+
+    // Prefix the vendor like upstream neofetch, e.g. "Dell Inc. XPS 15 9500"
+    let with_vendor = |vendor: Option<String>, model: String| match vendor {
+        Some(vendor) if !model.starts_with(&vendor) => format!("{} {}", vendor, model),
+        _ => model,
+    };
+
+    if let Some(product) = dmi("product_name") {
+        // The version is often the real model, e.g. "ThinkPad T480"
+        let product = match dmi("product_version") {
+            Some(version) if !product.contains(&version) => {
+                format!("{} {}", product, version)
+            }
+            _ => product,
+        };
+        return Some(with_vendor(dmi("sys_vendor"), product));
+    }
+
+    if let Some(board) = dmi("board_name") {
+        return Some(with_vendor(dmi("board_vendor"), board));
+    }
+
+    // ARM boards like the Raspberry Pi describe themselves in the device
+    // tree instead of DMI
+    [
+        "proc/device-tree/model",
+        "sys/firmware/devicetree/base/model",
+    ]
+    .iter()
+    .filter_map(|path| std::fs::read_to_string(root.join(path)).ok())
+    .find_map(|model| clean_host_part(model.trim_end_matches('\0')))
+}
+
+/// Smallest VRAM counted as a dedicated card
+///
+/// AMD APUs report their firmware carve-out from system memory in the same
+/// file, usually 512 MiB or less.
+const MIN_DEDICATED_VRAM: u64 = 1024 * 1024 * 1024;
+
+/// Largest VRAM size reported by amdgpu in sysfs, under `drm_root`
+fn amdgpu_vram_bytes(drm_root: &std::path::Path) -> Option<u64> {
+    std::fs::read_dir(drm_root)
+        .ok()?
+        .flatten()
+        // Skip connectors such as card0-DP-1
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("card") && !name.contains('-')
+        })
+        .filter_map(|entry| {
+            std::fs::read_to_string(entry.path().join("device/mem_info_vram_total")).ok()
+        })
+        .filter_map(|bytes| bytes.trim().parse::<u64>().ok())
+        .filter(|bytes| *bytes >= MIN_DEDICATED_VRAM)
+        .max()
+}
+
+/// VRAM of the first NVIDIA card, from `nvidia-smi`
+fn nvidia_vram_bytes() -> Option<u64> {
+    if !crate::utils::command_exists("nvidia-smi") {
+        return None;
+    }
+    let output = crate::utils::execute_command_with_timeout(
+        "nvidia-smi",
+        &["--query-gpu=memory.total", "--format=csv,noheader,nounits"],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    parse_nvidia_vram(&output)
+}
+
+/// The first card's VRAM from `nvidia-smi --query-gpu=memory.total`
+///
+/// One line per card, in MiB without the unit.
+fn parse_nvidia_vram(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.trim().parse::<u64>().ok())
+        .filter(|mib| *mib > 0)
+        .map(|mib| mib * 1024 * 1024)
+}
+
+/// Packages in apk's `installed` database, one `P:<name>` line each
+fn count_apk_packages(installed: &str) -> usize {
+    installed
+        .lines()
+        .filter(|line| line.starts_with("P:"))
+        .count()
+}
+
+/// Time of the first entry in pacman's log, i.e. the Arch install
+///
+/// Stamps without an offset are in the time zone `local`.
+fn pacman_log_start<Tz: chrono::TimeZone>(path: &std::path::Path, local: &Tz) -> Option<u64> {
+    use std::io::BufRead;
+
+    let log = std::fs::File::open(path).ok()?;
+    let first = std::io::BufReader::new(log).lines().next()?.ok()?;
+    let (stamp, _) = first.strip_prefix('[')?.split_once(']')?;
+
+    // "[2021-03-14T10:22:33+0100] [PACMAN] Running 'pacman -Syu'", or
+    // "[2016-02-23 11:43] Running 'pacman -Syu'" in older logs
+    let timestamp = match chrono::DateTime::parse_from_str(stamp, "%Y-%m-%dT%H:%M:%S%z") {
+        Ok(time) => time.timestamp(),
+        Err(_) => chrono::NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M")
+            .ok()
+            .and_then(|time| time.and_local_timezone(local.clone()).earliest())?
+            .timestamp(),
+    };
+    u64::try_from(timestamp).ok()
+}
+
+/// Read a string key with `gsettings`, without GVariant's quotes
+fn gsettings_string(schema: &str, key: &str) -> Option<String> {
+    if !crate::utils::command_exists("gsettings") {
+        return None;
+    }
+    let value = crate::utils::execute_command_with_timeout(
+        "gsettings",
+        &["get", schema, key],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+    Some(unquote_gvariant(&value).to_string())
+}
+
+/// A GVariant string as printed by `gsettings`, e.g. `'Adwaita'`
+fn unquote_gvariant(value: &str) -> &str {
+    value.trim().trim_matches('\'')
+}
+
+/// KWin's window decoration from the `[org.kde.kdecoration2]` group of
+/// `kwinrc` in `config_dir`
+fn kwin_decoration_theme(config_dir: &std::path::Path) -> Option<String> {
+    let kwinrc = std::fs::read_to_string(config_dir.join("kwinrc")).ok()?;
+    let mut in_group = false;
+    for line in kwinrc.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_group = line == "[org.kde.kdecoration2]";
+        } else if in_group {
+            if let Some(theme) = line.strip_prefix("theme=") {
+                // Aurorae themes are stored as "__aurorae__svg__<name>"
+                let theme = theme.rsplit("__").next().unwrap_or(theme);
+                return Some(theme.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// The `<theme><name>` from Openbox's config in `config_dir`, including the
+/// LXDE and LXQt variants of it
+fn openbox_theme(config_dir: &std::path::Path) -> Option<String> {
+    let config_dir = config_dir.join("openbox");
+    let rc = ["rc.xml", "lxde-rc.xml", "lxqt-rc.xml"]
+        .iter()
+        .find_map(|file| std::fs::read_to_string(config_dir.join(file)).ok())?;
+
+    let theme = &rc[rc.find("<theme>")?..];
+    let name = &theme[theme.find("<name>")? + "<name>".len()..];
+    let name = name[..name.find("</name>")?].trim();
+    Some(name.to_string())
+}
+
+/// Unit file suffixes, which tell a unit line from any summary text
+const UNIT_TYPES: &[&str] = &[
+    ".service",
+    ".socket",
+    ".target",
+    ".device",
+    ".mount",
+    ".automount",
+    ".swap",
+    ".timer",
+    ".path",
+    ".slice",
+    ".scope",
+];
+
+/// Count the units listed by `systemctl --failed --no-legend`
+///
+/// Only lines naming a unit count. Some systemd versions still print a
+/// "0 loaded units listed." summary when nothing failed, in the user's
+/// language if the locale isn't forced.
+fn count_failed_units(output: &str) -> usize {
+    output
+        .lines()
+        .filter_map(|line| {
+            line.split_whitespace()
+                .find(|word| !word.starts_with(|c: char| !c.is_alphanumeric()))
+        })
+        .filter(|unit| UNIT_TYPES.iter().any(|suffix| unit.ends_with(suffix)))
+        .count()
+}
+
+/// Parse `/proc/meminfo` into (used, total) bytes
+///
+/// Used memory is `MemTotal - MemFree - Buffers - Cached - SReclaimable +
+/// Shmem`, the same formula as original neofetch and `free`.
+fn parse_meminfo(content: &str) -> Option<(u64, u64)> {
+    let mut total = None;
+    let mut free = 0;
+    let mut buffers = 0;
+    let mut cached = 0;
+    let mut reclaimable = 0;
+    let mut shmem = 0;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(kib) = value.parse::<u64>() else {
+            continue;
+        };
+
+        match key {
+            "MemTotal:" => total = Some(kib),
+            "MemFree:" => free = kib,
+            "Buffers:" => buffers = kib,
+            "Cached:" => cached = kib,
+            "SReclaimable:" => reclaimable = kib,
+            "Shmem:" => shmem = kib,
+            _ => {}
+        }
+    }
+
+    let total = total?;
+    let used = (total + shmem).saturating_sub(free + buffers + cached + reclaimable);
+    Some((used * 1024, total * 1024))
+}
+
+/// hwmon drivers that report the CPU package temperature
+const CPU_HWMON_DRIVERS: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal"];
+
+/// Sensor labels that identify the CPU package temperature
+const CPU_SENSOR_LABELS: &[&str] = &["Package id 0", "Tctl", "Tdie"];
+
+/// Read the CPU temperature in Celsius from a hwmon sysfs tree
+///
+/// Only CPU drivers are considered. A sensor whose label names the whole
+/// package wins; otherwise the driver's first sensor is used.
+fn read_hwmon_cpu_temp(hwmon_root: &std::path::Path) -> Option<f64> {
+    let mut devices: Vec<_> = std::fs::read_dir(hwmon_root)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    devices.sort();
+
+    let mut fallback = None;
+
+    for device in devices {
+        let name = std::fs::read_to_string(device.join("name")).unwrap_or_default();
+        if !CPU_HWMON_DRIVERS.contains(&name.trim()) {
+            continue;
+        }
+
+        let mut inputs: Vec<_> = std::fs::read_dir(&device)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|file| file.starts_with("temp") && file.ends_with("_input"))
+            .collect();
+        inputs.sort();
+
+        for input in inputs {
+            // sysfs temperatures are in millidegrees Celsius
+            let Some(celsius) = std::fs::read_to_string(device.join(&input))
+                .ok()
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .map(|millidegrees| millidegrees / 1000.0)
+            else {
+                continue;
+            };
+
+            let label_file = input.replace("_input", "_label");
+            let label = std::fs::read_to_string(device.join(label_file)).unwrap_or_default();
+            if CPU_SENSOR_LABELS.contains(&label.trim()) {
+                return Some(celsius);
+            }
+
+            fallback.get_or_insert(celsius);
+        }
+    }
+
+    fallback
+}
+
+/// Format a display mode, optionally with its refresh rate
+fn format_display_mode(width: u64, height: u64, refresh: Option<f64>) -> String {
+    match refresh {
+        Some(hz) if hz > 0.0 => format!("{}x{} @ {}Hz", width, height, hz.round()),
+        _ => format!("{}x{}", width, height),
+    }
+}
+
+/// Parse the current mode of each connected output from `xrandr --query`
+fn parse_xrandr(output: &str, refresh_rate: bool) -> Vec<String> {
+    let mut resolutions = Vec::new();
+    let mut in_connected_output = false;
+
+    for line in output.lines() {
+        if !line.starts_with(' ') {
+            in_connected_output = line.contains(" connected");
+            continue;
+        }
+
+        // Mode lines look like "   2560x1440     59.95 + 165.00*"
+        if !in_connected_output || !line.contains('*') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let Some((width, height)) = fields.next().and_then(|mode| mode.split_once('x')) else {
+            continue;
+        };
+        // Interlaced modes carry an "i" suffix on the height
+        let height = height.trim_end_matches('i');
+
+        if let (Ok(width), Ok(height)) = (width.parse(), height.parse()) {
+            let refresh = if refresh_rate {
+                fields
+                    .find(|field| field.contains('*'))
+                    .and_then(|hz| hz.trim_end_matches(['*', '+']).parse().ok())
+            } else {
+                None
+            };
+            resolutions.push(format_display_mode(width, height, refresh));
+        }
+
+        // Only the current mode of each output is of interest
+        in_connected_output = false;
+    }
+
+    resolutions
+}
+
+/// Parse the current mode of each enabled output from `wlr-randr`
+fn parse_wlr_randr(output: &str, refresh_rate: bool) -> Vec<String> {
+    let mut resolutions = Vec::new();
+    let mut enabled = true;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        // Output headers aren't indented and reset the per-output state
+        if !line.starts_with(' ') {
+            enabled = true;
+            continue;
+        }
+
+        if let Some(value) = trimmed.strip_prefix("Enabled:") {
+            enabled = value.trim() == "yes";
+            continue;
+        }
+
+        // Mode lines look like "1920x1080 px, 60.000000 Hz (preferred, current)"
+        if enabled && trimmed.contains("current") && trimmed.contains(" px") {
+            let (mode, rest) = trimmed.split_once(" px").unwrap_or((trimmed, ""));
+            if let Some((width, height)) = mode.split_once('x') {
+                if let (Ok(width), Ok(height)) = (width.parse(), height.parse()) {
+                    let refresh = if refresh_rate {
+                        rest.trim_start_matches(',')
+                            .split_whitespace()
+                            .next()
+                            .and_then(|hz| hz.parse().ok())
+                    } else {
+                        None
+                    };
+                    resolutions.push(format_display_mode(width, height, refresh));
+                }
+            }
+        }
+    }
+
+    resolutions
+}
+
+/// Parse the active outputs reported by `swaymsg -t get_outputs -r`
+fn parse_swaymsg_outputs(output: &str, refresh_rate: bool) -> Vec<String> {
+    let mut resolutions = Vec::new();
+
+    if let Ok(serde_json::Value::Array(outputs)) = serde_json::from_str(output) {
+        for display in outputs {
+            if display["active"].as_bool() == Some(false) {
+                continue;
+            }
+
+            let mode = &display["current_mode"];
+            if let (Some(width), Some(height)) = (mode["width"].as_u64(), mode["height"].as_u64()) {
+                // sway reports the refresh rate in mHz
+                let refresh = if refresh_rate {
+                    mode["refresh"].as_f64().map(|mhz| mhz / 1000.0)
+                } else {
+                    None
+                };
+                resolutions.push(format_display_mode(width, height, refresh));
+            }
+        }
+    }
+
+    resolutions
+}
+
+/// Read the power profile from the ACPI `platform_profile` file
+///
+/// The file read is cheap, so `powerprofilesctl` output is only asked for
+/// when the file is missing or empty.
+fn power_profile_from(
+    platform_profile: &std::path::Path,
+    powerprofilesctl: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    if let Ok(content) = std::fs::read_to_string(platform_profile) {
+        let profile = content.trim();
+        if !profile.is_empty() {
+            return Some(profile.to_string());
+        }
+    }
+
+    // `powerprofilesctl get` prints the bare profile name
+    powerprofilesctl()
+        .and_then(|output| output.lines().next().map(|line| line.trim().to_string()))
+        .filter(|profile| !profile.is_empty())
+}
+
+/// Charge and state of the first system battery under `power_supply_root`
+///
+/// Batteries of peripherals such as mice report a `Device` scope and are
+/// skipped, as are AC adapters.
+fn read_battery(power_supply_root: &std::path::Path) -> Option<(u8, Option<String>)> {
+    let mut supplies: Vec<_> = std::fs::read_dir(power_supply_root)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    supplies.sort();
+
+    let read = |supply: &std::path::Path, file: &str| {
+        std::fs::read_to_string(supply.join(file))
+            .map(|value| value.trim().to_string())
+            .ok()
+            .filter(|value| !value.is_empty())
+    };
+    supplies.iter().find_map(|supply| {
+        if read(supply, "type").as_deref() != Some("Battery")
+            || read(supply, "scope").as_deref() == Some("Device")
+        {
+            return None;
+        }
+        let capacity = read(supply, "capacity")?.parse::<u8>().ok()?.min(100);
+        let state = read(supply, "status").filter(|status| status != "Unknown");
+        Some((capacity, state))
+    })
+}
+
+/// Read the preferred mode of every connected DRM connector under `drm_root`
+fn read_drm_modes(drm_root: &std::path::Path) -> Vec<String> {
+    let mut resolutions = Vec::new();
+
+    let Ok(entries) = std::fs::read_dir(drm_root) else {
+        return resolutions;
+    };
+
+    let mut connectors: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    connectors.sort();
+
+    for connector in connectors {
+        let status = std::fs::read_to_string(connector.join("status")).unwrap_or_default();
+        if status.trim() != "connected" {
+            continue;
+        }
+
+        if let Ok(modes) = std::fs::read_to_string(connector.join("modes")) {
+            if let Some(mode) = modes.lines().next() {
+                let mode = mode.trim();
+                if !mode.is_empty() {
+                    resolutions.push(mode.to_string());
+                }
+            }
+        }
+    }
+
+    resolutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_units_are_counted_from_systemctl_output() {
+        for (output, expected) in [
+            ("", 0),
+            ("\n", 0),
+            ("0 loaded units listed.\n", 0),
+            (
+                "nfs-server.service loaded failed failed NFS server and services\n",
+                1,
+            ),
+            (
+                "bluetooth.service    loaded failed failed Bluetooth service\n\
+                 systemd-networkd-wait-online.service loaded failed failed Wait for Network\n\
+                 \n",
+                2,
+            ),
+            // Without --plain, failed units get a bullet
+            ("● cups.service loaded failed failed CUPS Scheduler\n", 1),
+            ("* dev-sdb1.device loaded failed failed /dev/sdb1\n", 1),
+            // Summaries in other languages
+            ("0 geladene Units aufgelistet.\n", 0),
+            ("0 unités chargées listées.\n", 0),
+        ] {
+            assert_eq!(count_failed_units(output), expected, "{:?}", output);
+        }
+    }
+
+    #[test]
+    fn codename_is_appended_only_when_version_lacks_it() {
+        for (os_release, expected) in [
+            // Debian
+            (
+                "VERSION_ID=\"12\"\nVERSION=\"12 (bookworm)\"\nVERSION_CODENAME=bookworm",
+                "12 (bookworm)",
+            ),
+            // Ubuntu spells the codename out in title case
+            (
+                "VERSION=\"22.04.4 LTS (Jammy Jellyfish)\"\nVERSION_CODENAME=jammy",
+                "22.04.4 LTS (Jammy Jellyfish)",
+            ),
+            // Mint's codename only differs in case
+            (
+                "VERSION=\"21.3 (Virginia)\"\nVERSION_CODENAME=virginia",
+                "21.3 (Virginia)",
+            ),
+            // Fedora's parenthetical is the edition, not the codename
+            (
+                "VERSION=\"40 (Workstation Edition)\"\nVERSION_CODENAME=\"\"",
+                "40 (Workstation Edition)",
+            ),
+            (
+                "VERSION=\"40 (KDE Plasma)\"\nVERSION_CODENAME=f40",
+                "40 (KDE Plasma)",
+            ),
+            (
+                "VERSION=\"22.04 LTS\"\nVERSION_CODENAME=jammy",
+                "22.04 LTS (jammy)",
+            ),
+            (
+                "VERSION=\"JAMMY 22.04\"\nVERSION_CODENAME=jammy",
+                "JAMMY 22.04",
+            ),
+            // Debian testing has no VERSION at all
+            ("VERSION_CODENAME=trixie", "trixie"),
+            ("VERSION_ID=20240101", "20240101"),
+            // Arch
+            ("NAME=\"Arch Linux\"\nID=arch", ""),
+        ] {
+            let parsed = crate::utils::parse_os_release(os_release);
+            assert_eq!(release_version(&parsed), expected, "{}", os_release);
+        }
+    }
+
+    #[test]
+    fn power_profile_prefers_the_platform_profile_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("platform_profile");
+        std::fs::write(&path, "performance\n").unwrap();
+
+        let profile = power_profile_from(&path, || panic!("command should not run"));
+        assert_eq!(profile.as_deref(), Some("performance"));
+    }
+
+    #[test]
+    fn power_profile_falls_back_to_powerprofilesctl() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("platform_profile");
+        let profile = power_profile_from(&missing, || Some("power-saver\n".to_string()));
+        assert_eq!(profile.as_deref(), Some("power-saver"));
+
+        let empty = dir.path().join("empty");
+        std::fs::write(&empty, "\n").unwrap();
+        let profile = power_profile_from(&empty, || Some("balanced".to_string()));
+        assert_eq!(profile.as_deref(), Some("balanced"));
+    }
+
+    #[test]
+    fn power_profile_is_unknown_without_a_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("platform_profile");
+        assert_eq!(power_profile_from(&missing, || None), None);
+        assert_eq!(power_profile_from(&missing, || Some(String::new())), None);
+    }
+
+    /// Write `(path, content)` files under `root`
+    fn write_files(root: &std::path::Path, files: &[(&str, &str)]) {
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+    }
+
+    #[test]
+    fn hosts_from_dmi_and_the_device_tree() {
+        const DMI: &str = "sys/devices/virtual/dmi/id/";
+        for (files, expected) in [
+            // Placeholders everywhere leave nothing to show
+            (
+                &[
+                    ("sys_vendor", "To Be Filled By O.E.M.\n"),
+                    ("product_name", "To Be Filled By O.E.M.\n"),
+                    ("product_version", "To Be Filled By O.E.M.\n"),
+                    ("board_vendor", "Default string\n"),
+                    ("board_name", "Default string\n"),
+                ][..],
+                None,
+            ),
+            // The vendor is prefixed like upstream neofetch does
+            (
+                &[
+                    ("sys_vendor", "Dell Inc.\n"),
+                    ("product_name", "XPS 15 9500\n"),
+                    ("product_version", "Not Specified\n"),
+                    ("board_vendor", "Dell Inc.\n"),
+                    ("board_name", "0XWT2C\n"),
+                ],
+                Some("Dell Inc. XPS 15 9500"),
+            ),
+            (
+                &[("product_name", "ProLiant DL360 Gen10\n")],
+                Some("ProLiant DL360 Gen10"),
+            ),
+            // Desktop boards without a product fall back to the board and
+            // its vendor
+            (
+                &[
+                    ("sys_vendor", "System manufacturer\n"),
+                    ("product_name", "System Product Name\n"),
+                    ("product_version", "System Version\n"),
+                    ("board_vendor", "ASUSTeK COMPUTER INC.\n"),
+                    ("board_name", "PRIME B450M-A\n"),
+                ],
+                Some("ASUSTeK COMPUTER INC. PRIME B450M-A"),
+            ),
+            (
+                &[
+                    ("board_vendor", "ASRock\n"),
+                    ("board_name", "ASRock B450M Pro4\n"),
+                ],
+                Some("ASRock B450M Pro4"),
+            ),
+            (
+                &[("board_name", "X570 AORUS ELITE\n")],
+                Some("X570 AORUS ELITE"),
+            ),
+            // The vendor is already part of the product name
+            (
+                &[
+                    ("sys_vendor", "Micro-Star International Co., Ltd.\n"),
+                    (
+                        "product_name",
+                        "Micro-Star International Co., Ltd. MS-7C02\n",
+                    ),
+                    ("product_version", "1.0\n"),
+                ],
+                Some("Micro-Star International Co., Ltd. MS-7C02 1.0"),
+            ),
+            // Lenovo keep the marketing name in product_version
+            (
+                &[
+                    ("sys_vendor", "LENOVO\n"),
+                    ("product_name", "20L5CTO1WW\n"),
+                    ("product_version", "ThinkPad T480\n"),
+                ],
+                Some("LENOVO 20L5CTO1WW ThinkPad T480"),
+            ),
+            // Raspberry Pi, with the device tree's trailing NUL
+            (
+                &[(
+                    "/proc/device-tree/model",
+                    "Raspberry Pi 4 Model B Rev 1.4\0",
+                )],
+                Some("Raspberry Pi 4 Model B Rev 1.4"),
+            ),
+            (
+                &[(
+                    "/sys/firmware/devicetree/base/model",
+                    "Pine64 PinePhone (1.2)\0",
+                )],
+                Some("Pine64 PinePhone (1.2)"),
+            ),
+            (&[], None),
+        ] {
+            let root = tempfile::tempdir().unwrap();
+            // Bare names are DMI files, absolute paths are relative to the root
+            let files: Vec<(String, &str)> = files
+                .iter()
+                .map(|(file, content)| match file.strip_prefix('/') {
+                    Some(path) => (path.to_string(), *content),
+                    None => (format!("{}{}", DMI, file), *content),
+                })
+                .collect();
+            let files: Vec<(&str, &str)> = files
+                .iter()
+                .map(|(path, content)| (path.as_str(), *content))
+                .collect();
+            write_files(root.path(), &files);
+            assert_eq!(host_from(root.path()).as_deref(), expected, "{:?}", files);
+        }
+    }
+
+    /// Write a fake power supply with `(file, content)` attributes
+    fn power_supply(root: &std::path::Path, name: &str, attributes: &[(&str, &str)]) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, content) in attributes {
+            std::fs::write(dir.join(file), format!("{}\n", content)).unwrap();
+        }
+    }
+
+    #[test]
+    fn battery_from_power_supplies() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(read_battery(root.path()), None);
+        assert_eq!(read_battery(&root.path().join("missing")), None);
+
+        power_supply(root.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        power_supply(
+            root.path(),
+            "hidpp_battery_0",
+            &[("type", "Battery"), ("scope", "Device"), ("capacity", "40")],
+        );
+        assert_eq!(read_battery(root.path()), None);
+
+        power_supply(
+            root.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "87"),
+                ("status", "Discharging"),
+            ],
+        );
+        power_supply(
+            root.path(),
+            "BAT1",
+            &[("type", "Battery"), ("capacity", "3")],
+        );
+        assert_eq!(
+            read_battery(root.path()),
+            Some((87, Some("Discharging".to_string())))
+        );
+
+        // Miscalibrated batteries can report more than 100%
+        power_supply(
+            root.path(),
+            "BAT0",
+            &[("capacity", "104"), ("status", "Unknown")],
+        );
+        assert_eq!(read_battery(root.path()), Some((100, None)));
+    }
+
+    #[test]
+    fn wlr_randr_outputs() {
+        let output = include_str!("../../tests/fixtures/wayland/wlr-randr.txt");
+        assert_eq!(
+            parse_wlr_randr(output, true),
+            ["3840x2160 @ 60Hz", "1920x1080 @ 144Hz"]
+        );
+        assert_eq!(parse_wlr_randr(output, false), ["3840x2160", "1920x1080"]);
+        assert!(parse_wlr_randr("", true).is_empty());
+    }
+
+    #[test]
+    fn swaymsg_outputs() {
+        let output = include_str!("../../tests/fixtures/wayland/swaymsg-outputs.json");
+        assert_eq!(
+            parse_swaymsg_outputs(output, true),
+            ["3840x2160 @ 60Hz", "1920x1080 @ 144Hz"]
+        );
+        assert_eq!(
+            parse_swaymsg_outputs(output, false),
+            ["3840x2160", "1920x1080"]
+        );
+        assert!(parse_swaymsg_outputs("", true).is_empty());
+        assert!(parse_swaymsg_outputs("[]", true).is_empty());
+    }
+
+    #[test]
+    fn drm_connector_modes() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(read_drm_modes(root.path()).is_empty());
+        assert!(read_drm_modes(&root.path().join("missing")).is_empty());
+
+        let connector = |name: &str, status: &str, modes: &str| {
+            let dir = root.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("status"), format!("{}\n", status)).unwrap();
+            std::fs::write(dir.join("modes"), modes).unwrap();
+        };
+        // The card itself has no status file
+        std::fs::create_dir_all(root.path().join("card0")).unwrap();
+        connector("card0-eDP-1", "connected", "1920x1200\n1600x1200\n");
+        connector("card0-HDMI-A-1", "disconnected", "");
+        connector("card0-DP-1", "connected", "2560x1440\n1920x1080\n");
+        connector("card0-DP-2", "connected", "");
+
+        assert_eq!(read_drm_modes(root.path()), ["2560x1440", "1920x1200"]);
+    }
+
+    #[test]
+    fn amdgpu_vram_from_sysfs() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(amdgpu_vram_bytes(root.path()), None);
+        assert_eq!(amdgpu_vram_bytes(&root.path().join("missing")), None);
+
+        write_files(
+            root.path(),
+            &[
+                // An APU's 512 MiB carve-out isn't a dedicated card
+                ("card0/device/mem_info_vram_total", "536870912\n"),
+                ("card0-eDP-1/status", "connected\n"),
+                ("card2/device/vendor", "0x10de\n"),
+            ],
+        );
+        assert_eq!(amdgpu_vram_bytes(root.path()), None);
+
+        write_files(
+            root.path(),
+            &[
+                ("card1/device/mem_info_vram_total", "8573157376\n"),
+                ("card3/device/mem_info_vram_total", "garbage\n"),
+            ],
+        );
+        assert_eq!(amdgpu_vram_bytes(root.path()), Some(8573157376));
+    }
+
+    #[test]
+    fn nvidia_vram_from_nvidia_smi() {
+        for (output, expected) in [
+            ("8192\n", Some(8192 * 1024 * 1024)),
+            // The first card wins
+            ("24564\n12288\n", Some(24564 * 1024 * 1024)),
+            (" 4096 \n", Some(4096 * 1024 * 1024)),
+            ("[N/A]\n6144\n", Some(6144 * 1024 * 1024)),
+            ("0\n", None),
+            ("[N/A]\n", None),
+            ("", None),
+        ] {
+            assert_eq!(parse_nvidia_vram(output), expected, "{:?}", output);
+        }
+    }
+
+    /// Write a fake hwmon device with `(input, millidegrees, label)` sensors
+    fn hwmon_device(
+        root: &std::path::Path,
+        device: &str,
+        name: &str,
+        sensors: &[(&str, &str, Option<&str>)],
+    ) {
+        let dir = root.join(device);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("name"), format!("{}\n", name)).unwrap();
+        for (sensor, millidegrees, label) in sensors {
+            std::fs::write(dir.join(format!("{}_input", sensor)), millidegrees).unwrap();
+            if let Some(label) = label {
+                std::fs::write(dir.join(format!("{}_label", sensor)), label).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn hwmon_prefers_the_package_sensor() {
+        let root = tempfile::tempdir().unwrap();
+        hwmon_device(
+            root.path(),
+            "hwmon0",
+            "coretemp",
+            &[
+                ("temp1", "61000\n", Some("Core 0")),
+                ("temp2", "45500\n", Some("Package id 0")),
+            ],
+        );
+
+        assert_eq!(read_hwmon_cpu_temp(root.path()), Some(45.5));
+    }
+
+    #[test]
+    fn hwmon_skips_non_cpu_drivers() {
+        let root = tempfile::tempdir().unwrap();
+        hwmon_device(
+            root.path(),
+            "hwmon0",
+            "nvme",
+            &[("temp1", "38000", Some("Composite"))],
+        );
+        hwmon_device(
+            root.path(),
+            "hwmon1",
+            "amdgpu",
+            &[("temp1", "52000", Some("edge"))],
+        );
+        hwmon_device(
+            root.path(),
+            "hwmon2",
+            "k10temp",
+            &[("temp1", "48250", Some("Tctl"))],
+        );
+
+        assert_eq!(read_hwmon_cpu_temp(root.path()), Some(48.25));
+    }
+
+    #[test]
+    fn hwmon_falls_back_to_the_first_cpu_sensor() {
+        let root = tempfile::tempdir().unwrap();
+        hwmon_device(
+            root.path(),
+            "hwmon0",
+            "cpu_thermal",
+            &[("temp1", "51000", None), ("temp2", "not a number", None)],
+        );
+
+        assert_eq!(read_hwmon_cpu_temp(root.path()), Some(51.0));
+    }
+
+    #[test]
+    fn hwmon_without_cpu_sensors_gives_nothing() {
+        let root = tempfile::tempdir().unwrap();
+        hwmon_device(root.path(), "hwmon0", "acpitz", &[("temp1", "27800", None)]);
+
+        assert_eq!(read_hwmon_cpu_temp(root.path()), None);
+        assert_eq!(read_hwmon_cpu_temp(&root.path().join("missing")), None);
+    }
+
+    #[test]
+    fn apk_packages_from_the_database() {
+        // Provides ("p:") and file ("R:") lines don't count
+        let installed = include_str!("../../tests/fixtures/apk/installed");
+        assert_eq!(count_apk_packages(installed), 3);
+        assert_eq!(count_apk_packages(""), 0);
+    }
+
+    #[test]
+    fn install_time_from_the_pacman_log() {
+        let root = tempfile::tempdir().unwrap();
+        let log = root.path().join("pacman.log");
+        let cet = chrono::FixedOffset::east_opt(3600).unwrap();
+
+        for (first_line, expected) in [
+            (
+                "[2021-03-04T12:00:00+0100] [PACMAN] Running 'pacman -Syu'",
+                Some(1614855600),
+            ),
+            (
+                "[2021-03-04T11:00:00+0000] [ALPM] installed base (2-2)",
+                Some(1614855600),
+            ),
+            // Older logs are in local time, without seconds
+            ("[2021-03-04 12:00] Running 'pacman -Syu'", Some(1614855600)),
+            ("[someday] Running 'pacman -Syu'", None),
+            ("", None),
+        ] {
+            std::fs::write(&log, format!("{}\n[2021-03-05 09:00] later\n", first_line)).unwrap();
+            assert_eq!(pacman_log_start(&log, &cet), expected, "{}", first_line);
+        }
+        assert_eq!(
+            pacman_log_start(&root.path().join("missing.log"), &cet),
+            None
+        );
+    }
+
+    #[test]
+    fn meminfo_fixtures() {
+        let cases = [
+            (
+                include_str!("../../tests/fixtures/meminfo/desktop.txt"),
+                7665836,
+                16268288,
+            ),
+            (
+                include_str!("../../tests/fixtures/meminfo/raspberry-pi.txt"),
+                162608,
+                944096,
+            ),
+            // No SReclaimable before Linux 2.6.19
+            (
+                include_str!("../../tests/fixtures/meminfo/old-kernel.txt"),
+                1044480,
+                2048000,
+            ),
+        ];
+        for (content, used_kib, total_kib) in cases {
+            assert_eq!(
+                parse_meminfo(content),
+                Some((used_kib * 1024, total_kib * 1024))
+            );
+        }
+    }
+
+    #[test]
+    fn meminfo_without_a_total_is_rejected() {
+        assert_eq!(parse_meminfo(""), None);
+        assert_eq!(parse_meminfo("MemFree: 1024 kB\nCached: 2048 kB\n"), None);
+        assert_eq!(parse_meminfo("MemTotal: lots kB\n"), None);
+    }
+
+    #[test]
+    fn wm_from_session_variables() {
+        // What `wm()` shows when X can't be asked
+        let wm = |vars: &[(&str, &str)], processes: &[&str]| {
+            let env = |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            };
+            let processes = || processes.iter().map(|name| name.to_string()).collect();
+            is_wayland_session(env)
+                .then(|| detect_wayland_compositor(env, processes))
+                .flatten()
+                .map(str::to_string)
+                .unwrap_or_else(|| wm_from_env(env))
+        };
+
+        for (vars, processes, expected) in [
+            (
+                &[
+                    ("XDG_SESSION_TYPE", "wayland"),
+                    ("HYPRLAND_INSTANCE_SIGNATURE", "abc"),
+                ][..],
+                &[][..],
+                "Hyprland",
+            ),
+            (
+                &[
+                    ("WAYLAND_DISPLAY", "wayland-1"),
+                    ("SWAYSOCK", "/run/sway.sock"),
+                ],
+                &[],
+                "sway",
+            ),
+            (
+                &[
+                    ("XDG_SESSION_TYPE", "wayland"),
+                    ("XDG_CURRENT_DESKTOP", "ubuntu:GNOME"),
+                ],
+                &[],
+                "Mutter",
+            ),
+            (
+                &[
+                    ("WAYLAND_DISPLAY", "wayland-0"),
+                    ("XDG_CURRENT_DESKTOP", "KDE"),
+                ],
+                &[],
+                "KWin",
+            ),
+            (
+                &[
+                    ("WAYLAND_DISPLAY", "wayland-0"),
+                    ("XDG_CURRENT_DESKTOP", "river"),
+                ],
+                &[],
+                "river",
+            ),
+            (
+                &[("WAYLAND_DISPLAY", "wayland-0")],
+                &["systemd", "weston"],
+                "Weston",
+            ),
+            (
+                &[("XDG_SESSION_TYPE", "wayland")],
+                &["bash", "niri"],
+                "niri",
+            ),
+            // An unknown compositor falls back to the session variables
+            (
+                &[
+                    ("WAYLAND_DISPLAY", "wayland-0"),
+                    ("DESKTOP_SESSION", "cosmic"),
+                ],
+                &["cosmic-comp"],
+                "cosmic",
+            ),
+            (&[("XDG_SESSION_TYPE", "wayland")], &[], "Unknown"),
+            // X11 sessions never look at Wayland hints
+            (
+                &[("XDG_SESSION_TYPE", "x11"), ("SWAYSOCK", "/run/sway.sock")],
+                &["sway"],
+                "Unknown",
+            ),
+            (
+                &[("XDG_SESSION_TYPE", "x11"), ("DESKTOP_SESSION", "i3")],
+                &[],
+                "i3",
+            ),
+            (
+                &[("GNOME_DESKTOP_SESSION_ID", "this-is-deprecated")],
+                &[],
+                "Mutter",
+            ),
+            (&[("KDE_FULL_SESSION", "true")], &[], "KWin"),
+            (&[("DESKTOP_SESSION", "OpenBox")], &[], "Openbox"),
+            (&[], &["sway"], "Unknown"),
+        ] {
+            assert_eq!(wm(vars, processes), expected, "{:?} {:?}", vars, processes);
+        }
+    }
+
+    #[test]
+    fn x11_wm_from_xprop() {
+        for (output, expected) in [
+            (
+                "_NET_SUPPORTING_WM_CHECK: window id # 0x1200003\n",
+                Some("0x1200003"),
+            ),
+            (
+                "_NET_SUPPORTING_WM_CHECK: window id # 0xa00001, 0xa00002\n",
+                Some("0xa00001"),
+            ),
+            ("_NET_SUPPORTING_WM_CHECK:  not found.\n", None),
+            ("", None),
+        ] {
+            assert_eq!(parse_wm_check_window(output), expected, "{:?}", output);
+        }
+
+        for (output, expected) in [
+            ("_NET_WM_NAME = \"i3\"\n", Some("i3")),
+            ("_NET_WM_NAME = \"Openbox\"\n", Some("Openbox")),
+            ("_NET_WM_NAME = \"GNOME Shell\"\n", Some("Mutter")),
+            ("_NET_WM_NAME = \"\"\n", None),
+            ("_NET_WM_NAME:  not found.\n", None),
+            ("", None),
+        ] {
+            assert_eq!(parse_wm_name(output).as_deref(), expected, "{:?}", output);
+        }
+    }
+
+    #[test]
+    fn wm_themes_from_config_files() {
+        let config = tempfile::tempdir().unwrap();
+        assert_eq!(kwin_decoration_theme(config.path()), None);
+        assert_eq!(openbox_theme(config.path()), None);
+
+        let kwinrc = |content: &str| {
+            std::fs::write(config.path().join("kwinrc"), content).unwrap();
+            kwin_decoration_theme(config.path())
+        };
+        assert_eq!(
+            kwinrc(
+                "[Compositing]\ntheme=wrong\n\n\
+                 [org.kde.kdecoration2]\nButtonsOnLeft=MS\ntheme=Breeze\n"
+            )
+            .as_deref(),
+            Some("Breeze")
+        );
+        assert_eq!(
+            kwinrc("[org.kde.kdecoration2]\ntheme=__aurorae__svg__Sweet-Dark\n").as_deref(),
+            Some("Sweet-Dark")
+        );
+        assert_eq!(
+            kwinrc("[org.kde.kdecoration2]\nlibrary=org.kde.breeze\n"),
+            None
+        );
+
+        std::fs::create_dir(config.path().join("openbox")).unwrap();
+        let rc = |file: &str, content: &str| {
+            std::fs::write(config.path().join("openbox").join(file), content).unwrap();
+            openbox_theme(config.path())
+        };
+        assert_eq!(
+            rc(
+                "lxqt-rc.xml",
+                "<openbox_config>\n  <theme>\n    <name>Clearlooks</name>\n  </theme>\n</openbox_config>\n"
+            )
+            .as_deref(),
+            Some("Clearlooks")
+        );
+        // rc.xml wins, and names outside <theme> don't count
+        assert_eq!(
+            rc(
+                "rc.xml",
+                "<openbox_config>\n  <desktops><names><name>Main</name></names></desktops>\n  \
+                 <theme>\n    <name> Onyx </name>\n    <titleLayout>NLIMC</titleLayout>\n  \
+                 </theme>\n</openbox_config>\n"
+            )
+            .as_deref(),
+            Some("Onyx")
+        );
+        assert_eq!(rc("rc.xml", "<openbox_config/>\n"), None);
+
+        assert_eq!(unquote_gvariant("'Adwaita'\n"), "Adwaita");
+        assert_eq!(unquote_gvariant("''\n"), "");
+    }
+
+    #[test]
+    fn localized_and_invalid_output_is_not_misread() {
+        let lossy = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+
+        // A monitor name with broken bytes doesn't hide the mode
+        let xrandr = lossy(
+            b"Screen 0: minimum 320 x 200, current 1920 x 1080\n\
+              HDMI-1 connected primary 1920x1080+0+0 (\xff\xfe) 527mm x 296mm\n\
+              \x20  1920x1080     60.00*+  50.00\n",
+        );
+        assert_eq!(parse_xrandr(&xrandr, true), ["1920x1080 @ 60Hz"]);
+
+        // German xrandr says "verbunden", which isn't guessed at
+        let german = "HDMI-1 verbunden 1920x1080+0+0\n   1920x1080     60.00*+\n";
+        assert_eq!(parse_xrandr(german, false), Vec::<String>::new());
+
+        // Garbage instead of numbers gives nothing rather than a bad mode
+        let garbled = lossy(b"HDMI-1 connected\n   1920x10\xc3\x2880     60.00*+\n");
+        assert_eq!(parse_xrandr(&garbled, false), Vec::<String>::new());
+
+        assert_eq!(count_failed_units(&lossy(b"\xff\xfe\xfd\n")), 0);
+        assert_eq!(
+            count_failed_units(&lossy(b"caf\xe9.service loaded failed failed\n")),
+            1
+        );
+
+        assert_eq!(
+            first_version(&lossy(b"GNOME Shell \xff 46.2")).as_deref(),
+            Some("46.2")
+        );
+        // Arabic-Indic and full-width digits aren't version numbers
+        assert_eq!(first_version("GNOME Shell ٤٦٫٢"), None);
+        assert_eq!(first_version("GNOME Shell ４６.２"), None);
+    }
+
+    #[test]
+    fn cpu_names_from_cpuinfo() {
+        let pi_compatible = b"raspberrypi,4-model-b\0brcm,bcm2711\0";
+        for (cpuinfo, compatible, expected) in [
+            (
+                include_str!("../../tests/fixtures/cpuinfo/intel.txt"),
+                None,
+                "11th Gen Intel Core i7-1165G7",
+            ),
+            (
+                include_str!("../../tests/fixtures/cpuinfo/amd.txt"),
+                None,
+                "AMD Ryzen 9 5950X 16-Core",
+            ),
+            (
+                include_str!("../../tests/fixtures/cpuinfo/raspberry-pi-4.txt"),
+                Some(&pi_compatible[..]),
+                "BCM2711",
+            ),
+            (
+                include_str!("../../tests/fixtures/cpuinfo/raspberry-pi-4.txt"),
+                None,
+                "ARM Cortex-A72",
+            ),
+            (
+                include_str!("../../tests/fixtures/cpuinfo/raspberry-pi-3-32bit.txt"),
+                None,
+                "BCM2835",
+            ),
+        ] {
+            let name = parse_cpuinfo_name(cpuinfo, compatible).unwrap();
+            assert_eq!(crate::utils::clean_cpu_name(&name), expected);
+        }
+
+        assert_eq!(parse_cpuinfo_name("", None), None);
+        assert_eq!(parse_cpuinfo_name("model name\t: \n", None), None);
+        assert_eq!(
+            parse_cpuinfo_name("model name\t: ARMv7 Processor rev 4 (v7l)\n", None).as_deref(),
+            Some("ARMv7 Processor rev 4 (v7l)")
+        );
+    }
+
+    #[test]
+    fn desktops_are_normalized() {
+        for (raw, expected) in [
+            ("GNOME", Some(("GNOME", Some("gnome-shell")))),
+            ("ubuntu:GNOME", Some(("GNOME", Some("gnome-shell")))),
+            ("pop:GNOME", Some(("GNOME", Some("gnome-shell")))),
+            ("GNOME-Classic:GNOME", Some(("GNOME", Some("gnome-shell")))),
+            ("KDE", Some(("Plasma", Some("plasmashell")))),
+            ("X-Cinnamon", Some(("Cinnamon", Some("cinnamon")))),
+            ("XFCE", Some(("Xfce", Some("xfce4-session")))),
+            ("MATE", Some(("MATE", Some("mate-session")))),
+            ("LXQt", Some(("LXQt", Some("lxqt-session")))),
+            ("Budgie:GNOME", Some(("Budgie", Some("budgie-desktop")))),
+            ("Unity:Unity7:ubuntu", Some(("Unity", Some("unity")))),
+            ("Pantheon", Some(("Pantheon", None))),
+            ("COSMIC", Some(("COSMIC", None))),
+            ("xfce", Some(("Xfce", Some("xfce4-session")))),
+            ("Hyprland", Some(("Hyprland", None))),
+            (" : X-Generic ", Some(("Generic", None))),
+            ("", None),
+            (":", None),
+        ] {
+            let expected = expected.map(|(name, command)| (name.to_string(), command));
+            assert_eq!(normalize_desktop(raw), expected, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn desktop_versions_are_the_first_number() {
+        for (output, expected) in [
+            ("GNOME Shell 46.2", Some("46.2")),
+            ("plasmashell 6.0.4", Some("6.0.4")),
+            (
+                "xfce4-session 4.18.1 (Xfce 4.18)\n\nCopyright",
+                Some("4.18.1"),
+            ),
+            ("cinnamon 6.0.4", Some("6.0.4")),
+            ("MATE Desktop Environment 1.26.2,", Some("1.26.2")),
+            ("budgie-desktop 10.9.1\nCopyright (C) 2014", Some("10.9.1")),
+            ("enlightenment: unknown option", None),
+            ("", None),
+        ] {
+            assert_eq!(first_version(output).as_deref(), expected, "{:?}", output);
+        }
+    }
+}
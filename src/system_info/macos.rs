@@ -0,0 +1,290 @@
+//! macOS system information
+//!
+//! Gathered from `sw_vers`, `sysctl`, `system_profiler` and the user
+//! defaults database.
+
+use super::{OsInfo, PlatformGather};
+use crate::config::SpeedType;
+use std::time::Duration;
+
+/// `system_profiler` can take a few seconds on a cold start
+const SYSTEM_PROFILER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// macOS implementation of [`PlatformGather`]
+pub(super) struct MacOs;
+
+impl PlatformGather for MacOs {
+    fn os(os: &mut OsInfo) {
+        if let Ok(product_name) = crate::utils::execute_command("sw_vers", &["-productName"]) {
+            if !product_name.is_empty() {
+                os.name = product_name;
+            }
+        }
+        if let Ok(product_version) = crate::utils::execute_command("sw_vers", &["-productVersion"])
+        {
+            if !product_version.is_empty() {
+                os.version_id = product_version;
+            }
+        }
+        os.distro_id = "macos".to_string();
+        os.version = match macos_codename(&os.version_id) {
+            Some(codename) => format!("{} {}", os.version_id, codename),
+            None => os.version_id.clone(),
+        };
+    }
+
+    fn host() -> Option<String> {
+        let output = system_profiler("SPHardwareDataType")?;
+        output
+            .lines()
+            .find(|line| line.contains("Model Name:"))
+            .map(|line| line.split(':').nth(1).unwrap_or("").trim().to_string())
+    }
+
+    fn package_counts(_timeout: Duration) -> Vec<(usize, &'static str)> {
+        let mut package_managers = Vec::new();
+
+        // Homebrew
+        if let Ok(output) = crate::utils::output_with_timeout(
+            crate::utils::command("brew").args(["list", "--formula"]),
+            crate::utils::COMMAND_TIMEOUT,
+        ) {
+            if output.status.success() {
+                let count = String::from_utf8_lossy(&output.stdout).lines().count();
+                if count > 0 {
+                    package_managers.push((count, "brew"));
+                }
+            }
+        }
+
+        // MacPorts
+        if let Ok(output) = crate::utils::output_with_timeout(
+            crate::utils::command("port").args(["installed"]),
+            crate::utils::COMMAND_TIMEOUT,
+        ) {
+            if output.status.success() {
+                let count = String::from_utf8_lossy(&output.stdout).lines().count();
+                if count > 0 {
+                    package_managers.push((count, "port"));
+                }
+            }
+        }
+
+        package_managers
+    }
+
+    fn resolution(refresh_rate: bool) -> Option<String> {
+        let output = system_profiler("SPDisplaysDataType")?;
+
+        let mut resolutions = Vec::new();
+
+        for line in output.lines() {
+            if line.contains("Resolution:") {
+                if let Some(res) = line.split(':').nth(1) {
+                    let res = res.trim();
+                    if !res.is_empty() && res != "Unknown" {
+                        resolutions.push(res.to_string());
+                    }
+                }
+            } else if refresh_rate && line.contains("UI Looks like:") {
+                // e.g. "UI Looks like: 1512 x 982 @ 120.00Hz"
+                let refresh = line
+                    .split('@')
+                    .nth(1)
+                    .and_then(|hz| hz.trim().trim_end_matches("Hz").parse::<f64>().ok());
+                if let (Some(hz), Some(res)) = (refresh, resolutions.last_mut()) {
+                    res.push_str(&format!(" @ {}Hz", hz.round()));
+                }
+            }
+        }
+
+        (!resolutions.is_empty()).then(|| resolutions.join(", "))
+    }
+
+    fn de() -> String {
+        "Aqua".to_string()
+    }
+
+    fn wm() -> String {
+        "Quartz Compositor".to_string()
+    }
+
+    fn wm_theme(_wm: &str) -> Option<String> {
+        // Try to detect macOS appearance
+        let style =
+            crate::utils::execute_command("defaults", &["read", "-g", "AppleInterfaceStyle"])
+                .ok()?;
+        // The key only exists in dark mode
+        let dark = style == "Dark";
+        Some(if dark { "Blue (Dark)" } else { "Blue (Light)" }.to_string())
+    }
+
+    fn cpu_name() -> Option<String> {
+        let cpu_name =
+            crate::utils::execute_command("sysctl", &["-n", "machdep.cpu.brand_string"]).ok()?;
+        (!cpu_name.is_empty()).then_some(cpu_name)
+    }
+
+    fn cpu_frequency_mhz(_speed_type: &SpeedType) -> Option<f64> {
+        // Intel Macs expose the frequency in Hz; Apple Silicon doesn't
+        let hz = crate::utils::execute_command("sysctl", &["-n", "hw.cpufrequency"]).ok()?;
+        hz.parse::<f64>()
+            .ok()
+            .filter(|hz| *hz > 0.0)
+            .map(|hz| hz / 1_000_000.0)
+    }
+
+    fn gpu() -> Option<String> {
+        let output = system_profiler("SPDisplaysDataType")?;
+
+        output
+            .lines()
+            .filter(|line| line.contains("Chipset Model:"))
+            .filter_map(|line| line.split(':').nth(1))
+            .map(str::trim)
+            .find(|gpu| !gpu.is_empty() && *gpu != "Unknown")
+            .map(str::to_string)
+    }
+
+    /// Only discrete GPUs list "VRAM (Total)"; integrated ones report
+    /// "VRAM (Dynamic, Max)" and are skipped
+    fn gpu_memory_bytes() -> Option<u64> {
+        let output = system_profiler("SPDisplaysDataType")?;
+
+        // e.g. "      VRAM (Total): 8 GB"
+        output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("VRAM (Total):"))
+            .find_map(|size| {
+                let mut parts = size.split_whitespace();
+                let amount = parts.next()?.parse::<u64>().ok()?;
+                match parts.next()? {
+                    "GB" => Some(amount * 1024 * 1024 * 1024),
+                    "MB" => Some(amount * 1024 * 1024),
+                    _ => None,
+                }
+            })
+    }
+
+    fn power_profile() -> Option<String> {
+        let output = crate::utils::execute_command("pmset", &["-g"]).ok()?;
+        parse_pmset_power_profile(&output)
+    }
+
+    fn install_time() -> Option<u64> {
+        // Created when Setup Assistant finishes on a fresh install
+        std::fs::metadata("/var/db/.AppleSetupDone")
+            .and_then(|metadata| metadata.created())
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|elapsed| elapsed.as_secs())
+    }
+
+    fn locale() -> Option<String> {
+        crate::utils::execute_command("defaults", &["read", "-g", "AppleLocale"])
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+
+    fn uptime_seconds() -> Option<u64> {
+        // kern.boottime looks like "{ sec = 1700000000, usec = 0 } Tue Nov 14 ..."
+        let output = crate::utils::execute_command("sysctl", &["-n", "kern.boottime"]).ok()?;
+        let boot = output
+            .split("sec =")
+            .nth(1)?
+            .split(',')
+            .next()?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        Some(now.saturating_sub(boot))
+    }
+}
+
+/// Output of `system_profiler <data_type>`, or `None` if it fails or hangs
+fn system_profiler(data_type: &str) -> Option<String> {
+    let output = crate::utils::output_with_timeout(
+        crate::utils::command("system_profiler").arg(data_type),
+        SYSTEM_PROFILER_TIMEOUT,
+    )
+    .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Marketing name of a macOS release, keyed by its version number
+fn macos_codename(version: &str) -> Option<&'static str> {
+    let mut parts = version.split('.');
+    let major = parts.next()?;
+    let minor = parts.next().unwrap_or("0");
+
+    Some(match (major, minor) {
+        ("26", _) => "Tahoe",
+        ("15", _) => "Sequoia",
+        ("14", _) => "Sonoma",
+        ("13", _) => "Ventura",
+        ("12", _) => "Monterey",
+        ("11", _) => "Big Sur",
+        ("10", "15") => "Catalina",
+        ("10", "14") => "Mojave",
+        ("10", "13") => "High Sierra",
+        _ => return None,
+    })
+}
+
+/// Low power mode from `pmset -g`, e.g. ` lowpowermode         1`
+fn parse_pmset_power_profile(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("lowpowermode") {
+            return None;
+        }
+        Some(match parts.next() {
+            Some("1") => "low power".to_string(),
+            _ => "normal".to_string(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PMSET_LOW_POWER: &str = "\
+System-wide power settings:
+Currently in use:
+ standby              1
+ Sleep On Power Button 1
+ hibernatefile        /var/vm/sleepimage
+ lowpowermode         1
+ displaysleep         2
+";
+
+    #[test]
+    fn pmset_low_power_mode() {
+        assert_eq!(
+            parse_pmset_power_profile(PMSET_LOW_POWER).as_deref(),
+            Some("low power")
+        );
+        let normal = PMSET_LOW_POWER.replace("lowpowermode         1", "lowpowermode         0");
+        assert_eq!(
+            parse_pmset_power_profile(&normal).as_deref(),
+            Some("normal")
+        );
+    }
+
+    #[test]
+    fn pmset_without_low_power_mode() {
+        assert_eq!(
+            parse_pmset_power_profile("Currently in use:\n standby 1\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,1697 @@
+//! System information gathering module
+//!
+//! This module provides cross-platform system information gathering capabilities.
+//! `SystemInfo` holds the fields and orchestrates gathering; everything
+//! platform-specific sits behind the [`PlatformGather`] trait, implemented
+//! once per platform in its own submodule.
+
+mod common;
+
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+mod bsd;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use crate::cache::{FieldCache, CACHE_FILE};
+use crate::config::{
+    CompiledRewrite, Config, CpuTemp, DisplayMode, DistroShorthand, MemoryUnit, SpeedType,
+    UptimeStyle,
+};
+use anyhow::Result;
+use common::*;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::time::Duration;
+use sysinfo::System;
+
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+use bsd::Bsd as Platform;
+#[cfg(target_os = "linux")]
+use linux::Linux as Platform;
+#[cfg(target_os = "macos")]
+use macos::MacOs as Platform;
+#[cfg(target_os = "windows")]
+use windows::Windows as Platform;
+
+/// Platforms without a module of their own get only the defaults
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+struct Platform;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+impl PlatformGather for Platform {}
+
+/// Platform-specific probes
+///
+/// Every method has a default that detects nothing, so a platform only
+/// overrides what it can actually find out. `SystemInfo` falls back to
+/// sysinfo or `Unknown` wherever a probe returns `None`.
+trait PlatformGather {
+    /// Refine the sysinfo OS name and version and fill in the distro ids
+    fn os(_os: &mut OsInfo) {}
+
+    /// Host or model name
+    fn host() -> Option<String> {
+        None
+    }
+
+    /// Package managers to count concurrently, in display order
+    fn package_probes(_timeout: Duration) -> Vec<PackageProbe> {
+        Vec::new()
+    }
+
+    /// Package counts gathered after the concurrent probes
+    fn package_counts(_timeout: Duration) -> Vec<(usize, &'static str)> {
+        Vec::new()
+    }
+
+    /// Current resolution of every display, comma separated
+    fn resolution(_refresh_rate: bool) -> Option<String> {
+        None
+    }
+
+    /// Desktop environment
+    fn de() -> String {
+        "Unknown".to_string()
+    }
+
+    /// Window manager
+    fn wm() -> String {
+        "Unknown".to_string()
+    }
+
+    /// Window manager theme, looked up for the already detected `wm`
+    fn wm_theme(_wm: &str) -> Option<String> {
+        None
+    }
+
+    /// CPU model, when sysinfo's name isn't good enough
+    fn cpu_name() -> Option<String> {
+        None
+    }
+
+    /// CPU package temperature in Celsius
+    fn cpu_temp_celsius() -> Option<f64> {
+        None
+    }
+
+    /// CPU frequency in MHz for the given speed type
+    fn cpu_frequency_mhz(_speed_type: &SpeedType) -> Option<f64> {
+        None
+    }
+
+    /// GPU model
+    fn gpu() -> Option<String> {
+        None
+    }
+
+    /// Video memory of a dedicated GPU in bytes
+    ///
+    /// Integrated GPUs sharing system memory give `None`.
+    fn gpu_memory_bytes() -> Option<u64> {
+        None
+    }
+
+    /// Used and total memory in bytes, when sysinfo's numbers are off
+    fn memory() -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Charge of the system battery in percent, with its state such as
+    /// `Discharging` when known
+    fn battery() -> Option<(u8, Option<String>)> {
+        None
+    }
+
+    /// Active power profile
+    fn power_profile() -> Option<String> {
+        None
+    }
+
+    /// Service health summary
+    fn services() -> Option<Services> {
+        None
+    }
+
+    /// Logged-in users, comma separated, with their sessions if asked
+    fn users(sessions: bool) -> String {
+        who_users(sessions)
+    }
+
+    /// System locale, used when no locale variable is set
+    fn locale() -> Option<String> {
+        None
+    }
+
+    /// Seconds since boot, used when sysinfo can't tell
+    fn uptime_seconds() -> Option<u64> {
+        None
+    }
+
+    /// When the OS was installed, in seconds since the Unix epoch
+    fn install_time() -> Option<u64> {
+        None
+    }
+}
+
+/// OS name and version as gathered by [`PlatformGather::os`]
+struct OsInfo {
+    name: String,
+    /// Full version, e.g. `12 (bookworm)`
+    version: String,
+    /// Bare version number, e.g. `12`
+    version_id: String,
+    distro_id: String,
+    distro_id_like: String,
+}
+
+/// Service summary from [`PlatformGather::services`]
+struct Services {
+    count: usize,
+    /// Whether `count` is failed units rather than enabled services
+    failed: bool,
+}
+
+/// Every field exposed in structured output, in display order
+///
+/// The color blocks are left out since they are only escape codes.
+pub const FIELDS: &[&str] = &[
+    "title",
+    "os",
+    "host",
+    "kernel",
+    "uptime",
+    "datetime",
+    "install_date",
+    "packages",
+    "shell",
+    "editor",
+    "resolution",
+    "de",
+    "wm",
+    "wm_theme",
+    "theme",
+    "icons",
+    "terminal",
+    "terminal_font",
+    "multiplexer",
+    "cpu",
+    "cpu_temp",
+    "gpu",
+    "memory",
+    "swap",
+    "disk",
+    "battery",
+    "power_profile",
+    "services",
+    "local_ip",
+    "public_ip",
+    "users",
+    "locale",
+    "gpu_driver",
+    "song",
+    "weather",
+];
+
+/// Fields that change while running, with how long a value stays fresh
+///
+/// `--serve` gathers these again once their value is older than this.
+pub const VOLATILE_FIELDS: &[(&str, std::time::Duration)] = &[
+    ("uptime", std::time::Duration::from_secs(1)),
+    ("datetime", std::time::Duration::from_secs(1)),
+    ("memory", std::time::Duration::from_secs(1)),
+    ("swap", std::time::Duration::from_secs(1)),
+    ("cpu", std::time::Duration::from_secs(2)),
+    ("cpu_temp", std::time::Duration::from_secs(2)),
+    ("song", std::time::Duration::from_secs(2)),
+    ("battery", std::time::Duration::from_secs(10)),
+    ("power_profile", std::time::Duration::from_secs(10)),
+    ("resolution", std::time::Duration::from_secs(10)),
+    ("disk", std::time::Duration::from_secs(30)),
+    ("local_ip", std::time::Duration::from_secs(30)),
+    ("users", std::time::Duration::from_secs(30)),
+];
+
+/// Resolve a field name or alias to its name in `FIELDS`
+///
+/// `cols` and `colors` resolve to `colors`, which isn't in `FIELDS`.
+pub fn canonical_field(name: &str) -> Option<&'static str> {
+    let name = match name {
+        "distro" => "os",
+        "model" => "host",
+        "term" => "terminal",
+        "term_font" => "terminal_font",
+        "cols" => "colors",
+        name => name,
+    };
+    FIELDS
+        .iter()
+        .copied()
+        .chain(["colors"])
+        .find(|field| *field == name)
+}
+
+/// Generate `get_field` and `get_field_mut` from one name-to-field table
+macro_rules! field_accessors {
+    ($($($name:literal)|+ => $field:ident,)*) => {
+        /// Get a specific field by name
+        pub fn get_field(&self, field_name: &str) -> Option<&str> {
+            match field_name {
+                $($($name)|+ => Some(&self.$field),)*
+                _ => None,
+            }
+        }
+
+        /// Get a mutable reference to a specific field by name
+        pub fn get_field_mut(&mut self, field_name: &str) -> Option<&mut String> {
+            match field_name {
+                $($($name)|+ => Some(&mut self.$field),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+/// Main system information structure
+#[derive(Debug)]
+pub struct SystemInfo {
+    pub title: String,
+    pub os: String,
+    /// Machine-readable distro `ID` from os-release, e.g. `ubuntu`
+    pub distro_id: String,
+    /// Space separated `ID_LIKE` parents from os-release, e.g. `arch`
+    pub distro_id_like: String,
+    pub host: String,
+    pub kernel: String,
+    pub uptime: String,
+    /// Seconds since boot, for compact summaries like the window title
+    pub uptime_seconds: Option<u64>,
+    pub datetime: String,
+    pub install_date: String,
+    pub packages: String,
+    /// Package count per manager, in display order
+    pub package_counts: Vec<(usize, String)>,
+    /// Package managers that didn't answer within `behavior.package_timeout_ms`
+    pub skipped_package_managers: Vec<&'static str>,
+    pub shell: String,
+    pub resolution: String,
+    pub de: String,
+    pub wm: String,
+    pub wm_theme: String,
+    pub theme: String,
+    pub icons: String,
+    pub terminal: String,
+    pub terminal_font: String,
+    pub editor: String,
+    pub multiplexer: String,
+    pub cpu: String,
+    pub cpu_temp: String,
+    /// Overall CPU usage in percent, only sampled with `info.cpu_usage`
+    /// or a CPU bar
+    pub cpu_usage: Option<f32>,
+    pub gpu: String,
+    /// Dedicated video memory, only probed with `info.gpu_memory`
+    pub gpu_memory_bytes: Option<u64>,
+    pub memory: String,
+    pub memory_used_bytes: u64,
+    pub memory_total_bytes: u64,
+    pub swap: String,
+    pub disk: String,
+    /// Root filesystem usage, zero when unknown
+    pub disk_used_bytes: u64,
+    pub disk_total_bytes: u64,
+    pub battery: String,
+    /// Battery charge in percent, for the battery bar
+    pub battery_percent: Option<u8>,
+    pub power_profile: String,
+    pub services: String,
+    /// Failed units, or enabled services on non-systemd inits
+    pub services_count: Option<usize>,
+    /// Whether `services_count` counts failures rather than enabled services
+    pub services_failed: bool,
+    pub local_ip: String,
+    pub public_ip: String,
+    pub users: String,
+    pub locale: String,
+    pub gpu_driver: String,
+    pub song: String,
+    pub weather: String,
+    /// Output of each `info.custom` command by key; failed ones are left out
+    pub custom: Vec<(String, String)>,
+    pub colors: String,
+
+    // Internal system handle
+    system: System,
+    /// Hardware facts, loaded from the per-boot cache or just gathered
+    hardware: HardwareSnapshot,
+    /// Whether `hardware` came from the cache, so probes can be skipped
+    hardware_cached: bool,
+}
+
+/// Hardware facts that can't change without a reboot
+///
+/// Cached once per boot so later runs skip the slowest probes.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct HardwareSnapshot {
+    host: Option<String>,
+    cpu_model: Option<String>,
+    gpu: Option<String>,
+}
+
+impl SystemInfo {
+    /// Create a new SystemInfo instance
+    pub fn new() -> Result<Self> {
+        let mut system = System::new_all();
+        system.refresh_all();
+
+        Ok(Self {
+            title: String::new(),
+            os: String::new(),
+            distro_id: String::new(),
+            distro_id_like: String::new(),
+            host: String::new(),
+            kernel: String::new(),
+            uptime: String::new(),
+            uptime_seconds: None,
+            datetime: String::new(),
+            install_date: String::new(),
+            editor: String::new(),
+            multiplexer: String::new(),
+            packages: String::new(),
+            package_counts: Vec::new(),
+            skipped_package_managers: Vec::new(),
+            shell: String::new(),
+            resolution: String::new(),
+            de: String::new(),
+            wm: String::new(),
+            wm_theme: String::new(),
+            theme: String::new(),
+            icons: String::new(),
+            terminal: String::new(),
+            terminal_font: String::new(),
+            cpu: String::new(),
+            cpu_temp: String::new(),
+            cpu_usage: None,
+            gpu_memory_bytes: None,
+            gpu: String::new(),
+            memory: String::new(),
+            memory_used_bytes: 0,
+            memory_total_bytes: 0,
+            swap: String::new(),
+            disk: String::new(),
+            disk_used_bytes: 0,
+            disk_total_bytes: 0,
+            battery: String::new(),
+            battery_percent: None,
+            power_profile: String::new(),
+            services: String::new(),
+            services_count: None,
+            services_failed: false,
+            local_ip: String::new(),
+            public_ip: String::new(),
+            users: String::new(),
+            locale: String::new(),
+            gpu_driver: String::new(),
+            song: String::new(),
+            weather: String::new(),
+            custom: Vec::new(),
+            colors: String::new(),
+            system,
+            hardware: HardwareSnapshot::default(),
+            hardware_cached: false,
+        })
+    }
+
+    /// A fixed, made-up laptop profile for tests and benchmarks
+    ///
+    /// Nothing is gathered; fields without a sample value are `Unknown`.
+    pub fn sample() -> Result<Self> {
+        const MIB: u64 = 1024 * 1024;
+        const GIB: u64 = 1024 * MIB;
+
+        let mut info = Self::new()?;
+        for field in FIELDS {
+            if let Some(value) = info.get_field_mut(field) {
+                *value = "Unknown".to_string();
+            }
+        }
+        info.title = "user@laptop".to_string();
+        info.os = "Arch Linux x86_64".to_string();
+        info.distro_id = "arch".to_string();
+        info.host = "ThinkPad X1 Carbon Gen 9".to_string();
+        info.kernel = "6.8.1-arch1-1".to_string();
+        info.uptime = "3 days, 4 hours, 12 mins".to_string();
+        info.uptime_seconds = Some(((3 * 24 + 4) * 60 + 12) * 60);
+        info.datetime = "Fri 08 Mar 2024 12:24".to_string();
+        info.package_counts = vec![(1843, "pacman".to_string()), (12, "flatpak".to_string())];
+        info.packages = "1843 (pacman), 12 (flatpak)".to_string();
+        info.shell = "bash 5.2.26".to_string();
+        info.resolution = "1920x1080".to_string();
+        info.de = "GNOME 46.2 (Wayland)".to_string();
+        info.wm = "Mutter".to_string();
+        info.terminal = "kitty".to_string();
+        info.cpu = "Intel Core i7-1165G7 (8 cores) @ 4.7GHz".to_string();
+        info.gpu = "Intel TigerLake-LP GT2 [Iris Xe Graphics]".to_string();
+        info.memory_used_bytes = 7421 * MIB;
+        info.memory_total_bytes = 15887 * MIB;
+        info.memory = "7421MiB / 15887MiB".to_string();
+        info.disk_used_bytes = 112 * GIB;
+        info.disk_total_bytes = 476 * GIB;
+        info.disk = "112.00GiB / 476.00GiB (24%)".to_string();
+        info.locale = "en_US.UTF-8".to_string();
+        info.colors = String::new();
+        Ok(info)
+    }
+
+    /// Gather all system information based on configuration
+    ///
+    /// Probes that spawn processes or touch the network run on their own
+    /// threads while the sysinfo-backed fields are gathered on this one.
+    /// Package counts and the weather come from the cache while they're
+    /// fresh; the host, CPU model and GPU are cached until the next reboot,
+    /// see [`crate::cache::boot_key`].
+    pub fn gather_all(&mut self, config: &Config) -> Result<()> {
+        self.system.refresh_all();
+
+        let use_cache = !config.behavior.no_cache;
+        let cache_path = config.behavior.cache_dir.join(CACHE_FILE);
+        let mut cache = if use_cache {
+            FieldCache::load(&cache_path)
+        } else {
+            FieldCache::default()
+        };
+        let ttl = std::time::Duration::from_secs(config.behavior.cache_ttl_secs);
+        let cached_packages: Option<Vec<(usize, String)>> = cache.get("packages", ttl);
+        let boot_key = crate::cache::boot_key().filter(|_| use_cache);
+        if let Some(snapshot) = boot_key
+            .as_deref()
+            .and_then(|key| cache.get_boot::<HardwareSnapshot>(key, "hardware"))
+        {
+            self.hardware = snapshot;
+            self.hardware_cached = true;
+        }
+        let cached_gpu = self.hardware.gpu.clone();
+        let fresh_packages = cached_packages.is_none();
+        let fresh_gpu = cached_gpu.is_none();
+        let mut fresh_weather = false;
+
+        std::thread::scope(|scope| -> Result<()> {
+            let packages = scope.spawn(move || match cached_packages {
+                Some(counts) => (counts, Vec::new()),
+                None => {
+                    let (counts, skipped) = Self::probe_packages(config);
+                    let counts = counts
+                        .into_iter()
+                        .map(|(count, manager)| (count, manager.to_string()))
+                        .collect();
+                    (counts, skipped)
+                }
+            });
+            let resolution = scope.spawn(|| Self::probe_resolution(config));
+            let gpu = scope.spawn(move || cached_gpu.unwrap_or_else(Self::probe_gpu));
+            let gpu_memory = scope.spawn(|| {
+                config
+                    .info
+                    .gpu_memory
+                    .then(Platform::gpu_memory_bytes)
+                    .flatten()
+            });
+            let weather = scope.spawn(|| Self::probe_weather(config, &mut cache));
+            let custom = scope.spawn(|| Self::probe_custom(config));
+
+            self.get_title(config)?;
+            self.get_os(config)?;
+            self.get_host(config)?;
+            self.get_kernel(config)?;
+            self.get_uptime(config)?;
+            self.get_datetime(config)?;
+            self.get_shell(config)?;
+            self.get_de()?;
+            self.get_wm()?;
+            self.get_wm_theme()?;
+            self.get_theme()?;
+            self.get_icons()?;
+            self.get_terminal()?;
+            self.get_terminal_font()?;
+            self.get_editor(config)?;
+            self.get_multiplexer(config)?;
+            self.get_cpu_temp(config)?;
+            self.get_cpu(config)?;
+            self.get_memory(config)?;
+            self.get_swap(config)?;
+            self.get_disk(config)?;
+            self.get_battery()?;
+            self.get_power_profile(config)?;
+            self.get_services(config)?;
+            self.get_install_date(config)?;
+            self.get_local_ip()?;
+            self.get_users(config)?;
+            self.get_locale(config)?;
+            self.get_gpu_driver()?;
+            self.get_song()?;
+            self.get_colors(config)?;
+
+            // A panicking probe only loses its own field
+            let unknown = |_| "Unknown".to_string();
+            (self.package_counts, self.skipped_package_managers) =
+                packages.join().unwrap_or_default();
+            self.packages = format_packages(&self.package_counts, &config.info.package_managers);
+            self.resolution = resolution.join().unwrap_or_else(unknown);
+            self.gpu = gpu.join().unwrap_or_else(unknown);
+            self.gpu_memory_bytes = gpu_memory.join().unwrap_or_default();
+            (self.weather, fresh_weather) = weather
+                .join()
+                .unwrap_or_else(|_| ("Unknown".to_string(), false));
+            self.custom = custom.join().unwrap_or_default();
+
+            // Snap confinement hides the host package databases entirely
+            if crate::utils::detect_sandbox() == Some(crate::utils::Sandbox::Snap) {
+                self.packages = "Not available (snap sandbox)".to_string();
+            }
+
+            Ok(())
+        })?;
+
+        // A GPU that couldn't be probed last time is worth saving once found
+        let found_gpu = fresh_gpu && self.gpu != "Unknown";
+        let fresh_hardware = boot_key.is_some() && (!self.hardware_cached || found_gpu);
+        if use_cache && (fresh_packages || fresh_hardware || fresh_weather) {
+            if fresh_packages && !self.package_counts.is_empty() {
+                cache.set("packages", &self.package_counts);
+            }
+            if let Some(key) = boot_key.as_deref().filter(|_| fresh_hardware) {
+                self.hardware.gpu = (self.gpu != "Unknown").then(|| self.gpu.clone());
+                cache.set_boot(key, "hardware", &self.hardware);
+            }
+            // A cache that can't be written only costs speed next time
+            let _ = cache.save(&cache_path);
+        }
+
+        // Appended after caching so the cached name stays bare
+        if let Some(bytes) = self.gpu_memory_bytes.filter(|_| self.gpu != "Unknown") {
+            self.gpu = format!("{} ({})", self.gpu, format_gpu_memory(bytes));
+        }
+
+        Ok(())
+    }
+
+    /// Get system title (user@hostname)
+    fn get_title(&mut self, config: &Config) -> Result<()> {
+        let username = whoami::username();
+        let hostname = pick_hostname(
+            whoami::fallible::hostname().ok(),
+            std::env::var("HOSTNAME").ok(),
+        );
+
+        self.title = format_title(&username, &hostname, config.info.title_fqdn);
+        Ok(())
+    }
+
+    /// Get operating system information
+    fn get_os(&mut self, config: &Config) -> Result<()> {
+        let version_id = System::os_version().unwrap_or_default();
+        let mut os = OsInfo {
+            name: System::name().unwrap_or_else(|| "Unknown".to_string()),
+            version: version_id.clone(),
+            version_id,
+            distro_id: String::new(),
+            distro_id_like: String::new(),
+        };
+        Platform::os(&mut os);
+        self.distro_id = os.distro_id;
+        self.distro_id_like = os.distro_id_like;
+
+        self.os = match config.info.distro_shorthand {
+            DistroShorthand::Off => join_nonempty(&[&os.name, &os.version]),
+            DistroShorthand::On => join_nonempty(&[&os.name, &os.version_id]),
+            DistroShorthand::Tiny => os.name,
+        };
+
+        // Some probes behave differently under WSL, so make it visible.
+        // Upstream neofetch puts the Windows version before the arch.
+        let wsl = crate::utils::wsl_version();
+        if wsl.is_some() {
+            self.os = format!("{} on {}", self.os, crate::utils::wsl_windows_name());
+        }
+
+        if config.info.os_arch {
+            self.os = format!("{} {}", self.os, crate::utils::machine_arch());
+        }
+
+        if let Some(wsl) = wsl {
+            self.os = format!("{} [WSL{}]", self.os, wsl);
+        }
+
+        let tag = match config.info.show_virt {
+            Some(false) => None,
+            Some(true) => crate::utils::virtualization_tag(true),
+            None => crate::utils::virtualization_tag(false),
+        };
+        if let Some(tag) = tag {
+            self.os = format!("{} ({})", self.os, tag);
+        }
+        Ok(())
+    }
+
+    /// Get host/model information
+    fn get_host(&mut self, config: &Config) -> Result<()> {
+        let container = config
+            .info
+            .show_container
+            .then(crate::utils::detect_container)
+            .flatten();
+        self.set_host(container, Platform::host);
+        Ok(())
+    }
+
+    /// Fill in the host, or the container runtime when given one
+    ///
+    /// The model is probed either way, so the per-boot snapshot is complete
+    /// for runs that don't show the container.
+    fn set_host(&mut self, container: Option<String>, probe: impl FnOnce() -> Option<String>) {
+        if !self.hardware_cached {
+            self.hardware.host = probe().and_then(|host| clean_host_part(&host));
+        }
+
+        // The hardware belongs to the machine outside the container
+        if let Some(runtime) = container {
+            self.host = format!("{} Container", runtime);
+            return;
+        }
+        let host = self.hardware.host.clone();
+
+        // VMs report nothing or a bare "KVM"; name the hypervisor instead
+        let generic = match &host {
+            Some(host) => VM_HOST_NAMES
+                .iter()
+                .any(|name| host.to_lowercase().starts_with(name)),
+            None => true,
+        };
+        let vm = generic
+            .then(|| match crate::utils::detect_virtualization() {
+                Some(hypervisor) => Some(format!("{} Virtual Machine", hypervisor)),
+                None => crate::utils::has_hypervisor_flag().then(|| "Virtual Machine".to_string()),
+            })
+            .flatten();
+
+        self.host = vm.or(host).unwrap_or_else(|| "Unknown".to_string());
+    }
+
+    /// Get kernel information
+    fn get_kernel(&mut self, config: &Config) -> Result<()> {
+        self.kernel = System::kernel_version().unwrap_or_else(|| "Unknown".to_string());
+
+        if !config.info.kernel_shorthand && self.kernel != "Unknown" {
+            self.kernel = format!("{} {}", crate::utils::kernel_name(), self.kernel);
+        }
+        Ok(())
+    }
+
+    /// Get system uptime
+    fn get_uptime(&mut self, config: &Config) -> Result<()> {
+        self.uptime_seconds = uptime_seconds();
+        self.uptime = match (self.uptime_seconds, &config.info.uptime_style) {
+            (Some(seconds), UptimeStyle::Units) => {
+                crate::utils::format_uptime(seconds, &config.info.uptime_shorthand)
+            }
+            (Some(seconds), UptimeStyle::Relative) => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_secs())
+                    .unwrap_or(0);
+                let boot = now.saturating_sub(seconds);
+                crate::utils::format_uptime_since(boot, now, &chrono::Local)
+            }
+            (None, _) => "Unknown".to_string(),
+        };
+        Ok(())
+    }
+
+    /// Get the current local date and time
+    ///
+    /// An empty or invalid `info.datetime_format` leaves it unknown, hiding
+    /// the line.
+    fn get_datetime(&mut self, config: &Config) -> Result<()> {
+        self.datetime = "Unknown".to_string();
+
+        if config.info.datetime_format.is_empty() {
+            return Ok(());
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or(0);
+        if let Some(datetime) =
+            crate::utils::format_datetime(now, &chrono::Local, &config.info.datetime_format)
+        {
+            self.datetime = datetime;
+        }
+        Ok(())
+    }
+
+    /// Get package count, and the managers that didn't answer in time
+    fn probe_packages(config: &Config) -> (Vec<(usize, &'static str)>, Vec<&'static str>) {
+        let timeout = std::time::Duration::from_millis(config.behavior.package_timeout_ms);
+        let mut probes = Platform::package_probes(timeout);
+        probes.extend(
+            config
+                .info
+                .package_managers_extra
+                .iter()
+                .filter_map(|name| extra_package_probe(name, timeout)),
+        );
+
+        let (mut package_managers, skipped) = run_package_probes(probes, timeout);
+        package_managers.extend(Platform::package_counts(timeout));
+        (package_managers, skipped)
+    }
+
+    /// Get shell information
+    fn get_shell(&mut self, config: &Config) -> Result<()> {
+        // The parent process is the shell actually running us, which may
+        // differ from the login shell in $SHELL
+        let Some(shell) = parent_shell().or_else(|| std::env::var("SHELL").ok()) else {
+            self.shell = "Unknown".to_string();
+            return Ok(());
+        };
+
+        let shell_name = std::path::Path::new(&shell)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Unknown");
+
+        self.shell = if config.info.shell_path {
+            shell.clone()
+        } else {
+            shell_name.to_string()
+        };
+
+        // Spawning the shell is slow, so only do it when the version is wanted
+        if config.info.shell_version {
+            if let Some(version) = command_version(&shell, "--version") {
+                self.shell = format!("{} {}", self.shell, version);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the default editor from `$VISUAL`, falling back to `$EDITOR`
+    fn get_editor(&mut self, config: &Config) -> Result<()> {
+        self.editor = "Unknown".to_string();
+
+        if !config.info.editor {
+            return Ok(());
+        }
+
+        let editor = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.trim().is_empty());
+        // Editors are often set with arguments, e.g. `code --wait`
+        let program = match editor.as_deref().and_then(|e| e.split_whitespace().next()) {
+            Some(program) => program.to_string(),
+            None => return Ok(()),
+        };
+
+        self.editor = std::path::Path::new(&program)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| program.clone());
+        if let Some(version) = command_version(&program, "--version") {
+            self.editor = format!("{} {}", self.editor, version);
+        }
+        Ok(())
+    }
+
+    /// Get the terminal multiplexer we're running in
+    ///
+    /// Each one sets its own variable in the sessions it starts, so nothing
+    /// is spawned outside of them.
+    fn get_multiplexer(&mut self, config: &Config) -> Result<()> {
+        self.multiplexer = "Unknown".to_string();
+
+        if !config.info.multiplexer {
+            return Ok(());
+        }
+
+        let (name, version_arg) = if std::env::var_os("TMUX").is_some() {
+            ("tmux", "-V")
+        } else if std::env::var_os("ZELLIJ").is_some() {
+            ("zellij", "--version")
+        } else if std::env::var_os("STY").is_some() {
+            ("screen", "--version")
+        } else {
+            return Ok(());
+        };
+
+        self.multiplexer = match command_version(name, version_arg) {
+            Some(version) => format!("{} {}", name, version),
+            None => name.to_string(),
+        };
+        Ok(())
+    }
+
+    /// Get screen resolution
+    fn probe_resolution(config: &Config) -> String {
+        Platform::resolution(config.info.refresh_rate).unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    /// Get desktop environment
+    fn get_de(&mut self) -> Result<()> {
+        self.de = Platform::de();
+        Ok(())
+    }
+
+    /// Get window manager
+    fn get_wm(&mut self) -> Result<()> {
+        self.wm = Platform::wm();
+        Ok(())
+    }
+
+    /// Get window manager theme
+    fn get_wm_theme(&mut self) -> Result<()> {
+        self.wm_theme = Platform::wm_theme(&self.wm).unwrap_or_else(|| "Unknown".to_string());
+        Ok(())
+    }
+
+    /// Get system theme
+    fn get_theme(&mut self) -> Result<()> {
+        self.theme = "Unknown".to_string();
+        Ok(())
+    }
+
+    /// Get icon theme
+    fn get_icons(&mut self) -> Result<()> {
+        self.icons = "Unknown".to_string();
+        Ok(())
+    }
+
+    /// Get terminal information
+    fn get_terminal(&mut self) -> Result<()> {
+        // TERM says little inside tmux or screen, so look for the emulator
+        if let Some(terminal) = terminal_from_process_tree() {
+            self.terminal = terminal;
+        } else if let Ok(term) = std::env::var("TERM_PROGRAM") {
+            self.terminal = term;
+        } else if let Ok(term) = std::env::var("TERM") {
+            self.terminal = term;
+        } else {
+            self.terminal = "Unknown".to_string();
+        }
+        Ok(())
+    }
+
+    /// Get terminal font
+    fn get_terminal_font(&mut self) -> Result<()> {
+        self.terminal_font = "Unknown".to_string();
+        Ok(())
+    }
+
+    /// Get CPU information
+    fn get_cpu(&mut self, config: &Config) -> Result<()> {
+        let mut speed = if config.info.cpu_speed {
+            self.cpu_frequency_mhz(config)
+                .map(|mhz| format_cpu_speed(mhz, config.info.speed_shorthand))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        if !self.cpu_temp.is_empty() && self.cpu_temp != "Unknown" {
+            speed.push_str(&format!(" [{}]", self.cpu_temp));
+        }
+        // A CPU bar needs the usage even when it isn't printed
+        if config.info.cpu_usage || !matches!(config.format.cpu_display, DisplayMode::Off) {
+            let usage = self.sample_cpu_usage();
+            self.cpu_usage = Some(usage);
+            if config.info.cpu_usage {
+                speed.push_str(&format!(" [{:.0}%]", usage));
+            }
+        }
+
+        // sysinfo's brand is the fallback; its name() is just "cpu0"
+        if !self.hardware_cached {
+            self.hardware.cpu_model = Platform::cpu_name().or_else(|| {
+                self.system
+                    .cpus()
+                    .first()
+                    .map(|cpu| cpu.brand().trim().to_string())
+                    .filter(|brand| !brand.is_empty())
+            });
+        }
+
+        self.cpu = match &self.hardware.cpu_model {
+            Some(cpu_name) => format!(
+                "{} ({} cores){}",
+                crate::utils::clean_cpu_name(cpu_name),
+                self.system.cpus().len(),
+                speed
+            ),
+            None => "Unknown".to_string(),
+        };
+        Ok(())
+    }
+
+    /// Measure overall CPU usage
+    ///
+    /// Usage is the change between two refreshes, so this waits sysinfo's
+    /// minimum update interval before taking the second one.
+    fn sample_cpu_usage(&mut self) -> f32 {
+        self.system.refresh_cpu_usage();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        self.system.refresh_cpu_usage();
+        self.system.global_cpu_info().cpu_usage()
+    }
+
+    /// Get the CPU temperature in the configured unit
+    fn get_cpu_temp(&mut self, config: &Config) -> Result<()> {
+        self.cpu_temp = "Unknown".to_string();
+
+        if matches!(config.info.cpu_temp, CpuTemp::Off) {
+            return Ok(());
+        }
+
+        if let Some(celsius) = Platform::cpu_temp_celsius() {
+            self.cpu_temp = format_temperature(celsius, &config.info.cpu_temp);
+        }
+
+        Ok(())
+    }
+
+    /// Get the CPU frequency in MHz for the configured speed type
+    fn cpu_frequency_mhz(&self, config: &Config) -> Option<f64> {
+        // Fallback to sysinfo
+        Platform::cpu_frequency_mhz(&config.info.speed_type).or_else(|| {
+            self.system
+                .cpus()
+                .first()
+                .map(|cpu| cpu.frequency() as f64)
+                .filter(|mhz| *mhz > 0.0)
+        })
+    }
+
+    /// Get GPU information
+    fn probe_gpu() -> String {
+        Platform::gpu().unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    /// Get memory information
+    fn get_memory(&mut self, config: &Config) -> Result<()> {
+        self.memory_total_bytes = self.system.total_memory();
+        self.memory_used_bytes = self.system.used_memory();
+
+        if let Some((used, total)) = Platform::memory() {
+            self.memory_used_bytes = used;
+            self.memory_total_bytes = total;
+        }
+
+        self.memory = format_memory(
+            self.memory_used_bytes,
+            self.memory_total_bytes,
+            &config.info.memory_unit,
+            config.info.memory_percent,
+        );
+        Ok(())
+    }
+
+    /// Get swap usage
+    fn get_swap(&mut self, config: &Config) -> Result<()> {
+        let total_swap = self.system.total_swap();
+
+        self.swap = if config.info.swap && total_swap > 0 {
+            format_memory(
+                self.system.used_swap(),
+                total_swap,
+                &config.info.memory_unit,
+                config.info.memory_percent,
+            )
+        } else {
+            "Unknown".to_string()
+        };
+        Ok(())
+    }
+
+    /// Get usage of the root filesystem, or the first disk elsewhere
+    fn get_disk(&mut self, config: &Config) -> Result<()> {
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        let root = disks
+            .list()
+            .iter()
+            .filter(|disk| disk.total_space() > 0)
+            .min_by_key(|disk| disk.mount_point() != std::path::Path::new("/"));
+
+        match root {
+            Some(disk) => {
+                self.disk_total_bytes = disk.total_space();
+                self.disk_used_bytes = disk.total_space().saturating_sub(disk.available_space());
+                self.disk = format_memory(
+                    self.disk_used_bytes,
+                    self.disk_total_bytes,
+                    &MemoryUnit::Gib,
+                    config.info.memory_percent,
+                );
+            }
+            None => self.disk = "Unknown".to_string(),
+        }
+        Ok(())
+    }
+
+    /// Get battery information
+    fn get_battery(&mut self) -> Result<()> {
+        let battery = Platform::battery();
+        self.battery_percent = battery.as_ref().map(|(percent, _)| *percent);
+        self.battery = match battery {
+            Some((percent, Some(state))) => format!("{}% [{}]", percent, state),
+            Some((percent, None)) => format!("{}%", percent),
+            None => "Unknown".to_string(),
+        };
+        Ok(())
+    }
+
+    /// Get the active power profile
+    fn get_power_profile(&mut self, config: &Config) -> Result<()> {
+        self.power_profile = Platform::power_profile().unwrap_or_else(|| "Unknown".to_string());
+
+        // Append the profile to the battery row when requested and there is one
+        if config.info.battery_show_profile
+            && self.power_profile != "Unknown"
+            && !self.battery.is_empty()
+            && self.battery != "Unknown"
+        {
+            self.battery = format!("{} [{}]", self.battery, self.power_profile);
+        }
+
+        Ok(())
+    }
+
+    /// Get local IP address
+    fn get_local_ip(&mut self) -> Result<()> {
+        // Simplified - just indicate network is available
+        self.local_ip = "Available".to_string();
+        Ok(())
+    }
+
+    /// Get logged in users
+    fn get_users(&mut self, config: &Config) -> Result<()> {
+        self.users = Platform::users(config.info.users_sessions);
+        Ok(())
+    }
+
+    /// Get system locale
+    fn get_locale(&mut self, config: &Config) -> Result<()> {
+        let locale = pick_locale(|var| std::env::var(var).ok(), Platform::locale);
+
+        self.locale = match locale {
+            Some(locale) if config.info.locale_shorthand => strip_utf8_suffix(&locale).to_string(),
+            Some(locale) => locale,
+            None => "Unknown".to_string(),
+        };
+        Ok(())
+    }
+
+    /// Get GPU driver information
+    fn get_gpu_driver(&mut self) -> Result<()> {
+        self.gpu_driver = "Unknown".to_string();
+        Ok(())
+    }
+
+    /// Get currently playing song
+    fn get_song(&mut self) -> Result<()> {
+        self.song = "Unknown".to_string();
+        Ok(())
+    }
+
+    /// Get a service health summary
+    ///
+    /// Opt-in. On systemd this is the number of failed units; on runit and
+    /// OpenRC systems, the number of enabled services.
+    fn get_services(&mut self, config: &Config) -> Result<()> {
+        self.services = "Unknown".to_string();
+
+        if !config.info.services {
+            return Ok(());
+        }
+
+        if let Some(services) = Platform::services() {
+            let state = if services.failed { "failed" } else { "enabled" };
+            self.services = format!("{} {}", services.count, state);
+            self.services_count = Some(services.count);
+            self.services_failed = services.failed;
+        }
+
+        Ok(())
+    }
+
+    /// Get the OS install date
+    ///
+    /// Opt-in, and hidden when the platform has no trustworthy source.
+    fn get_install_date(&mut self, config: &Config) -> Result<()> {
+        self.install_date = "Unknown".to_string();
+
+        if !config.info.install_date {
+            return Ok(());
+        }
+
+        if let Some(installed) = Platform::install_time() {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0);
+            self.install_date = crate::utils::format_install_date(installed, now, &chrono::Local);
+        }
+
+        Ok(())
+    }
+
+    /// Run the `info.custom` commands concurrently
+    ///
+    /// Commands that fail, time out or print nothing are left out. They keep
+    /// the user's locale, unlike our own probes.
+    fn probe_custom(config: &Config) -> Vec<(String, String)> {
+        std::thread::scope(|scope| {
+            let runs: Vec<_> = config
+                .info
+                .custom
+                .iter()
+                .filter(|custom| !custom.command.trim().is_empty())
+                .map(|custom| (custom.key(), scope.spawn(|| run_custom_command(custom))))
+                .collect();
+
+            runs.into_iter()
+                .filter_map(|(key, run)| Some((key.to_string(), run.join().ok()??)))
+                .collect()
+        })
+    }
+
+    /// Get the current weather from a wttr.in-compatible endpoint
+    ///
+    /// Opt-in only: nothing touches the network unless `info.weather` is set.
+    /// Reports are kept in `cache` for 30 minutes per URL and failures hide
+    /// the line. Also returns whether a report was fetched just now.
+    fn probe_weather(config: &Config, cache: &mut FieldCache) -> (String, bool) {
+        if !config.info.weather {
+            return ("Unknown".to_string(), false);
+        }
+
+        let url = weather_url(&config.info.weather_url, &config.info.weather_location);
+        let cached: Option<(String, String)> = cache.get("weather", WEATHER_CACHE_TTL);
+        if let Some((_, report)) = cached.filter(|(cached_url, _)| *cached_url == url) {
+            return (report, false);
+        }
+
+        match fetch_weather(&url, WEATHER_TIMEOUT) {
+            Some(report) => {
+                cache.set("weather", &(&url, &report));
+                (report, true)
+            }
+            None => ("Unknown".to_string(), false),
+        }
+    }
+
+    /// Get color information
+    fn get_colors(&mut self, config: &Config) -> Result<()> {
+        // The blocks are raw escape codes that `colored` can't strip for us
+        if config.format.no_color {
+            self.colors = String::new();
+            return Ok(());
+        }
+
+        // Legacy conhost smears background colors across the line when the
+        // buffer scrolls, so draw foreground-colored full blocks there instead
+        let style = if crate::utils::is_legacy_conhost() {
+            BlockStyle::Foreground
+        } else {
+            BlockStyle::Background
+        };
+
+        // Generate color blocks for display, one row per group of 8 colors
+        let (start, end) = config.format.block_range;
+        let cell = style.cell().repeat(config.format.block_width as usize);
+        let mut rows = Vec::new();
+
+        let mut row = String::new();
+        for color in start..=end {
+            row.push_str(&style.code(color));
+            row.push_str(&cell);
+            row.push_str("\x1b[0m");
+
+            // Normal and bright colors go on separate rows like neofetch
+            if color % 8 == 7 || color == end {
+                for _ in 0..config.format.block_height.max(1) {
+                    rows.push(row.clone());
+                }
+                row.clear();
+            }
+        }
+
+        self.colors = rows.join("\n");
+        Ok(())
+    }
+
+    field_accessors! {
+        "title" => title,
+        "os" | "distro" => os,
+        "host" | "model" => host,
+        "kernel" => kernel,
+        "uptime" => uptime,
+        "datetime" => datetime,
+        "install_date" => install_date,
+        "packages" => packages,
+        "shell" => shell,
+        "editor" => editor,
+        "resolution" => resolution,
+        "de" => de,
+        "wm" => wm,
+        "wm_theme" => wm_theme,
+        "theme" => theme,
+        "icons" => icons,
+        "terminal" | "term" => terminal,
+        "terminal_font" | "term_font" => terminal_font,
+        "multiplexer" => multiplexer,
+        "cpu" => cpu,
+        "cpu_temp" => cpu_temp,
+        "gpu" => gpu,
+        "memory" => memory,
+        "swap" => swap,
+        "disk" => disk,
+        "battery" => battery,
+        "power_profile" => power_profile,
+        "local_ip" => local_ip,
+        "public_ip" => public_ip,
+        "users" => users,
+        "locale" => locale,
+        "gpu_driver" => gpu_driver,
+        "song" => song,
+        "services" => services,
+        "weather" => weather,
+        "cols" | "colors" => colors,
+    }
+
+    /// Gather a single field from [`VOLATILE_FIELDS`] again
+    ///
+    /// Fields that share a gatherer are refreshed together; all of them are
+    /// returned. Fields that don't change while running give an empty list.
+    pub fn refresh_field(
+        &mut self,
+        field: &str,
+        config: &Config,
+    ) -> Result<&'static [&'static str]> {
+        Ok(match field {
+            "uptime" => {
+                self.get_uptime(config)?;
+                &["uptime"]
+            }
+            "datetime" => {
+                self.get_datetime(config)?;
+                &["datetime"]
+            }
+            "memory" | "swap" => {
+                self.system.refresh_memory();
+                self.get_memory(config)?;
+                self.get_swap(config)?;
+                &["memory", "swap"]
+            }
+            // The temperature is part of the CPU line
+            "cpu" | "cpu_temp" => {
+                self.get_cpu_temp(config)?;
+                self.get_cpu(config)?;
+                &["cpu", "cpu_temp"]
+            }
+            "song" => {
+                self.get_song()?;
+                &["song"]
+            }
+            // The profile may be appended to the battery line
+            "battery" | "power_profile" => {
+                self.get_battery()?;
+                self.get_power_profile(config)?;
+                &["battery", "power_profile"]
+            }
+            "resolution" => {
+                self.resolution = Self::probe_resolution(config);
+                &["resolution"]
+            }
+            "disk" => {
+                self.get_disk(config)?;
+                &["disk"]
+            }
+            "local_ip" => {
+                self.get_local_ip()?;
+                &["local_ip"]
+            }
+            "users" => {
+                self.get_users(config)?;
+                &["users"]
+            }
+            _ => &[],
+        })
+    }
+
+    /// Apply rewrite rules to the gathered values, in order
+    pub fn apply_rewrites(&mut self, rewrites: &[CompiledRewrite]) {
+        for rewrite in rewrites {
+            if let Some(value) = self.get_field_mut(&rewrite.field) {
+                let rewritten = rewrite.regex.replace_all(value, rewrite.replace.as_str());
+                *value = rewritten.into_owned();
+            }
+        }
+    }
+}
+
+/// Trimmed output of a custom info command, `None` when it fails
+fn run_custom_command(custom: &crate::config::CustomInfo) -> Option<String> {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", &custom.command]);
+        command
+    } else {
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", &custom.command]);
+        command
+    };
+    let output = crate::utils::output_with_timeout(
+        &mut command,
+        std::time::Duration::from_millis(custom.timeout_ms),
+    )
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Host strings hypervisors report instead of a real model, lowercased
+const VM_HOST_NAMES: &[&str] = &[
+    "kvm",
+    "standard pc",
+    "virtual machine",
+    "virtualbox",
+    "vmware virtual platform",
+    "hvm domu",
+    "bochs",
+];
+
+impl Serialize for SystemInfo {
+    /// Serialize every field in `FIELDS`, with undetected values as empty
+    /// strings so the schema stays stable
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(FIELDS.len() + 12))?;
+        for field in FIELDS {
+            let value = self.get_field(field).unwrap_or("");
+            let value = if value == "Unknown" { "" } else { value };
+            map.serialize_entry(field, value)?;
+        }
+
+        // Raw values so scripts don't have to parse the human-readable strings
+        map.serialize_entry("uptime_seconds", &self.uptime_seconds)?;
+        map.serialize_entry("memory_used_bytes", &self.memory_used_bytes)?;
+        map.serialize_entry("memory_total_bytes", &self.memory_total_bytes)?;
+        map.serialize_entry("disk_used_bytes", &self.disk_used_bytes)?;
+        map.serialize_entry("disk_total_bytes", &self.disk_total_bytes)?;
+        map.serialize_entry("distro_id", &self.distro_id)?;
+        map.serialize_entry("services_count", &self.services_count)?;
+        map.serialize_entry("cpu_usage", &self.cpu_usage.map(|usage| usage.round()))?;
+        map.serialize_entry("gpu_memory_bytes", &self.gpu_memory_bytes)?;
+        map.serialize_entry("battery_percent", &self.battery_percent)?;
+        let package_counts: std::collections::BTreeMap<&str, usize> = self
+            .package_counts
+            .iter()
+            .map(|(count, manager)| (manager.as_str(), *count))
+            .collect();
+        map.serialize_entry("package_counts", &package_counts)?;
+        let custom: std::collections::BTreeMap<&str, &str> = self
+            .custom
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        map.serialize_entry("custom", &custom)?;
+        map.end()
+    }
+}
+
+/// Seconds since boot
+///
+/// sysinfo covers every platform including Windows and the BSDs; the native
+/// sources are only consulted when it can't tell.
+fn uptime_seconds() -> Option<u64> {
+    uptime_from(System::uptime(), Platform::uptime_seconds)
+}
+
+/// sysinfo's uptime, or the native one when sysinfo reports 0
+fn uptime_from(sysinfo: u64, native: impl FnOnce() -> Option<u64>) -> Option<u64> {
+    match sysinfo {
+        0 => native(),
+        seconds => Some(seconds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{InfoConfig, RewriteRule};
+
+    #[test]
+    fn uptime_falls_back_to_the_native_source() {
+        assert_eq!(uptime_from(90, || panic!("native source read")), Some(90));
+        assert_eq!(uptime_from(0, || Some(12)), Some(12));
+        assert_eq!(uptime_from(0, || None), None);
+    }
+
+    #[test]
+    fn cached_host_survives_showing_a_container() {
+        let mut info = SystemInfo::sample().unwrap();
+        info.hardware_cached = false;
+        info.set_host(Some("Docker".to_string()), || {
+            Some("ThinkPad X1 Carbon Gen 9".to_string())
+        });
+        assert_eq!(info.host, "Docker Container");
+
+        let mut cache = FieldCache::default();
+        cache.set_boot("boot", "hardware", &info.hardware);
+
+        // Same boot, --show-container off
+        let mut info = SystemInfo::sample().unwrap();
+        info.hardware = cache.get_boot("boot", "hardware").unwrap();
+        info.hardware_cached = true;
+        info.set_host(None, || panic!("host probed despite the snapshot"));
+        assert_eq!(info.host, "ThinkPad X1 Carbon Gen 9");
+    }
+
+    fn compile(rules: &[(&str, &str, &str)]) -> Vec<CompiledRewrite> {
+        let info = InfoConfig {
+            rewrite: rules
+                .iter()
+                .map(|(field, pattern, replace)| RewriteRule {
+                    field: field.to_string(),
+                    pattern: pattern.to_string(),
+                    replace: replace.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        info.compile_rewrites().unwrap()
+    }
+
+    #[test]
+    fn every_field_has_accessors() {
+        let mut info = SystemInfo::sample().unwrap();
+        for field in FIELDS {
+            assert!(info.get_field(field).is_some(), "{}", field);
+            assert!(info.get_field_mut(field).is_some(), "{}", field);
+        }
+    }
+
+    #[test]
+    fn chained_rewrites_apply_in_order() {
+        let mut info = SystemInfo::sample().unwrap();
+        info.cpu = "AMD Ryzen 7 5800H with Radeon Graphics (16) @ 4.4GHz".to_string();
+        info.de = "GNOME Shell 46.2".to_string();
+
+        info.apply_rewrites(&compile(&[
+            ("cpu", " with Radeon Graphics", ""),
+            ("cpu", r"\(16\)", "(8C/16T)"),
+            ("cpu", "8C", "eight cores"),
+            ("de", "GNOME Shell", "GNOME"),
+        ]));
+
+        assert_eq!(info.cpu, "AMD Ryzen 7 5800H (eight cores/16T) @ 4.4GHz");
+        assert_eq!(info.de, "GNOME 46.2");
+    }
+
+    #[test]
+    fn rewrites_handle_unicode() {
+        let mut info = SystemInfo::sample().unwrap();
+        info.host = "ThinkPad X1 Carbon Gen 9".to_string();
+        info.locale = "日本語 (ja_JP)".to_string();
+
+        info.apply_rewrites(&compile(&[
+            ("model", "ThinkPad", "💻"),
+            ("locale", r"^\p{Han}+", "ニホンゴ"),
+            ("locale", r"ニホンゴ \((\w+)\)", "$1 — ニホンゴ"),
+        ]));
+
+        assert_eq!(info.host, "💻 X1 Carbon Gen 9");
+        assert_eq!(info.locale, "ja_JP — ニホンゴ");
+    }
+
+    /// Serve `body` over HTTP on a local port, or never answer if it's `None`
+    ///
+    /// Gives the weather URL to use and the request lines received so far.
+    fn weather_server(
+        body: Option<&'static str>,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/{{location}}?format=3",
+            listener.local_addr().unwrap()
+        );
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request_line = String::new();
+                BufReader::new(&stream)
+                    .read_line(&mut request_line)
+                    .unwrap();
+                seen.lock()
+                    .unwrap()
+                    .push(request_line.trim_end().to_string());
+                match body {
+                    Some(body) => write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .unwrap(),
+                    None => std::thread::sleep(Duration::from_secs(10)),
+                }
+            }
+        });
+        (url, requests)
+    }
+
+    fn weather_config(url: &str, location: &str) -> Config {
+        Config {
+            info: InfoConfig {
+                weather: true,
+                weather_url: url.to_string(),
+                weather_location: location.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn weather_location_is_percent_encoded() {
+        let url = "https://wttr.in/{location}?format=3";
+        for (location, expected) in [
+            ("", "https://wttr.in/?format=3"),
+            ("Berlin", "https://wttr.in/Berlin?format=3"),
+            ("New York", "https://wttr.in/New%20York?format=3"),
+            ("São Paulo", "https://wttr.in/S%C3%A3o%20Paulo?format=3"),
+            (
+                "a&b=c?d#e/f",
+                "https://wttr.in/a%26b%3Dc%3Fd%23e%2Ff?format=3",
+            ),
+            (
+                "~Eiffel-tower_1.0",
+                "https://wttr.in/~Eiffel-tower_1.0?format=3",
+            ),
+        ] {
+            assert_eq!(weather_url(url, location), expected, "{:?}", location);
+        }
+    }
+
+    #[test]
+    fn weather_is_fetched_once_and_then_cached() {
+        if !crate::utils::command_exists("curl") && !crate::utils::command_exists("wget") {
+            return;
+        }
+        let (url, requests) = weather_server(Some("New York: ⛅️  +12°C\n"));
+        let mut cache = FieldCache::default();
+
+        let config = weather_config(&url, "New York");
+        let first = SystemInfo::probe_weather(&config, &mut cache);
+        assert_eq!(first, ("New York: ⛅️  +12°C".to_string(), true));
+        let second = SystemInfo::probe_weather(&config, &mut cache);
+        assert_eq!(second, ("New York: ⛅️  +12°C".to_string(), false));
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["GET /New%20York?format=3 HTTP/1.1"]
+        );
+
+        // Another location isn't answered from the cache
+        let config = weather_config(&url, "Oslo");
+        assert!(SystemInfo::probe_weather(&config, &mut cache).1);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn weather_gives_up_on_a_silent_endpoint() {
+        if !crate::utils::command_exists("curl") && !crate::utils::command_exists("wget") {
+            return;
+        }
+        let (url, requests) = weather_server(None);
+        let url = weather_url(&url, "Berlin");
+
+        let started = std::time::Instant::now();
+        assert_eq!(fetch_weather(&url, Duration::from_millis(500)), None);
+        assert!(
+            started.elapsed() < Duration::from_secs(3),
+            "{:?}",
+            started.elapsed()
+        );
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn weather_failures_are_not_cached() {
+        let mut cache = FieldCache::default();
+        // Nothing listens on port 9 (discard) here
+        let config = weather_config("http://127.0.0.1:9/{location}", "Berlin");
+
+        assert_eq!(
+            SystemInfo::probe_weather(&config, &mut cache),
+            ("Unknown".to_string(), false)
+        );
+        assert_eq!(
+            cache.get::<(String, String)>("weather", WEATHER_CACHE_TTL),
+            None
+        );
+    }
+}
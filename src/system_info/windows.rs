@@ -0,0 +1,253 @@
+//! Windows system information
+//!
+//! Most fields come from sysinfo; this module covers what it doesn't.
+
+use super::common::{add_user_session, clean_host_part, format_users, PackageProbe};
+use super::PlatformGather;
+use std::time::Duration;
+
+/// Windows implementation of [`PlatformGather`]
+pub(super) struct Windows;
+
+impl PlatformGather for Windows {
+    fn host() -> Option<String> {
+        // wmic is gone from recent Windows 11 builds, the registry isn't
+        let (manufacturer, model) = wmic_host().or_else(registry_host)?;
+        Some(match manufacturer {
+            Some(manufacturer) if !model.starts_with(&manufacturer) => {
+                format!("{} {}", manufacturer, model)
+            }
+            _ => model,
+        })
+    }
+
+    /// Each manager is only asked when it's installed
+    fn package_probes(timeout: Duration) -> Vec<PackageProbe> {
+        let mut probes: Vec<PackageProbe> = Vec::new();
+        if crate::utils::command_exists("choco") {
+            probes.push(("choco", Box::new(count_choco_packages)));
+        }
+        if crate::utils::command_exists("scoop") {
+            probes.push(("scoop", Box::new(count_scoop_packages)));
+        }
+        if crate::utils::command_exists("winget") {
+            probes.push(("winget", Box::new(move || count_winget_packages(timeout))));
+        }
+        probes
+    }
+
+    fn resolution(refresh_rate: bool) -> Option<String> {
+        // One "Key=Value" block per video controller, separated by blank lines
+        let output = crate::utils::execute_command_with_timeout(
+            "wmic",
+            &[
+                "path",
+                "Win32_VideoController",
+                "get",
+                "CurrentHorizontalResolution,CurrentVerticalResolution,CurrentRefreshRate",
+                "/value",
+            ],
+            crate::utils::COMMAND_TIMEOUT,
+        )
+        .ok()?;
+
+        let resolutions = parse_video_controllers(&output, refresh_rate);
+        (!resolutions.is_empty()).then(|| resolutions.join(", "))
+    }
+
+    fn de() -> String {
+        "Windows".to_string()
+    }
+
+    fn wm() -> String {
+        "Desktop Window Manager".to_string()
+    }
+
+    /// The user's locale from `GetUserDefaultLocaleName`, e.g. `en-US`
+    fn locale() -> Option<String> {
+        use winapi::um::winnls::GetUserDefaultLocaleName;
+
+        // LOCALE_NAME_MAX_LENGTH, including the terminating NUL
+        let mut buffer = [0u16; 85];
+        // SAFETY: the buffer is valid for its full length, which is passed
+        // along; the call writes at most that many characters.
+        let length = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+        if length <= 1 {
+            return None;
+        }
+        // The returned length counts the NUL
+        Some(String::from_utf16_lossy(&buffer[..length as usize - 1]))
+    }
+
+    /// `query user` lists every session; Home editions lack it, leaving
+    /// only the current user
+    fn users(sessions: bool) -> String {
+        let output = crate::utils::execute_command_with_timeout(
+            "query",
+            &["user"],
+            crate::utils::COMMAND_TIMEOUT,
+        )
+        .unwrap_or_default();
+        let users = parse_query_user(&output);
+        if users.is_empty() {
+            return whoami::username();
+        }
+        format_users(&users, sessions)
+    }
+}
+
+/// Users and session names from `query user` output
+///
+/// Rows look like `>alice  console  1  Active  none  1/2/2024 9:00 AM`,
+/// with `>` marking our own session. Disconnected sessions have no name,
+/// so the ID comes second.
+fn parse_query_user(output: &str) -> Vec<(String, Vec<String>)> {
+    let mut users = Vec::new();
+    for line in output.lines().skip(1) {
+        let mut columns = line.trim_start_matches([' ', '>']).split_whitespace();
+        let name = match columns.next() {
+            Some(name) => name,
+            None => continue,
+        };
+        let session = columns
+            .next()
+            .filter(|session| session.parse::<u32>().is_err());
+        add_user_session(&mut users, name, session);
+    }
+    users
+}
+
+/// Count Chocolatey packages, one directory each under its `lib`
+///
+/// Reading the directory is much faster than `choco list`, which also
+/// changed its flags between major versions.
+fn count_choco_packages() -> Option<usize> {
+    let root = std::env::var_os("ChocolateyInstall")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from(r"C:\ProgramData\chocolatey"));
+    let count = std::fs::read_dir(root.join("lib")).ok()?.count();
+    (count > 0).then_some(count)
+}
+
+/// Count Scoop apps, one directory each under `~/scoop/apps`
+fn count_scoop_packages() -> Option<usize> {
+    let root = std::env::var_os("SCOOP")
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join("scoop")))?;
+    // Scoop installs itself as an app too
+    let count = std::fs::read_dir(root.join("apps"))
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name() != "scoop")
+        .count();
+    (count > 0).then_some(count)
+}
+
+/// Count the rows of the table `winget list` prints
+fn count_winget_packages(timeout: Duration) -> Option<usize> {
+    let output = crate::utils::output_with_timeout(
+        crate::utils::command("winget").args(["list", "--accept-source-agreements"]),
+        timeout,
+    )
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let count = count_winget_rows(&String::from_utf8_lossy(&output.stdout));
+    (count > 0).then_some(count)
+}
+
+/// Rows below the dashed line under the table header
+///
+/// Progress spinners are drawn above the header, so anything before the
+/// dashes is skipped; a lone `-` is a spinner frame, not the line.
+fn count_winget_rows(output: &str) -> usize {
+    output
+        .lines()
+        .skip_while(|line| {
+            let line = line.trim();
+            line.len() < 2 || !line.chars().all(|c| c == '-')
+        })
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .count()
+}
+
+/// Resolutions from `wmic path Win32_VideoController ... /value` output
+///
+/// Controllers without an attached display leave the fields empty and are
+/// skipped.
+fn parse_video_controllers(output: &str, refresh_rate: bool) -> Vec<String> {
+    let mut resolutions = Vec::new();
+    let (mut width, mut height, mut refresh) = (None, None, None);
+
+    // The trailing empty line flushes the last block
+    for line in output.lines().map(str::trim).chain(std::iter::once("")) {
+        if line.is_empty() {
+            if let (Some(width), Some(height)) = (width.take(), height.take()) {
+                let mut resolution = format!("{}x{}", width, height);
+                if let Some(hz) = refresh.filter(|_| refresh_rate) {
+                    resolution.push_str(&format!(" @ {}Hz", hz));
+                }
+                resolutions.push(resolution);
+            }
+            refresh = None;
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let value = value.trim().parse::<u32>().ok().filter(|value| *value > 0);
+        match key {
+            "CurrentHorizontalResolution" => width = value,
+            "CurrentVerticalResolution" => height = value,
+            "CurrentRefreshRate" => refresh = value,
+            _ => {}
+        }
+    }
+
+    resolutions
+}
+
+/// Manufacturer and model from WMI's `Win32_ComputerSystem`
+fn wmic_host() -> Option<(Option<String>, String)> {
+    // `/value` gives "Manufacturer=Dell Inc." and "Model=XPS 13" lines
+    let output = crate::utils::execute_command_with_timeout(
+        "wmic",
+        &["computersystem", "get", "manufacturer,model", "/value"],
+        crate::utils::COMMAND_TIMEOUT,
+    )
+    .ok()?;
+
+    let field = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(key))
+            .and_then(clean_host_part)
+    };
+    Some((field("Manufacturer="), field("Model=")?))
+}
+
+/// Manufacturer and model from the firmware values in the registry
+fn registry_host() -> Option<(Option<String>, String)> {
+    // Lines look like "    SystemProductName    REG_SZ    XPS 15 9500"
+    let value = |name: &str| {
+        crate::utils::execute_command_with_timeout(
+            "reg",
+            &[
+                "query",
+                r"HKLM\HARDWARE\DESCRIPTION\System\BIOS",
+                "/v",
+                name,
+            ],
+            crate::utils::COMMAND_TIMEOUT,
+        )
+        .ok()?
+        .lines()
+        .find_map(|line| line.split_once("REG_SZ"))
+        .and_then(|(_, value)| clean_host_part(value))
+    };
+    Some((value("SystemManufacturer"), value("SystemProductName")?))
+}
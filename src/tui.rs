@@ -0,0 +1,637 @@
+//! Interactive `--tui` mode for browsing fields and editing the config
+//!
+//! The rendered output is shown next to the list of info lines, which can be
+//! toggled and reordered, a logo picker and the logo color slots. Changes are
+//! previewed right away; saving writes only the edited keys back to the
+//! config file, leaving its comments and other settings alone.
+
+use crate::ascii_art::AsciiArt;
+use crate::config::Config;
+use crate::output;
+use crate::system_info::{canonical_field, SystemInfo, FIELDS};
+use anyhow::{bail, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::path::PathBuf;
+
+/// Config keys the TUI edits, and the only ones it saves
+pub const EDITED_KEYS: &[&str] = &["info.order", "display.ascii_distro", "display.ascii_colors"];
+
+/// Logo color slots offered for editing
+const COLOR_SLOTS: usize = 6;
+
+/// Key bindings shown at the bottom
+const HELP: &str =
+    "Tab pane · ↑↓ select · Space toggle · Shift+↑↓ move · ←→ logo/slot · +/- color · s save · q quit";
+
+/// The part of the screen keys go to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Fields,
+    Logo,
+    Colors,
+}
+
+impl Pane {
+    fn next(self) -> Self {
+        match self {
+            Pane::Fields => Pane::Logo,
+            Pane::Logo => Pane::Colors,
+            Pane::Colors => Pane::Fields,
+        }
+    }
+
+    fn previous(self) -> Self {
+        self.next().next()
+    }
+}
+
+/// State of the TUI, independent of the terminal
+pub struct App<'a> {
+    system_info: &'a SystemInfo,
+    config: Config,
+    config_path: PathBuf,
+    /// Every info line and whether it's shown, in display order
+    fields: Vec<(String, bool)>,
+    selected: usize,
+    pane: Pane,
+    /// Built-in logo names; `None` picks the logo from the detected OS
+    logos: Vec<String>,
+    logo: Option<usize>,
+    /// One color per logo line in turn, see `display.ascii_colors`
+    colors: Vec<String>,
+    slot: usize,
+    status: String,
+    quit: bool,
+}
+
+impl<'a> App<'a> {
+    /// Start editing `config`, which is saved to `config_path`
+    pub fn new(system_info: &'a SystemInfo, config: Config, config_path: PathBuf) -> Self {
+        // Lines in the order come first and are shown; everything else that
+        // could be listed follows, hidden
+        let mut fields: Vec<(String, bool)> = config
+            .info
+            .order
+            .iter()
+            .map(|name| (name.clone(), true))
+            .collect();
+        let available = ["title", "underline"]
+            .into_iter()
+            .chain(FIELDS.iter().copied())
+            .chain(["colors"]);
+        for name in available {
+            // The order may use aliases such as `cols` or `distro`
+            if !fields
+                .iter()
+                .any(|(listed, _)| canonical_field(listed).unwrap_or(listed) == name)
+            {
+                fields.push((name.to_string(), false));
+            }
+        }
+
+        let logos: Vec<String> = AsciiArt::shared()
+            .logo_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let logo = config
+            .display
+            .ascii_distro
+            .as_ref()
+            .and_then(|distro| logos.iter().position(|name| name == distro));
+
+        let mut colors = config.display.ascii_colors.clone();
+        colors.resize(COLOR_SLOTS.max(colors.len()), "distro".to_string());
+
+        Self {
+            system_info,
+            config,
+            config_path,
+            fields,
+            selected: 0,
+            pane: Pane::Fields,
+            logos,
+            logo,
+            colors,
+            slot: 0,
+            status: String::new(),
+            quit: false,
+        }
+    }
+
+    /// The config with the edits applied
+    pub fn config(&self) -> Config {
+        let mut config = self.config.clone();
+        config.info.order = self
+            .fields
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(name, _)| name.clone())
+            .collect();
+        config.display.ascii_distro = self.logo.map(|index| self.logos[index].clone());
+
+        // Trailing `distro` slots change nothing, the colors repeat anyway
+        let mut colors = self.colors.clone();
+        while colors.len() > 1 && colors.last().map(String::as_str) == Some("distro") {
+            colors.pop();
+        }
+        config.display.ascii_colors = colors;
+        config
+    }
+
+    /// Whether the user asked to leave
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// React to a key press
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('s') => self.save(),
+            KeyCode::Tab => self.pane = self.pane.next(),
+            KeyCode::BackTab => self.pane = self.pane.previous(),
+            code => match self.pane {
+                Pane::Fields => self.handle_fields_key(code, shift),
+                Pane::Logo => self.handle_logo_key(code),
+                Pane::Colors => self.handle_colors_key(code),
+            },
+        }
+    }
+
+    fn handle_fields_key(&mut self, code: KeyCode, shift: bool) {
+        let last = self.fields.len().saturating_sub(1);
+        let previous = self.selected;
+        match code {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => {
+                self.selected = self.selected.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => {
+                self.selected = (self.selected + 1).min(last)
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                if let Some((_, shown)) = self.fields.get_mut(self.selected) {
+                    *shown = !*shown;
+                }
+            }
+            _ => {}
+        }
+
+        // Shift (or a capital J/K) carries the field along
+        let moving = shift || matches!(code, KeyCode::Char('J') | KeyCode::Char('K'));
+        if moving && self.selected != previous {
+            self.fields.swap(previous, self.selected);
+        }
+    }
+
+    fn handle_logo_key(&mut self, code: KeyCode) {
+        // Positions are 0 for the detected logo, then each built-in one
+        let choices = self.logos.len() + 1;
+        let position = self.logo.map_or(0, |index| index + 1);
+        let position = match code {
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Up | KeyCode::Char('k') => {
+                (position + choices - 1) % choices
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Down | KeyCode::Char('j') => {
+                (position + 1) % choices
+            }
+            _ => return,
+        };
+        self.logo = position.checked_sub(1);
+    }
+
+    fn handle_colors_key(&mut self, code: KeyCode) {
+        let slots = self.colors.len();
+        match code {
+            KeyCode::Left | KeyCode::Char('h') => self.slot = (self.slot + slots - 1) % slots,
+            KeyCode::Right | KeyCode::Char('l') => self.slot = (self.slot + 1) % slots,
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('+') => self.cycle_color(1),
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('-') => self.cycle_color(-1),
+            _ => {}
+        }
+    }
+
+    /// Step the selected slot through `distro`, `fg` and colors 0-15
+    fn cycle_color(&mut self, step: i32) {
+        const CHOICES: i32 = 18;
+        let position = match self.colors[self.slot].as_str() {
+            "distro" => 0,
+            "fg" => 1,
+            number => number.parse::<i32>().map_or(0, |number| number.min(15) + 2),
+        };
+        self.colors[self.slot] = match (position + step).rem_euclid(CHOICES) {
+            0 => "distro".to_string(),
+            1 => "fg".to_string(),
+            number => (number - 2).to_string(),
+        };
+    }
+
+    /// Write the edited keys to the config file
+    fn save(&mut self) {
+        self.status = match self.config().save_keys(&self.config_path, EDITED_KEYS) {
+            Ok(()) => format!("Saved to {}", self.config_path.display()),
+            Err(err) => format!("Save failed: {:#}", err),
+        };
+    }
+
+    /// Lay out and draw every widget
+    pub fn draw(&self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, preview] =
+            Layout::horizontal([Constraint::Length(28), Constraint::Min(10)]).areas(main);
+        let [fields, logo, colors] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .areas(sidebar);
+
+        self.draw_fields(frame, fields);
+        self.draw_logo(frame, logo);
+        self.draw_colors(frame, colors);
+        self.draw_preview(frame, preview);
+
+        let footer_text = if self.status.is_empty() {
+            HELP
+        } else {
+            &self.status
+        };
+        frame.render_widget(
+            Paragraph::new(footer_text).style(Style::new().add_modifier(Modifier::DIM)),
+            footer,
+        );
+    }
+
+    fn pane_block(&self, pane: Pane, title: &'static str) -> Block<'static> {
+        let block = Block::bordered().title(title);
+        if self.pane == pane {
+            block.border_style(Style::new().fg(Color::Cyan))
+        } else {
+            block
+        }
+    }
+
+    fn draw_fields(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .fields
+            .iter()
+            .map(|(name, shown)| {
+                let check = if *shown { "[x] " } else { "[ ] " };
+                ListItem::new(format!("{}{}", check, name))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.pane_block(Pane::Fields, " Fields "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_logo(&self, frame: &mut Frame, area: Rect) {
+        let name = match self.logo {
+            Some(index) => self.logos[index].as_str(),
+            None => "auto (detected)",
+        };
+        frame.render_widget(
+            Paragraph::new(format!("◀ {} ▶", name)).block(self.pane_block(Pane::Logo, " Logo ")),
+            area,
+        );
+    }
+
+    fn draw_colors(&self, frame: &mut Frame, area: Rect) {
+        let spans: Vec<Span> = self
+            .colors
+            .iter()
+            .enumerate()
+            .flat_map(|(slot, color)| {
+                let style = match color.parse::<u8>() {
+                    Ok(number) => Style::new().fg(Color::Indexed(number)),
+                    Err(_) => Style::new(),
+                };
+                let style = if slot == self.slot && self.pane == Pane::Colors {
+                    style.add_modifier(Modifier::REVERSED)
+                } else {
+                    style
+                };
+                let short = match color.as_str() {
+                    "distro" => "d",
+                    other => other,
+                };
+                [Span::styled(short.to_string(), style), Span::raw(" ")]
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)).block(self.pane_block(Pane::Colors, " Logo colors ")),
+            area,
+        );
+    }
+
+    fn draw_preview(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(" Preview ");
+        let width = block.inner(area).width as usize;
+        let text = match output::render_layout(self.system_info, &self.config(), width) {
+            Ok(rendered) => ansi_to_text(&rendered),
+            Err(err) => Text::from(format!("Can't render: {:#}", err)),
+        };
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+}
+
+/// Run the TUI until the user quits, restoring the terminal afterwards
+pub fn run(system_info: &SystemInfo, config: Config, config_path: PathBuf) -> Result<()> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() || !std::io::stdin().is_terminal() {
+        bail!("--tui needs an interactive terminal");
+    }
+
+    let mut app = App::new(system_info, config, config_path);
+
+    // Raw mode must be switched off again on every way out, errors included
+    let terminal = ratatui::try_init();
+    let _restore = RestoreTerminal;
+    let mut terminal = terminal?;
+
+    while !app.should_quit() {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            // Windows reports releases too
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Leaves raw mode and the alternate screen when dropped
+struct RestoreTerminal;
+
+impl Drop for RestoreTerminal {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// Convert text with SGR escape codes into styled lines
+///
+/// Handles the codes the renderer emits: bold, the 16 base colors, 256-color
+/// and truecolor foregrounds and backgrounds, and resets.
+fn ansi_to_text(text: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut style = Style::new();
+
+    for line in text.split('\n') {
+        let mut spans = Vec::new();
+        let mut rest = line;
+        while let Some(start) = rest.find('\x1b') {
+            if start > 0 {
+                spans.push(Span::styled(rest[..start].to_string(), style));
+            }
+            let sequence = &rest[start + 1..];
+            let Some(end) = sequence.find(|c: char| c.is_ascii_alphabetic()) else {
+                rest = "";
+                break;
+            };
+            if let Some(params) = sequence[..end].strip_prefix('[') {
+                if sequence[end..].starts_with('m') {
+                    style = apply_sgr(style, params);
+                }
+            }
+            rest = &sequence[end + 1..];
+        }
+        if !rest.is_empty() {
+            spans.push(Span::styled(rest.to_string(), style));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    Text::from(lines)
+}
+
+/// Apply the parameters of one SGR sequence, e.g. `1;36`, to `style`
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let codes: Vec<u16> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let mut codes = codes.iter().copied();
+
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::new(),
+            1 => style.add_modifier(Modifier::BOLD),
+            30..=37 => style.fg(Color::Indexed((code - 30) as u8)),
+            90..=97 => style.fg(Color::Indexed((code - 90 + 8) as u8)),
+            40..=47 => style.bg(Color::Indexed((code - 40) as u8)),
+            100..=107 => style.bg(Color::Indexed((code - 100 + 8) as u8)),
+            39 => style.fg(Color::Reset),
+            49 => style.bg(Color::Reset),
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(|index| Color::Indexed(index as u8)),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r as u8, g as u8, b as u8)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (code, color) {
+                    (38, Some(color)) => style.fg(color),
+                    (_, Some(color)) => style.bg(color),
+                    _ => style,
+                }
+            }
+            _ => style,
+        };
+    }
+
+    style
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn draw(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn headless_smoke_test() {
+        let system_info = SystemInfo::sample().unwrap();
+        let mut app = App::new(
+            &system_info,
+            Config::default(),
+            PathBuf::from("unused.toml"),
+        );
+
+        let screen = draw(&app);
+        assert!(screen.contains("[x] title"));
+        assert!(screen.contains("user@laptop"));
+        assert!(screen.contains("Arch Linux x86_64"));
+
+        // Every key in every pane, then draw again
+        for pane in 0..3 {
+            for code in [
+                KeyCode::Up,
+                KeyCode::Down,
+                KeyCode::Left,
+                KeyCode::Right,
+                KeyCode::Char(' '),
+                KeyCode::Char('+'),
+                KeyCode::Char('-'),
+                KeyCode::Char('J'),
+                KeyCode::Char('K'),
+            ] {
+                press(&mut app, code);
+                draw(&app);
+            }
+            assert_ne!(pane, 3);
+            press(&mut app, KeyCode::Tab);
+        }
+        assert!(!app.should_quit());
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn toggling_and_reordering_edit_the_order() {
+        let system_info = SystemInfo::sample().unwrap();
+        let mut config = Config::default();
+        config.info.order = vec!["title".into(), "os".into(), "kernel".into()];
+        let mut app = App::new(&system_info, config, PathBuf::from("unused.toml"));
+
+        // Move `os` below `kernel`, then hide `title`
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('J'));
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.config().info.order, ["kernel", "os"]);
+
+        // Show the first hidden field, which follows the listed ones
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.config().info.order, ["kernel", "os", "underline"]);
+        let screen = draw(&app);
+        let kernel = screen.find("Kernel:").unwrap();
+        assert!(screen.find("Arch Linux x86_64").unwrap() > kernel);
+        assert!(!screen.contains("user@laptop"));
+    }
+
+    #[test]
+    fn aliases_in_the_order_are_not_listed_twice() {
+        let system_info = SystemInfo::sample().unwrap();
+        let mut config = Config::default();
+        assert!(config.info.order.iter().any(|name| name == "cols"));
+        let app = App::new(&system_info, config.clone(), PathBuf::from("unused.toml"));
+        let listed = |app: &App, field: &str| -> Vec<(String, bool)> {
+            app.fields
+                .iter()
+                .filter(|(name, _)| canonical_field(name) == Some(field))
+                .cloned()
+                .collect()
+        };
+        assert_eq!(listed(&app, "colors"), [("cols".to_string(), true)]);
+
+        config.info.order = vec!["distro".into(), "model".into(), "cols".into()];
+        let app = App::new(&system_info, config, PathBuf::from("unused.toml"));
+        assert_eq!(listed(&app, "os"), [("distro".to_string(), true)]);
+        assert_eq!(listed(&app, "host"), [("model".to_string(), true)]);
+        assert_eq!(listed(&app, "colors"), [("cols".to_string(), true)]);
+    }
+
+    #[test]
+    fn logo_and_color_slots_edit_the_display_settings() {
+        let system_info = SystemInfo::sample().unwrap();
+        let mut app = App::new(
+            &system_info,
+            Config::default(),
+            PathBuf::from("unused.toml"),
+        );
+        assert_eq!(app.config().display.ascii_distro, None);
+
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Right);
+        let first = AsciiArt::shared().logo_names()[0].to_string();
+        assert_eq!(app.config().display.ascii_distro, Some(first));
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.config().display.ascii_distro, None);
+
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('+'));
+        press(&mut app, KeyCode::Char('+'));
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.config().display.ascii_colors, ["distro", "1"]);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(app.config().display.ascii_colors, ["15", "1"]);
+    }
+
+    #[test]
+    fn save_writes_only_the_edited_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "# my settings\n[info]\nbold = false # keep\n").unwrap();
+
+        let system_info = SystemInfo::sample().unwrap();
+        let mut config = Config::default();
+        config.info.order = vec!["os".into()];
+        let mut app = App::new(&system_info, config, path.clone());
+        press(&mut app, KeyCode::Char('s'));
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with("# my settings\n[info]\nbold = false # keep\n"));
+        assert!(saved.contains("order = [\"os\"]"));
+        assert!(saved.contains("ascii_colors = [\"distro\"]"));
+        assert!(!saved.contains("ascii_distro"));
+        assert!(draw(&app).contains("Saved to"));
+    }
+
+    #[test]
+    fn sgr_codes_become_styles() {
+        let text =
+            ansi_to_text("\x1b[1;36mOS\x1b[0m: \x1b[38;5;202mArch\x1b[0m\n\x1b[41m   \x1b[0m");
+
+        let first = &text.lines[0].spans;
+        assert_eq!(first[0].content, "OS");
+        assert_eq!(
+            first[0].style,
+            Style::new()
+                .fg(Color::Indexed(6))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(first[1].style, Style::new());
+        assert_eq!(first[2].style, Style::new().fg(Color::Indexed(202)));
+        assert_eq!(
+            text.lines[1].spans[0].style,
+            Style::new().bg(Color::Indexed(1))
+        );
+    }
+}
@@ -2,27 +2,321 @@
 //!
 //! This module contains various utility functions used throughout the application.
 
+use crate::config::UptimeShorthand;
 use anyhow::Result;
-use std::process::Command;
+use chrono::{DateTime, TimeZone};
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long subprocesses get before they're killed
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Build a command whose output is safe to parse
+///
+/// `LC_ALL=C` keeps tools from translating the keywords and number formats
+/// our parsers look for (a German `xrandr` or a localized `pacman`).
+pub fn command(program: &str) -> Command {
+    let mut command = Command::new(program);
+    command.env("LC_ALL", "C");
+    command
+}
+
+/// A limit on how many external commands run at once
+///
+/// Package, GPU and version probes run on their own threads, so without a
+/// limit a cold run can start a couple dozen processes at the same time.
+/// Every command goes through the shared [`COMMAND_SLOTS`].
+struct CommandSlots {
+    limit: AtomicUsize,
+    running: Mutex<usize>,
+    freed: Condvar,
+    waited_micros: AtomicU64,
+}
+
+static COMMAND_SLOTS: CommandSlots = CommandSlots::new(4);
+
+impl CommandSlots {
+    const fn new(limit: usize) -> Self {
+        Self {
+            limit: AtomicUsize::new(limit),
+            running: Mutex::new(0),
+            freed: Condvar::new(),
+            waited_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait until fewer than the limit of commands are running
+    fn acquire(&self) -> CommandSlot<'_> {
+        let started = Instant::now();
+        let mut running = self
+            .running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            let limit = self.limit.load(Ordering::Relaxed);
+            if limit == 0 || *running < limit {
+                break;
+            }
+            running = self
+                .freed
+                .wait(running)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        *running += 1;
+
+        self.waited_micros
+            .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+        CommandSlot(self)
+    }
+
+    /// Total time commands spent waiting for a free slot so far
+    fn waited(&self) -> Duration {
+        Duration::from_micros(self.waited_micros.load(Ordering::Relaxed))
+    }
+}
+
+/// A running command's place in its [`CommandSlots`], given back on drop
+struct CommandSlot<'a>(&'a CommandSlots);
+
+impl Drop for CommandSlot<'_> {
+    fn drop(&mut self) {
+        let mut running = self
+            .0
+            .running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *running = running.saturating_sub(1);
+        self.0.freed.notify_one();
+    }
+}
+
+/// Set how many external commands may run at once; 0 removes the limit
+pub fn set_max_concurrent_commands(limit: usize) {
+    COMMAND_SLOTS.limit.store(limit, Ordering::Relaxed);
+    COMMAND_SLOTS.freed.notify_all();
+}
+
+/// Total time commands spent waiting for a free slot so far
+pub fn command_queue_wait() -> Duration {
+    COMMAND_SLOTS.waited()
+}
 
 /// Execute a shell command and return its output
+///
+/// The command is killed after [`COMMAND_TIMEOUT`].
 pub fn execute_command(command: &str, args: &[&str]) -> Result<String> {
-    let output = Command::new(command).args(args).output()?;
+    execute_command_with_timeout(command, args, COMMAND_TIMEOUT)
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+/// Execute a command, killing it if it runs longer than `timeout`
+///
+/// A command that times out or fails gives an empty string.
+pub fn execute_command_with_timeout(
+    command: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String> {
+    trimmed_stdout(output_with_timeout(
+        self::command(command).args(args),
+        timeout,
+    ))
+}
+
+/// Trimmed stdout of a finished command; failures and timeouts give ""
+fn trimmed_stdout(output: std::io::Result<Output>) -> Result<String> {
+    match output {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        Ok(_) => Ok(String::new()),
+        Err(err) if err.kind() == std::io::ErrorKind::TimedOut => Ok(String::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Run a command like `Command::output`, killing it after `timeout`
+///
+/// Stdin and stderr are discarded. A killed command gives a `TimedOut`
+/// error. The timeout starts once the command gets a slot under
+/// `behavior.max_concurrent_commands`, so queueing doesn't count against it.
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> std::io::Result<Output> {
+    output_in_slot(&COMMAND_SLOTS, command, timeout)
+}
+
+/// [`output_with_timeout`] with the command queued in `slots`
+fn output_in_slot(
+    slots: &CommandSlots,
+    command: &mut Command,
+    timeout: Duration,
+) -> std::io::Result<Output> {
+    let _slot = slots.acquire();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Drain stdout on another thread so a chatty child can't fill the pipe
+    // and block
+    let mut stdout = child.stdout.take();
+    let reader = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_end(&mut buffer);
+        }
+        buffer
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut poll = Duration::from_millis(1);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The reader is left detached, since a grandchild may still hold
+            // the pipe open
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("command timed out after {:?}", timeout),
+            ));
+        }
+        std::thread::sleep(poll);
+        poll = (poll * 2).min(Duration::from_millis(20));
+    };
+
+    Ok(Output {
+        status,
+        stdout: reader.join().unwrap_or_default(),
+        stderr: Vec::new(),
+    })
+}
+
+/// Packaging sandbox the tool itself may be running in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sandbox {
+    Flatpak,
+    Snap,
+}
+
+/// Detect whether neofetch-rs is running inside a Flatpak or Snap sandbox
+pub fn detect_sandbox() -> Option<Sandbox> {
+    sandbox_from(std::path::Path::new("/"), |name| std::env::var(name).ok())
+}
+
+/// [`detect_sandbox`] with the filesystem under `root`
+fn sandbox_from(root: &std::path::Path, env: impl Fn(&str) -> Option<String>) -> Option<Sandbox> {
+    if root.join(".flatpak-info").exists() {
+        Some(Sandbox::Flatpak)
+    } else if env("SNAP").is_some() {
+        Some(Sandbox::Snap)
+    } else {
+        None
+    }
+}
+
+/// Build a command that runs on the host system
+///
+/// Inside Flatpak the host's package databases and tools are hidden, so the
+/// command is routed through `flatpak-spawn --host`. Everywhere else this is
+/// a plain [`command`]. Either way the host command runs with `LC_ALL=C`.
+pub fn host_command(command: &str) -> Command {
+    if detect_sandbox() == Some(Sandbox::Flatpak) {
+        // flatpak-spawn doesn't pass our environment on to the host
+        let mut host = Command::new("flatpak-spawn");
+        host.args(["--host", "--env=LC_ALL=C", command]);
+        host
     } else {
-        Ok(String::new())
+        self::command(command)
     }
 }
 
+/// Execute a command on the host system, see [`host_command`]
+///
+/// The command is killed after [`COMMAND_TIMEOUT`].
+pub fn execute_host_command(command: &str, args: &[&str]) -> Result<String> {
+    trimmed_stdout(output_with_timeout(
+        host_command(command).args(args),
+        COMMAND_TIMEOUT,
+    ))
+}
+
 /// Check if a command exists in the system PATH
+///
+/// Walks `PATH` directly rather than spawning `which`, which isn't
+/// available on Windows. On Windows the `PATHEXT` extensions (`.exe`,
+/// `.cmd`, `.bat`, ...) are tried as well.
 pub fn command_exists(command: &str) -> bool {
-    Command::new("which")
-        .arg(command)
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+    if command.is_empty() {
+        return false;
+    }
+
+    let path = std::path::Path::new(command);
+    if path.components().count() > 1 {
+        return is_executable(path);
+    }
+
+    let Some(search_path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    command_in_path(command, &search_path)
+}
+
+/// Look for `command` in the directories of a `PATH`-style list
+fn command_in_path(command: &str, search_path: &std::ffi::OsStr) -> bool {
+    #[cfg(windows)]
+    let extensions: Vec<String> = std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| ext.to_string())
+        .collect();
+
+    std::env::split_paths(search_path).any(|dir| {
+        let candidate = dir.join(command);
+        if is_executable(&candidate) {
+            return true;
+        }
+
+        #[cfg(windows)]
+        {
+            if extensions
+                .iter()
+                .any(|ext| is_executable(&dir.join(format!("{}{}", command, ext))))
+            {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+/// Check whether a path is an executable file
+fn is_executable(path: &std::path::Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        true
+    }
 }
 
 /// Trim quotes from a string
@@ -31,96 +325,430 @@ pub fn trim_quotes(s: &str) -> String {
 }
 
 /// Clean up CPU name by removing common suffixes and prefixes
+///
+/// A rated speed such as `@ 3.20GHz` is dropped too, since the measured
+/// speed is appended separately.
 pub fn clean_cpu_name(name: &str) -> String {
+    let name = name.split(" @ ").next().unwrap_or(name);
     name.replace("(R)", "")
         .replace("(TM)", "")
         .replace("(tm)", "")
         .replace("CPU", "")
         .replace("Processor", "")
-        .replace("  ", " ")
-        .trim()
-        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Convert bytes to human-readable format
+///
+/// KiB and MiB are rounded to whole numbers and GiB to two decimals,
+/// matching original neofetch.
 pub fn bytes_to_human_readable(bytes: u64, unit: &str) -> String {
     match unit.to_lowercase().as_str() {
-        "kib" => format!("{:.1}KiB", bytes as f64 / 1024.0),
-        "mib" => format!("{:.1}MiB", bytes as f64 / 1024.0 / 1024.0),
-        "gib" => format!("{:.1}GiB", bytes as f64 / 1024.0 / 1024.0 / 1024.0),
-        _ => format!("{:.1}MiB", bytes as f64 / 1024.0 / 1024.0),
+        "kib" => format!("{:.0}KiB", bytes as f64 / 1024.0),
+        "mib" => format!("{:.0}MiB", bytes as f64 / 1024.0 / 1024.0),
+        "gib" => format!("{:.2}GiB", bytes as f64 / 1024.0 / 1024.0 / 1024.0),
+        _ => format!("{:.0}MiB", bytes as f64 / 1024.0 / 1024.0),
     }
 }
 
-/// Format uptime in a human-readable way
-pub fn format_uptime(seconds: u64, shorthand: bool) -> String {
+/// Locale conventions for displaying numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub decimal_separator: char,
+    pub group_separator: Option<char>,
+}
+
+/// Languages that write decimals with a comma
+const COMMA_DECIMAL_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "eu", "fi", "fr", "gl", "hr", "hu", "id", "is",
+    "it", "lt", "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr",
+    "uk", "vi",
+];
+
+impl NumberFormat {
+    /// The C locale: `.` decimals and no digit grouping
+    pub const C: NumberFormat = NumberFormat {
+        decimal_separator: '.',
+        group_separator: None,
+    };
+
+    /// Conventions for a locale string such as `de_DE.UTF-8`
+    ///
+    /// Comma-decimal locales group thousands with a thin space. Everything
+    /// else, including unknown or empty locales, falls back to C.
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+
+        if COMMA_DECIMAL_LANGUAGES.contains(&language.as_str()) {
+            NumberFormat {
+                decimal_separator: ',',
+                group_separator: Some('\u{2009}'),
+            }
+        } else {
+            Self::C
+        }
+    }
+
+    /// Rewrite every number in `text` with this format's separators
+    ///
+    /// Expects numbers written the C way, e.g. `1843 (apt)` or `3.52GiB`.
+    pub fn localize(&self, text: &str) -> String {
+        if *self == Self::C {
+            return text.to_string();
+        }
+
+        let mut result = String::with_capacity(text.len() + 8);
+        let chars: Vec<char> = text.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if !chars[i].is_ascii_digit() {
+                result.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            // Integer part
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits = &chars[start..i];
+            for (position, digit) in digits.iter().enumerate() {
+                let remaining = digits.len() - position;
+                if position > 0 && remaining.is_multiple_of(3) {
+                    if let Some(separator) = self.group_separator {
+                        result.push(separator);
+                    }
+                }
+                result.push(*digit);
+            }
+
+            // Fractional part
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+                result.push(self.decimal_separator);
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    result.push(chars[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Format an uptime in seconds
+///
+/// `Off` spells units out (`1 day, 3 hours, 44 minutes`), `On` shortens
+/// minutes (`1 day, 3 hours, 44 mins`) and `Tiny` gives `1d 3h 44m`.
+/// Zero units are dropped, except for minutes when nothing else is shown.
+pub fn format_uptime(seconds: u64, shorthand: &UptimeShorthand) -> String {
     let days = seconds / 86400;
     let hours = (seconds % 86400) / 3600;
     let minutes = (seconds % 3600) / 60;
 
-    if shorthand {
-        if days > 0 {
-            format!("{}d {}h {}m", days, hours, minutes)
-        } else if hours > 0 {
-            format!("{}h {}m", hours, minutes)
-        } else {
-            format!("{}m", minutes)
-        }
+    let (minute, minutes_plural) = match shorthand {
+        UptimeShorthand::Off => (" minute", " minutes"),
+        _ => (" min", " mins"),
+    };
+    let units = [
+        (days, " day", " days", "d"),
+        (hours, " hour", " hours", "h"),
+        (minutes, minute, minutes_plural, "m"),
+    ];
+
+    let parts: Vec<String> = units
+        .iter()
+        .enumerate()
+        .filter(|(i, (value, ..))| *value > 0 || (*i == 2 && days == 0 && hours == 0))
+        .map(|(_, (value, singular, plural, tiny))| match shorthand {
+            UptimeShorthand::Tiny => format!("{}{}", value, tiny),
+            _ if *value == 1 => format!("{}{}", value, singular),
+            _ => format!("{}{}", value, plural),
+        })
+        .collect();
+
+    match shorthand {
+        UptimeShorthand::Tiny => parts.join(" "),
+        _ => parts.join(", "),
+    }
+}
+
+/// `timestamp` as a date and time in the time zone `tz`
+fn local_time<Tz: TimeZone>(timestamp: i64, tz: &Tz) -> Option<DateTime<Tz>> {
+    tz.timestamp_opt(timestamp, 0).single()
+}
+
+/// Format the boot time as `up since Tuesday 08:12`
+///
+/// Shown in the time zone `tz`. After a week the weekday is ambiguous, so
+/// the date is shown instead (`up since 2024-03-05`).
+pub fn format_uptime_since<Tz: TimeZone>(boot: u64, now: u64, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let Some(boot_time) = local_time(boot as i64, tz) else {
+        return "Unknown".to_string();
+    };
+    if now.saturating_sub(boot) < 7 * 86400 {
+        boot_time.format("up since %A %H:%M").to_string()
     } else {
-        if days > 0 {
-            format!("{} days, {} hours, {} mins", days, hours, minutes)
-        } else if hours > 0 {
-            format!("{} hours, {} mins", hours, minutes)
-        } else {
-            format!("{} mins", minutes)
-        }
+        boot_time.format("up since %F").to_string()
     }
 }
 
-/// Get the terminal width
-pub fn get_terminal_width() -> usize {
-    if let Ok(output) = Command::new("tput").arg("cols").output() {
-        if output.status.success() {
-            if let Ok(width_str) = String::from_utf8(output.stdout) {
-                if let Ok(width) = width_str.trim().parse::<usize>() {
-                    return width;
-                }
+/// Format an install time as `2021-03-14 (1190 days ago)`, dated in `tz`
+pub fn format_install_date<Tz: TimeZone>(installed: u64, now: u64, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let Some(date) = local_time(installed as i64, tz) else {
+        return "Unknown".to_string();
+    };
+    let days_ago = now.saturating_sub(installed) / 86400;
+    let ago = match days_ago {
+        0 => "today".to_string(),
+        1 => "1 day ago".to_string(),
+        days => format!("{} days ago", days),
+    };
+    format!("{} ({})", date.format("%F"), ago)
+}
+
+/// Format a Unix timestamp in the time zone `tz` with a strftime `format`
+///
+/// Takes every sequence chrono knows, names are in English. `None` when
+/// `format` has a sequence chrono doesn't know, such as `%Q` or a
+/// trailing `%`.
+pub fn format_datetime<Tz: TimeZone>(timestamp: i64, tz: &Tz, format: &str) -> Option<String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    use chrono::format::{Item, StrftimeItems};
+
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    Some(local_time(timestamp, tz)?.format(format).to_string())
+}
+
+/// The SGR sequence that clears all colors and styles
+const RESET: &str = "\x1b[0m";
+
+/// Append `text` in the SGR style `style`, e.g. `1;36` for bold cyan
+///
+/// Writes the same bytes as `colored` without its intermediate strings,
+/// including restoring the style after resets nested in `text`. Plain
+/// text when colors are off.
+pub fn push_styled(output: &mut String, text: &str, style: &str) {
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        output.push_str(text);
+        return;
+    }
+
+    let push_style = |output: &mut String| {
+        output.push_str("\x1b[");
+        output.push_str(style);
+        output.push('m');
+    };
+
+    push_style(output);
+    let mut rest = text;
+    while let Some(index) = rest.find(RESET) {
+        let (before, after) = rest.split_at(index + RESET.len());
+        output.push_str(before);
+        push_style(output);
+        rest = after;
+    }
+    output.push_str(rest);
+    output.push_str(RESET);
+}
+
+/// Enable ANSI escape support on the terminal
+///
+/// Windows 10 consoles print escape codes literally until virtual terminal
+/// processing is turned on for the output handle. Returns false when that
+/// isn't possible (legacy conhost, redirected output), in which case the
+/// caller should fall back to colorless output. Always true elsewhere.
+pub fn enable_ansi_support() -> bool {
+    #[cfg(windows)]
+    {
+        use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+        use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+        use winapi::um::processenv::GetStdHandle;
+        use winapi::um::winbase::STD_OUTPUT_HANDLE;
+        use winapi::um::wincon::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+
+        // SAFETY: the handle comes straight from GetStdHandle and is checked
+        // before use; the mode pointer refers to a live stack variable.
+        unsafe {
+            let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+            if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+                return false;
+            }
+
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) == 0 {
+                return false;
+            }
+            if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+                return true;
             }
+
+            SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
         }
     }
 
-    // Fallback to environment variable
-    if let Ok(cols) = std::env::var("COLUMNS") {
-        if let Ok(width) = cols.parse::<usize>() {
-            return width;
+    #[cfg(not(windows))]
+    {
+        true
+    }
+}
+
+/// Whether output goes to the legacy Windows console host
+///
+/// Windows Terminal sets `WT_SESSION` and third-party terminals such as
+/// mintty or VS Code set `TERM` or `TERM_PROGRAM`; anything else on Windows
+/// is conhost. Always false elsewhere.
+pub fn is_legacy_conhost() -> bool {
+    cfg!(windows)
+        && ["WT_SESSION", "TERM", "TERM_PROGRAM"]
+            .iter()
+            .all(|var| std::env::var_os(var).is_none())
+}
+
+/// Get the visible size of the Windows console window as (columns, rows)
+#[cfg(windows)]
+fn windows_console_size() -> Option<(usize, usize)> {
+    use winapi::um::processenv::GetStdHandle;
+    use winapi::um::winbase::STD_OUTPUT_HANDLE;
+    use winapi::um::wincon::{GetConsoleScreenBufferInfo, CONSOLE_SCREEN_BUFFER_INFO};
+
+    // SAFETY: the buffer info struct is plain data and fully written by the
+    // call on success; failure is reported through the return value.
+    unsafe {
+        let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut info: CONSOLE_SCREEN_BUFFER_INFO = std::mem::zeroed();
+        if GetConsoleScreenBufferInfo(handle, &mut info) == 0 {
+            return None;
         }
+
+        let window = info.srWindow;
+        let columns = (window.Right - window.Left + 1) as usize;
+        let rows = (window.Bottom - window.Top + 1) as usize;
+        Some((columns, rows))
     }
+}
 
-    // Default fallback
-    80
+/// Smallest and largest terminal dimensions taken at face value
+const TERMINAL_WIDTH_RANGE: (usize, usize) = (20, 1000);
+const TERMINAL_HEIGHT_RANGE: (usize, usize) = (5, 1000);
+
+/// Parse a reported terminal dimension, clamped to `range`
+///
+/// Zero and unparseable values mean the size is unknown (CI logs, `ssh host
+/// command` without a pty) and give `None`.
+fn parse_terminal_dimension(value: &str, range: (usize, usize)) -> Option<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(0) | Err(_) => None,
+        Ok(size) => Some(size.clamp(range.0, range.1)),
+    }
 }
 
-/// Get the terminal height
-pub fn get_terminal_height() -> usize {
-    if let Ok(output) = Command::new("tput").arg("lines").output() {
-        if output.status.success() {
-            if let Ok(height_str) = String::from_utf8(output.stdout) {
-                if let Ok(height) = height_str.trim().parse::<usize>() {
-                    return height;
-                }
+/// Prefer `tput`'s answer, then the environment variable, e.g. `COLUMNS`
+fn pick_terminal_dimension(
+    tput: Option<String>,
+    env: impl FnOnce() -> Option<String>,
+    range: (usize, usize),
+) -> Option<usize> {
+    tput.and_then(|value| parse_terminal_dimension(&value, range))
+        .or_else(|| env().and_then(|value| parse_terminal_dimension(&value, range)))
+}
+
+/// Ask `tput` for a terminal capability such as `cols` or `lines`
+fn tput(capability: &str) -> Option<String> {
+    let output = output_with_timeout(Command::new("tput").arg(capability), COMMAND_TIMEOUT).ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// Detect the terminal width, or `None` when it can't be determined
+pub fn detect_terminal_width() -> Option<usize> {
+    #[cfg(windows)]
+    {
+        if let Some((columns, _)) = windows_console_size() {
+            if columns > 0 {
+                return Some(columns.clamp(TERMINAL_WIDTH_RANGE.0, TERMINAL_WIDTH_RANGE.1));
             }
         }
     }
 
-    // Fallback to environment variable
-    if let Ok(lines) = std::env::var("LINES") {
-        if let Ok(height) = lines.parse::<usize>() {
-            return height;
+    pick_terminal_dimension(
+        tput("cols"),
+        || std::env::var("COLUMNS").ok(),
+        TERMINAL_WIDTH_RANGE,
+    )
+}
+
+/// Detect the terminal height, or `None` when it can't be determined
+pub fn detect_terminal_height() -> Option<usize> {
+    #[cfg(windows)]
+    {
+        if let Some((_, rows)) = windows_console_size() {
+            if rows > 0 {
+                return Some(rows.clamp(TERMINAL_HEIGHT_RANGE.0, TERMINAL_HEIGHT_RANGE.1));
+            }
         }
     }
 
-    // Default fallback
-    24
+    pick_terminal_dimension(
+        tput("lines"),
+        || std::env::var("LINES").ok(),
+        TERMINAL_HEIGHT_RANGE,
+    )
+}
+
+/// Get the terminal width
+pub fn get_terminal_width() -> usize {
+    detect_terminal_width().unwrap_or(80)
+}
+
+/// Get the terminal height
+pub fn get_terminal_height() -> usize {
+    detect_terminal_height().unwrap_or(24)
+}
+
+/// Parse `os-release` style `KEY=value` content into a map
+///
+/// Values have their surrounding quotes removed; comments and malformed
+/// lines are skipped.
+pub fn parse_os_release(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), trim_quotes(value.trim())))
+        .collect()
+}
+
+/// Read `/etc/os-release`, falling back to `/usr/lib/os-release`
+pub fn read_os_release() -> HashMap<String, String> {
+    ["/etc/os-release", "/usr/lib/os-release"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|content| parse_os_release(&content))
+        .unwrap_or_default()
 }
 
 /// Detect the current operating system
@@ -171,6 +799,32 @@ pub fn detect_os() -> String {
     }
 }
 
+/// Get the kernel name as `uname -s` would report it
+pub fn kernel_name() -> &'static str {
+    match std::env::consts::OS {
+        "linux" | "android" => "Linux",
+        "macos" | "ios" => "Darwin",
+        "windows" => "Windows_NT",
+        "freebsd" => "FreeBSD",
+        "openbsd" => "OpenBSD",
+        "netbsd" => "NetBSD",
+        "dragonfly" => "DragonFly",
+        "solaris" | "illumos" => "SunOS",
+        other => other,
+    }
+}
+
+/// Get the machine architecture as `uname -m` would report it
+pub fn machine_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "i686",
+        "arm" => "armv7l",
+        "aarch64" if cfg!(target_os = "macos") => "arm64",
+        "powerpc64" => "ppc64",
+        other => other,
+    }
+}
+
 /// Get the hostname
 pub fn get_hostname() -> String {
     whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string())
@@ -183,12 +837,225 @@ pub fn get_username() -> String {
 
 /// Check if running in a container
 pub fn is_container() -> bool {
-    // Check for common container indicators
-    std::path::Path::new("/.dockerenv").exists()
-        || std::env::var("container").is_ok()
-        || std::fs::read_to_string("/proc/1/cgroup")
-            .map(|content| content.contains("docker") || content.contains("lxc"))
-            .unwrap_or(false)
+    detect_container().is_some()
+}
+
+/// Name the container runtime we're running under, e.g. `Docker`
+///
+/// Runtimes leave marker files (`/.dockerenv`, `/run/.containerenv`) or
+/// set `$container`; cgroup paths catch the rest.
+pub fn detect_container() -> Option<String> {
+    container_runtime(std::path::Path::new("/"), |name| std::env::var(name).ok())
+}
+
+/// [`detect_container`] with the filesystem under `root`
+fn container_runtime(
+    root: &std::path::Path,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    if root.join("run/.containerenv").exists() {
+        return Some("Podman".to_string());
+    }
+    if root.join(".dockerenv").exists() {
+        return Some("Docker".to_string());
+    }
+
+    if let Some(container) = env("container") {
+        return Some(
+            match container.as_str() {
+                "docker" => "Docker",
+                "podman" => "Podman",
+                "lxc" | "lxc-libvirt" => "LXC",
+                "systemd-nspawn" => "systemd-nspawn",
+                "" | "oci" => "OCI",
+                other => other,
+            }
+            .to_string(),
+        );
+    }
+
+    let cgroup = std::fs::read_to_string(root.join("proc/1/cgroup")).unwrap_or_default();
+    if cgroup.contains("docker") {
+        Some("Docker".to_string())
+    } else if cgroup.contains("kubepods") {
+        Some("Kubernetes".to_string())
+    } else if cgroup.contains("lxc") {
+        Some("LXC".to_string())
+    } else {
+        None
+    }
+}
+
+/// Check if running under the Windows Subsystem for Linux
+pub fn is_wsl() -> bool {
+    wsl_version().is_some()
+}
+
+/// WSL generation, 1 or 2, when running under WSL
+///
+/// WSL2 kernels are named like `5.15.153.1-microsoft-standard-WSL2`, while
+/// WSL1 reports the Windows build, e.g. `4.4.0-19041-Microsoft`. Custom
+/// WSL2 kernels may drop the suffix, but `WSL_DISTRO_NAME` is still set.
+pub fn wsl_version() -> Option<u8> {
+    let release = std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .or_else(|_| std::fs::read_to_string("/proc/version"))
+        .unwrap_or_default();
+    wsl_version_from(&release, |name| std::env::var(name).ok())
+}
+
+/// [`wsl_version`] from the kernel release string and environment
+fn wsl_version_from(release: &str, env: impl Fn(&str) -> Option<String>) -> Option<u8> {
+    let release = release.to_lowercase();
+
+    if release.contains("wsl2") || release.contains("microsoft-standard") {
+        Some(2)
+    } else if release.contains("microsoft") || release.contains("wsl") {
+        Some(1)
+    } else if env("WSL_DISTRO_NAME").is_some() {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Name of the Windows host when running under WSL, e.g. `Windows 11`
+///
+/// Asks `cmd.exe` through WSL interop; builds from 22000 on are Windows 11.
+pub fn wsl_windows_name() -> String {
+    // "Microsoft Windows [Version 10.0.22631.4169]"
+    let ver = execute_command_with_timeout("cmd.exe", &["/c", "ver"], COMMAND_TIMEOUT)
+        .unwrap_or_default();
+    windows_name_from_ver(&ver)
+}
+
+/// Windows name from the output of `cmd.exe /c ver`
+fn windows_name_from_ver(ver: &str) -> String {
+    let build = ver
+        .split("Version")
+        .nth(1)
+        .and_then(|version| version.trim().split('.').nth(2))
+        .and_then(|build| build.trim_end_matches(']').parse::<u32>().ok());
+
+    match build {
+        Some(build) if build >= 22000 => "Windows 11".to_string(),
+        Some(_) => "Windows 10".to_string(),
+        None => "Windows".to_string(),
+    }
+}
+
+/// Hypervisor names by `systemd-detect-virt --vm` ID
+const DETECT_VIRT_NAMES: &[(&str, &str)] = &[
+    ("kvm", "KVM/QEMU"),
+    ("qemu", "KVM/QEMU"),
+    ("vmware", "VMware"),
+    ("oracle", "VirtualBox"),
+    ("microsoft", "Hyper-V"),
+    ("xen", "Xen"),
+    ("parallels", "Parallels"),
+    ("bhyve", "bhyve"),
+    ("amazon", "Amazon EC2"),
+];
+
+/// Detect the hypervisor we're running under, e.g. `KVM/QEMU`
+///
+/// `systemd-detect-virt --vm` is asked first, then the DMI vendor and
+/// product strings are checked. Containers don't count.
+pub fn detect_virtualization() -> Option<String> {
+    virtualization_from(std::path::Path::new("/"), || {
+        if !command_exists("systemd-detect-virt") {
+            return None;
+        }
+        // Prints "none" and exits with 1 on bare metal, so the status is
+        // no use
+        let output = output_with_timeout(
+            self::command("systemd-detect-virt").arg("--vm"),
+            COMMAND_TIMEOUT,
+        )
+        .ok()?;
+        let virt = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!virt.is_empty()).then_some(virt)
+    })
+}
+
+/// [`detect_virtualization`] with DMI under `root`, and `detect_virt`
+/// giving the output of `systemd-detect-virt --vm` if it ran
+fn virtualization_from(
+    root: &std::path::Path,
+    detect_virt: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    if let Some(virt) = detect_virt() {
+        // systemd's answer is better than guessing from DMI, "none" included
+        if virt == "none" {
+            return None;
+        }
+        let name = DETECT_VIRT_NAMES.iter().find(|(id, _)| *id == virt);
+        return Some(name.map_or(virt, |(_, name)| name.to_string()));
+    }
+
+    let dmi = |file: &str| {
+        std::fs::read_to_string(root.join("sys/devices/virtual/dmi/id").join(file))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    hypervisor_from_dmi(&dmi("sys_vendor"), &dmi("product_name")).map(str::to_string)
+}
+
+/// The hypervisor named by the DMI vendor and product
+///
+/// Only values hypervisors set themselves count: Oracle and Microsoft sell
+/// physical machines too, and EC2 `*.metal` instances are bare metal.
+fn hypervisor_from_dmi(vendor: &str, product: &str) -> Option<&'static str> {
+    Some(match (vendor, product) {
+        ("innotek GmbH", _) | (_, "VirtualBox") => "VirtualBox",
+        ("QEMU", _) | (_, "KVM") => "KVM/QEMU",
+        // "Standard PC (Q35 + ICH9, 2009)"
+        (_, product) if product.starts_with("Standard PC (") => "KVM/QEMU",
+        ("VMware, Inc.", _) => "VMware",
+        ("Microsoft Corporation", "Virtual Machine") => "Hyper-V",
+        ("Xen", _) => "Xen",
+        (_, "Parallels Virtual Platform" | "Parallels ARM Virtual Machine") => "Parallels",
+        (_, "BHYVE") => "bhyve",
+        ("Amazon EC2", product) if !product.ends_with(".metal") => "Amazon EC2",
+        _ => return None,
+    })
+}
+
+/// Check if the CPU reports running under a hypervisor
+///
+/// Catches VMs that [`detect_virtualization`] can't name.
+pub fn has_hypervisor_flag() -> bool {
+    hypervisor_flag_from(std::path::Path::new("/"))
+}
+
+/// [`has_hypervisor_flag`] with `/proc` under `root`
+fn hypervisor_flag_from(root: &std::path::Path) -> bool {
+    std::fs::read_to_string(root.join("proc/cpuinfo"))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| line.starts_with("flags"))
+        .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor"))
+}
+
+/// Short tag for the OS line: `container`, or the hypervisor, e.g. `kvm`
+///
+/// VMs are only checked with `vms`, since that means spawning
+/// `systemd-detect-virt`.
+pub fn virtualization_tag(vms: bool) -> Option<String> {
+    if is_container() {
+        return Some("container".to_string());
+    }
+    if !vms {
+        return None;
+    }
+
+    match detect_virtualization() {
+        // "KVM/QEMU" -> "kvm"
+        Some(hypervisor) => hypervisor
+            .split('/')
+            .next()
+            .map(|name| name.to_lowercase().replace(' ', "-")),
+        None => has_hypervisor_flag().then(|| "vm".to_string()),
+    }
 }
 
 /// Get the current shell
@@ -207,11 +1074,12 @@ pub fn get_current_shell() -> String {
 /// Parse version string from command output
 pub fn parse_version_from_output(output: &str) -> Option<String> {
     // Common version patterns
+    // `\d` would also match non-ASCII digits, so spell the range out
     let patterns = [
-        r"(\d+\.\d+\.\d+)",
-        r"(\d+\.\d+)",
-        r"version (\d+\.\d+\.\d+)",
-        r"v(\d+\.\d+\.\d+)",
+        r"([0-9]+\.[0-9]+\.[0-9]+)",
+        r"([0-9]+\.[0-9]+)",
+        r"version ([0-9]+\.[0-9]+\.[0-9]+)",
+        r"v([0-9]+\.[0-9]+\.[0-9]+)",
     ];
 
     for pattern in &patterns {
@@ -226,3 +1094,569 @@ pub fn parse_version_from_output(output: &str) -> Option<String> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminal_size_from_tput_and_the_environment() {
+        let some = |value: &str| Some(value.to_string());
+        let width = |tput: Option<String>, columns: Option<String>| {
+            pick_terminal_dimension(tput, || columns, TERMINAL_WIDTH_RANGE)
+        };
+
+        assert_eq!(width(some("120\n"), some("80")), Some(120));
+        assert_eq!(width(None, some("132")), Some(132));
+        // tput without a terminal answers 0 or fails
+        assert_eq!(width(some("0\n"), some("100")), Some(100));
+        assert_eq!(width(None, some("0")), None);
+        assert_eq!(width(None, some("3")), Some(20));
+        assert_eq!(width(None, some("99999")), Some(1000));
+        assert_eq!(width(None, some("wide")), None);
+        assert_eq!(width(None, None), None);
+
+        // LINES unset
+        let height = pick_terminal_dimension(None, || None, TERMINAL_HEIGHT_RANGE);
+        assert_eq!(height, None);
+        let height = pick_terminal_dimension(None, || some("2"), TERMINAL_HEIGHT_RANGE);
+        assert_eq!(height, Some(5));
+    }
+
+    /// Run `count` commands sleeping 200ms on their own threads
+    #[cfg(unix)]
+    fn sleep_in_slots(slots: &CommandSlots, count: usize) -> Duration {
+        let started = Instant::now();
+        std::thread::scope(|scope| {
+            for _ in 0..count {
+                scope.spawn(|| {
+                    let output =
+                        output_in_slot(slots, Command::new("sleep").arg("0.2"), COMMAND_TIMEOUT);
+                    assert!(output.unwrap().status.success());
+                });
+            }
+        });
+        started.elapsed()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_queue_for_a_free_slot() {
+        let step = Duration::from_millis(200);
+
+        // Six commands two at a time take three rounds; the ones queued
+        // wait one or two rounds
+        let limited = CommandSlots::new(2);
+        let elapsed = sleep_in_slots(&limited, 6);
+        assert!(elapsed >= step * 3, "{:?}", elapsed);
+        assert!(
+            elapsed < step * 3 + Duration::from_millis(500),
+            "{:?}",
+            elapsed
+        );
+        let waited = limited.waited();
+        assert!(waited >= step * 5, "{:?}", waited);
+        assert!(
+            waited < step * 6 + Duration::from_millis(500),
+            "{:?}",
+            waited
+        );
+
+        let unlimited = CommandSlots::new(0);
+        let elapsed = sleep_in_slots(&unlimited, 6);
+        assert!(elapsed < step * 2, "{:?}", elapsed);
+        assert!(unlimited.waited() < Duration::from_millis(50));
+        assert_eq!(*unlimited.running.lock().unwrap(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn timeouts_start_once_a_slot_is_free() {
+        // The second command queues behind the first for 200ms, longer than
+        // its own 150ms timeout, and still finishes
+        let slots = CommandSlots::new(1);
+        std::thread::scope(|scope| {
+            let first = scope.spawn(|| {
+                output_in_slot(&slots, Command::new("sleep").arg("0.2"), COMMAND_TIMEOUT)
+            });
+            std::thread::sleep(Duration::from_millis(50));
+            let second = output_in_slot(
+                &slots,
+                &mut Command::new("true"),
+                Duration::from_millis(150),
+            );
+            assert!(second.unwrap().status.success());
+            assert!(first.join().unwrap().unwrap().status.success());
+        });
+        assert!(slots.waited() >= Duration::from_millis(100));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hanging_commands_are_killed() {
+        let started = Instant::now();
+        assert_eq!(execute_command("sleep", &["10"]).unwrap(), "");
+        assert_eq!(execute_host_command("sleep", &["10"]).unwrap(), "");
+        assert!(started.elapsed() < COMMAND_TIMEOUT * 2 + Duration::from_secs(2));
+
+        assert_eq!(execute_command("echo", &[" hello "]).unwrap(), "hello");
+        assert_eq!(execute_command("false", &[]).unwrap(), "");
+        assert!(execute_command("neofetch-no-such-command", &[]).is_err());
+    }
+
+    #[test]
+    fn versions_are_ascii_numbers() {
+        for (output, expected) in [
+            ("zsh 5.9 (x86_64-pc-linux-gnu)", Some("5.9")),
+            (
+                "GNU bash, Version 5.2.26(1)-release (x86_64-pc-linux-gnu)",
+                Some("5.2.26"),
+            ),
+            ("fish, Version 3.7.1", Some("3.7.1")),
+            ("nvim v0.10.0\nBuild type: Release", Some("0.10.0")),
+            ("bash, الإصدار ٥٫٢٫٢٦", None),
+            ("ｂａｓｈ １.２.３", None),
+            ("no version here", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                parse_version_from_output(output).as_deref(),
+                expected,
+                "{:?}",
+                output
+            );
+        }
+
+        let lossy = String::from_utf8_lossy(b"tmux \xff\xfe3.4\xc0");
+        assert_eq!(parse_version_from_output(&lossy).as_deref(), Some("3.4"));
+    }
+
+    #[test]
+    fn known_binaries_are_found() {
+        let cargo = std::path::Path::new(env!("CARGO"));
+        assert!(command_exists(env!("CARGO")));
+
+        let cargo_dir = cargo.parent().unwrap().as_os_str();
+        let name = cargo.file_stem().unwrap().to_str().unwrap();
+        assert!(command_in_path(name, cargo_dir));
+        assert!(!command_in_path("neofetch-no-such-command", cargo_dir));
+        assert!(!command_exists(""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_executable_files_count() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        for (name, mode) in [("tool", 0o755), ("notes", 0o644)] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        std::fs::create_dir(dir.path().join("folder")).unwrap();
+
+        let empty = tempfile::tempdir().unwrap();
+        let search_path = std::env::join_paths([empty.path(), dir.path()]).unwrap();
+        assert!(command_in_path("tool", &search_path));
+        assert!(!command_in_path("notes", &search_path));
+        assert!(!command_in_path("folder", &search_path));
+        assert!(command_exists(dir.path().join("tool").to_str().unwrap()));
+        assert!(!command_exists(dir.path().join("notes").to_str().unwrap()));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn pathext_extensions_are_tried() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("tool.cmd"), "@echo off\r\n").unwrap();
+        assert!(command_in_path("tool", dir.path().as_os_str()));
+        assert!(command_in_path("tool.cmd", dir.path().as_os_str()));
+    }
+
+    #[test]
+    fn uptime_at_the_edges() {
+        const DAY: u64 = 86400;
+        let year_and_change = 400 * DAY + 3 * 3600 + 60;
+        for (seconds, off, on, tiny) in [
+            (0, "0 minutes", "0 mins", "0m"),
+            (59, "0 minutes", "0 mins", "0m"),
+            (60, "1 minute", "1 min", "1m"),
+            (3600, "1 hour", "1 hour", "1h"),
+            (DAY + 60, "1 day, 1 minute", "1 day, 1 min", "1d 1m"),
+            (
+                year_and_change,
+                "400 days, 3 hours, 1 minute",
+                "400 days, 3 hours, 1 min",
+                "400d 3h 1m",
+            ),
+        ] {
+            assert_eq!(format_uptime(seconds, &UptimeShorthand::Off), off);
+            assert_eq!(format_uptime(seconds, &UptimeShorthand::On), on);
+            assert_eq!(format_uptime(seconds, &UptimeShorthand::Tiny), tiny);
+        }
+    }
+
+    #[test]
+    fn uptime_since_boot() {
+        const DAY: u64 = 86400;
+        let offset = |seconds: i32| chrono::FixedOffset::east_opt(seconds).unwrap();
+        // Tuesday 2024-03-05 08:12:00 UTC
+        let boot = 1_709_626_320;
+        for (now, utc_offset, expected) in [
+            (boot + 3 * DAY, 0, "up since Tuesday 08:12"),
+            (boot + 60, 3600, "up since Tuesday 09:12"),
+            (boot + 60, -9 * 3600, "up since Monday 23:12"),
+            (
+                boot + 5 * 3600 + 30 * 60,
+                5 * 3600 + 1800,
+                "up since Tuesday 13:42",
+            ),
+            (boot + 7 * DAY - 1, 0, "up since Tuesday 08:12"),
+            (boot + 7 * DAY, 0, "up since 2024-03-05"),
+            (boot + 8 * DAY, -9 * 3600, "up since 2024-03-04"),
+            (boot + 400 * DAY, 14 * 3600, "up since 2024-03-05"),
+            // A clock behind the boot time counts as a short uptime
+            (boot - 60, 0, "up since Tuesday 08:12"),
+        ] {
+            assert_eq!(
+                format_uptime_since(boot, now, &offset(utc_offset)),
+                expected,
+                "now {} offset {}",
+                now,
+                utc_offset
+            );
+        }
+
+        // Leap day, and the epoch itself
+        let leap_day = 1_709_208_000;
+        assert_eq!(
+            format_uptime_since(leap_day, leap_day + 30 * DAY, &chrono::Utc),
+            "up since 2024-02-29"
+        );
+        assert_eq!(
+            format_uptime_since(0, 10 * DAY, &chrono::Utc),
+            "up since 1970-01-01"
+        );
+        assert_eq!(
+            format_uptime_since(0, 60, &chrono::Utc),
+            "up since Thursday 00:00"
+        );
+    }
+
+    #[test]
+    fn datetime_formats() {
+        let offset = |seconds: i32| chrono::FixedOffset::east_opt(seconds).unwrap();
+        // Tuesday 2024-03-05 08:12:09 UTC
+        let at = 1_709_626_329;
+        for (format, utc_offset, expected) in [
+            ("%a %d %b %Y %H:%M", 0, Some("Tue 05 Mar 2024 08:12")),
+            ("%A %e %B %y", 0, Some("Tuesday  5 March 24")),
+            ("%F %T %z", 0, Some("2024-03-05 08:12:09 +0000")),
+            ("%F %R %z", 19_800, Some("2024-03-05 13:42 +0530")),
+            // The offset moves the date too
+            ("%F %I:%M %p %z", -36_000, Some("2024-03-04 10:12 PM -1000")),
+            ("%j %m %S", 0, Some("065 03 09")),
+            ("%H%%", 0, Some("08%")),
+            ("%u %-d %s", 0, Some("2 5 1709626329")),
+            ("%c", 0, Some("Tue Mar  5 08:12:09 2024")),
+            ("", 0, Some("")),
+            // Sequences chrono doesn't know hide the line
+            ("%Q", 0, None),
+            ("%H:%M %", 0, None),
+        ] {
+            assert_eq!(
+                format_datetime(at, &offset(utc_offset), format).as_deref(),
+                expected,
+                "{:?} {}",
+                format,
+                utc_offset
+            );
+        }
+
+        // Leap day, and the last day of a leap year
+        let utc = &chrono::Utc;
+        assert_eq!(
+            format_datetime(1_709_164_800, utc, "%F %j").as_deref(),
+            Some("2024-02-29 060")
+        );
+        assert_eq!(
+            format_datetime(1_735_603_200, utc, "%F %j").as_deref(),
+            Some("2024-12-31 366")
+        );
+        assert_eq!(
+            format_datetime(0, utc, "%a %F").as_deref(),
+            Some("Thu 1970-01-01")
+        );
+    }
+
+    #[test]
+    fn install_date_with_days_ago() {
+        const DAY: u64 = 86400;
+        let offset = |seconds: i32| chrono::FixedOffset::east_opt(seconds).unwrap();
+        // 2021-03-14 00:00:00 UTC
+        let installed = 1_615_680_000;
+        for (now, utc_offset, expected) in [
+            (installed + 3600, 0, "2021-03-14 (today)"),
+            (installed + DAY, 0, "2021-03-14 (1 day ago)"),
+            (installed + 1190 * DAY, 0, "2021-03-14 (1190 days ago)"),
+            (installed + DAY, -3600, "2021-03-13 (1 day ago)"),
+            (installed - DAY, 0, "2021-03-14 (today)"),
+        ] {
+            assert_eq!(
+                format_install_date(installed, now, &offset(utc_offset)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn number_format_per_locale() {
+        let de = NumberFormat::for_locale("de_DE.UTF-8");
+        let en = NumberFormat::for_locale("en_US.UTF-8");
+        let c = NumberFormat::for_locale("C");
+
+        assert_eq!(de.decimal_separator, ',');
+        assert_eq!(de.group_separator, Some('\u{2009}'));
+        assert_eq!(en, NumberFormat::C);
+        assert_eq!(c, NumberFormat::C);
+        assert_eq!(NumberFormat::for_locale(""), NumberFormat::C);
+        assert_eq!(NumberFormat::for_locale("POSIX"), NumberFormat::C);
+    }
+
+    #[test]
+    fn localize_at_grouping_boundaries() {
+        let de = NumberFormat::for_locale("de_DE.UTF-8");
+        let cases = [
+            ("0", "0"),
+            ("999 (apt)", "999 (apt)"),
+            ("1000 (apt)", "1\u{2009}000 (apt)"),
+            (
+                "1843 (pacman), 12 (flatpak)",
+                "1\u{2009}843 (pacman), 12 (flatpak)",
+            ),
+            ("999999", "999\u{2009}999"),
+            ("1000000", "1\u{2009}000\u{2009}000"),
+            ("0.50GiB", "0,50GiB"),
+            ("3.52GiB / 15.51GiB (23%)", "3,52GiB / 15,51GiB (23%)"),
+            ("1234.5", "1\u{2009}234,5"),
+            // A trailing dot is punctuation, not a decimal point
+            ("12.", "12."),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(de.localize(text), expected, "{:?}", text);
+        }
+
+        for (text, _) in cases {
+            assert_eq!(NumberFormat::for_locale("en_US.UTF-8").localize(text), text);
+            assert_eq!(NumberFormat::C.localize(text), text);
+        }
+    }
+
+    #[test]
+    fn sandboxes_from_markers_and_the_environment() {
+        let root = tempfile::tempdir().unwrap();
+        let snap = |name: &str| (name == "SNAP").then(|| "/snap/neofetch/12".to_string());
+
+        assert_eq!(sandbox_from(root.path(), |_| None), None);
+        assert_eq!(sandbox_from(root.path(), snap), Some(Sandbox::Snap));
+
+        std::fs::write(
+            root.path().join(".flatpak-info"),
+            "[Application]\nname=io.github.neofetch\n",
+        )
+        .unwrap();
+        assert_eq!(sandbox_from(root.path(), |_| None), Some(Sandbox::Flatpak));
+        assert_eq!(sandbox_from(root.path(), snap), Some(Sandbox::Flatpak));
+    }
+
+    #[test]
+    fn containers_from_marker_files() {
+        let root = tempfile::tempdir().unwrap();
+        let no_env = |_: &str| None;
+        assert_eq!(container_runtime(root.path(), no_env), None);
+
+        std::fs::write(root.path().join(".dockerenv"), "").unwrap();
+        assert_eq!(
+            container_runtime(root.path(), no_env).as_deref(),
+            Some("Docker")
+        );
+
+        // Podman also creates /.dockerenv for compatibility
+        std::fs::create_dir(root.path().join("run")).unwrap();
+        std::fs::write(root.path().join("run/.containerenv"), "").unwrap();
+        assert_eq!(
+            container_runtime(root.path(), no_env).as_deref(),
+            Some("Podman")
+        );
+    }
+
+    #[test]
+    fn containers_from_the_environment() {
+        let root = tempfile::tempdir().unwrap();
+        for (container, expected) in [
+            ("docker", "Docker"),
+            ("podman", "Podman"),
+            ("lxc", "LXC"),
+            ("lxc-libvirt", "LXC"),
+            ("systemd-nspawn", "systemd-nspawn"),
+            ("oci", "OCI"),
+            ("", "OCI"),
+            ("wsl", "wsl"),
+        ] {
+            let env = |name: &str| (name == "container").then(|| container.to_string());
+            assert_eq!(
+                container_runtime(root.path(), env).as_deref(),
+                Some(expected),
+                "{:?}",
+                container
+            );
+        }
+    }
+
+    #[test]
+    fn containers_from_cgroups() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("proc/1")).unwrap();
+        let runtime = |cgroup: &str| {
+            std::fs::write(root.path().join("proc/1/cgroup"), cgroup).unwrap();
+            container_runtime(root.path(), |_| None)
+        };
+
+        assert_eq!(
+            runtime("12:memory:/docker/3f2a9c\n0::/docker/3f2a9c\n").as_deref(),
+            Some("Docker")
+        );
+        assert_eq!(
+            runtime("0::/kubepods/besteffort/pod1a2b\n").as_deref(),
+            Some("Kubernetes")
+        );
+        assert_eq!(runtime("0::/lxc.payload.web\n").as_deref(), Some("LXC"));
+        // cgroup v2 namespaces hide the path, and hosts show the init scope
+        assert_eq!(runtime("0::/\n"), None);
+        assert_eq!(runtime("0::/init.scope\n"), None);
+    }
+
+    #[test]
+    fn hypervisors_from_dmi() {
+        let root = tempfile::tempdir().unwrap();
+        let dmi = root.path().join("sys/devices/virtual/dmi/id");
+        std::fs::create_dir_all(&dmi).unwrap();
+
+        for (vendor, product, expected) in [
+            ("Oracle Corporation", "ORACLE SERVER X8-2", None),
+            ("Amazon EC2", "m5.metal", None),
+            ("Amazon EC2", "t3.micro", Some("Amazon EC2")),
+            ("innotek GmbH", "VirtualBox", Some("VirtualBox")),
+            ("Oracle Corporation", "VirtualBox", Some("VirtualBox")),
+            ("QEMU", "Standard PC (Q35 + ICH9, 2009)", Some("KVM/QEMU")),
+            ("Red Hat", "KVM", Some("KVM/QEMU")),
+            ("VMware, Inc.", "VMware Virtual Platform", Some("VMware")),
+            ("Microsoft Corporation", "Virtual Machine", Some("Hyper-V")),
+            ("Microsoft Corporation", "Surface Laptop 5", None),
+            ("Xen", "HVM domU", Some("Xen")),
+            ("Dell Inc.", "XPS 15 9500", None),
+            ("", "", None),
+        ] {
+            std::fs::write(dmi.join("sys_vendor"), format!("{}\n", vendor)).unwrap();
+            std::fs::write(dmi.join("product_name"), format!("{}\n", product)).unwrap();
+            assert_eq!(
+                virtualization_from(root.path(), || None).as_deref(),
+                expected,
+                "{} / {}",
+                vendor,
+                product
+            );
+        }
+    }
+
+    #[test]
+    fn systemd_detect_virt_wins_over_dmi() {
+        let root = tempfile::tempdir().unwrap();
+        let dmi = root.path().join("sys/devices/virtual/dmi/id");
+        std::fs::create_dir_all(&dmi).unwrap();
+        std::fs::write(dmi.join("sys_vendor"), "innotek GmbH\n").unwrap();
+        std::fs::write(dmi.join("product_name"), "VirtualBox\n").unwrap();
+
+        let virt =
+            |output: Option<&str>| virtualization_from(root.path(), || output.map(str::to_string));
+        assert_eq!(virt(Some("none")), None);
+        assert_eq!(virt(Some("kvm")).as_deref(), Some("KVM/QEMU"));
+        assert_eq!(virt(Some("oracle")).as_deref(), Some("VirtualBox"));
+        assert_eq!(virt(Some("microsoft")).as_deref(), Some("Hyper-V"));
+        // IDs without a nicer name are shown as they are
+        assert_eq!(virt(Some("zvm")).as_deref(), Some("zvm"));
+        // Without systemd the DMI strings decide
+        assert_eq!(virt(None).as_deref(), Some("VirtualBox"));
+    }
+
+    #[test]
+    fn hypervisor_flag_from_cpuinfo() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(!hypervisor_flag_from(root.path()));
+
+        std::fs::create_dir_all(root.path().join("proc")).unwrap();
+        let cpuinfo = |flags: &str| {
+            std::fs::write(
+                root.path().join("proc/cpuinfo"),
+                format!(
+                    "processor\t: 0\nmodel name\t: Intel Xeon\nflags\t\t: {}\n",
+                    flags
+                ),
+            )
+            .unwrap();
+            hypervisor_flag_from(root.path())
+        };
+        assert!(cpuinfo("fpu vme de pse tsc msr hypervisor lahf_lm"));
+        assert!(!cpuinfo("fpu vme de pse tsc msr vmx lahf_lm"));
+        assert!(!cpuinfo("fpu hypervisor_x"));
+    }
+
+    #[test]
+    fn wsl_from_the_kernel_release() {
+        let no_env = |_: &str| None;
+        for (release, expected) in [
+            ("5.15.153.1-microsoft-standard-WSL2\n", Some(2)),
+            ("6.6.36.3-microsoft-standard-WSL2+", Some(2)),
+            ("4.19.128-microsoft-standard", Some(2)),
+            ("4.4.0-19041-Microsoft", Some(1)),
+            ("4.4.0-22621-Microsoft\n", Some(1)),
+            ("6.8.1-arch1-1", None),
+            ("6.1.0-18-amd64", None),
+            ("", None),
+        ] {
+            assert_eq!(wsl_version_from(release, no_env), expected, "{:?}", release);
+        }
+
+        // /proc/version, the fallback, has the release in a sentence
+        let version = "Linux version 5.15.153.1-microsoft-standard-WSL2 (root@941d701f84f1) \
+                       (gcc (GCC) 11.2.0) #1 SMP Fri Mar 29 23:14:13 UTC 2024";
+        assert_eq!(wsl_version_from(version, no_env), Some(2));
+    }
+
+    #[test]
+    fn wsl_with_a_custom_kernel() {
+        let distro = |name: &str| (name == "WSL_DISTRO_NAME").then(|| "Ubuntu".to_string());
+        assert_eq!(wsl_version_from("6.10.0-custom", distro), Some(2));
+        // The release wins over the environment
+        assert_eq!(wsl_version_from("4.4.0-19041-Microsoft", distro), Some(1));
+        assert_eq!(wsl_version_from("6.10.0-custom", |_| None), None);
+    }
+
+    #[test]
+    fn windows_names_from_the_build() {
+        for (ver, expected) in [
+            ("Microsoft Windows [Version 10.0.22631.4169]", "Windows 11"),
+            ("Microsoft Windows [Version 10.0.22000.194]", "Windows 11"),
+            ("Microsoft Windows [Version 10.0.19045.4894]", "Windows 10"),
+            (
+                "\r\nMicrosoft Windows [Version 10.0.19041]\r\n",
+                "Windows 10",
+            ),
+            ("'cmd.exe' is not recognized", "Windows"),
+            ("", "Windows"),
+        ] {
+            assert_eq!(windows_name_from_ver(ver), expected, "{:?}", ver);
+        }
+    }
+}
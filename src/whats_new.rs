@@ -0,0 +1,147 @@
+//! One-time "what's new" notice after an upgrade
+//!
+//! The version of the last run is kept in `cache_dir/last_version`. When the
+//! running binary is newer, the highlights of every release in between are
+//! summed up in a single line on stderr.
+
+use std::path::Path;
+
+/// File inside `cache_dir` holding the version of the last run
+pub const LAST_VERSION_FILE: &str = "last_version";
+
+/// Highlights per release, oldest first
+const HIGHLIGHTS: &[(&str, &str)] = &[(
+    "7.2.0",
+    "presets, --anonymize, --portable, locale and users fields, config diff-defaults",
+)];
+
+/// Record the running version and describe what changed since the last run
+///
+/// Returns a notice only once per upgrade: the new version is written
+/// straight away, so the next run stays quiet. A first run, a downgrade or
+/// an unreadable cache gives no notice.
+pub fn check(cache_dir: &Path) -> Option<String> {
+    check_version(cache_dir, env!("CARGO_PKG_VERSION"))
+}
+
+/// [`check`] for a given running version
+fn check_version(cache_dir: &Path, current: &str) -> Option<String> {
+    let path = cache_dir.join(LAST_VERSION_FILE);
+    let previous = std::fs::read_to_string(&path).ok();
+    let previous = previous.as_deref().map(str::trim);
+
+    if previous != Some(current) {
+        // Failing to record the version only means the notice may repeat
+        let _ = std::fs::create_dir_all(cache_dir);
+        let _ = std::fs::write(&path, current);
+    }
+
+    let previous = previous?;
+    if parse_version(current) <= parse_version(previous) {
+        return None;
+    }
+
+    let highlights: Vec<&str> = HIGHLIGHTS
+        .iter()
+        .filter(|(version, _)| {
+            parse_version(version) > parse_version(previous)
+                && parse_version(version) <= parse_version(current)
+        })
+        .map(|(_, highlights)| *highlights)
+        .collect();
+
+    let mut notice = format!("neofetch-rs updated {}→{}", previous, current);
+    if !highlights.is_empty() {
+        notice.push_str(&format!(
+            ": new {} — see --help-full",
+            highlights.join(", ")
+        ));
+    }
+    Some(notice)
+}
+
+/// Dotted version as numbers for comparison; non-numeric parts count as 0
+///
+/// Pre-release and build suffixes are ignored and trailing zeros dropped,
+/// so `7.2`, `7.2.0` and `7.2.0-beta.1` compare equal.
+fn parse_version(version: &str) -> Vec<u32> {
+    let release = version.trim().split(['-', '+']).next().unwrap_or("");
+    let mut parts: Vec<u32> = release
+        .split('.')
+        .map(|part| {
+            part.chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0)
+        })
+        .collect();
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(parse_version("7.10.0") > parse_version("7.9.1"));
+        assert!(parse_version("8.0.0") > parse_version("7.99"));
+        assert!(parse_version("7.2.1") > parse_version("7.2"));
+        assert_eq!(parse_version("7.2"), parse_version("7.2.0"));
+        assert_eq!(parse_version("7.2.0-beta.1"), parse_version("7.2.0"));
+        assert_eq!(parse_version(" 7.1.0\n"), [7, 1]);
+        assert_eq!(parse_version("garbage"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn notice_is_shown_once_per_upgrade() {
+        let cache = tempfile::tempdir().unwrap();
+        let dir = cache.path().join("neofetch");
+        let last_version = || std::fs::read_to_string(dir.join(LAST_VERSION_FILE)).unwrap();
+
+        // A first run only records the version
+        assert_eq!(check_version(&dir, "7.1.0"), None);
+        assert_eq!(last_version(), "7.1.0");
+        assert_eq!(check_version(&dir, "7.1.0"), None);
+
+        let notice = check_version(&dir, "7.2.0").unwrap();
+        assert!(
+            notice.starts_with("neofetch-rs updated 7.1.0→7.2.0: new presets"),
+            "{}",
+            notice
+        );
+        assert!(notice.ends_with(" — see --help-full"), "{}", notice);
+        assert_eq!(last_version(), "7.2.0");
+        assert_eq!(check_version(&dir, "7.2.0"), None);
+
+        // Releases without highlights still say what changed
+        assert_eq!(
+            check_version(&dir, "7.2.1").as_deref(),
+            Some("neofetch-rs updated 7.2.0→7.2.1")
+        );
+
+        // Skipping releases picks up their highlights
+        std::fs::write(dir.join(LAST_VERSION_FILE), "7.0.3\n").unwrap();
+        assert!(check_version(&dir, "7.3.0")
+            .unwrap()
+            .contains(": new presets"));
+
+        // Downgrades are recorded but not announced
+        assert_eq!(check_version(&dir, "7.1.0"), None);
+        assert_eq!(last_version(), "7.1.0");
+    }
+
+    #[test]
+    fn unwritable_cache_gives_no_notice() {
+        let cache = tempfile::tempdir().unwrap();
+        let file = cache.path().join("not-a-dir");
+        std::fs::write(&file, "").unwrap();
+
+        assert_eq!(check_version(&file, "7.2.0"), None);
+        assert_eq!(check_version(&file, "7.3.0"), None);
+    }
+}
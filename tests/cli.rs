@@ -0,0 +1,220 @@
+//! Command-line behavior of the `neofetch` binary
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn neofetch(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_neofetch"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn conflicting_flags_exit_with_a_usage_error() {
+    for (args, message) in [
+        (
+            &["--json", "--yaml"][..],
+            "'--json' cannot be used with '--yaml'",
+        ),
+        (&["--logo", "--stdout"], "cannot be used with"),
+        (
+            &["--backend", "kitty", "--ascii", "arch"],
+            "'--backend kitty' cannot be used with '--ascii'",
+        ),
+    ] {
+        let output = neofetch(args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert_eq!(output.status.code(), Some(2), "{:?}: {}", args, stderr);
+        assert!(stderr.contains(message), "{:?}: {}", args, stderr);
+        assert!(output.stdout.is_empty(), "{:?}", args);
+    }
+}
+
+#[test]
+fn unknown_values_exit_with_a_usage_error() {
+    for (args, message) in [
+        (&["--cpu-temp", "kelvin"][..], "invalid value 'kelvin'"),
+        (
+            &["--cpu-usage", "yse"],
+            "invalid value 'yse' for '--cpu-usage <BOOL>'",
+        ),
+        (
+            &["--disk", "enabled"],
+            "invalid value 'enabled' for '--disk <BOOL>'",
+        ),
+        (
+            &["--show-virt", "bogus"],
+            "invalid value 'bogus' for '--show-virt <BOOL>'",
+        ),
+        (
+            &["--block-range", "3-20"],
+            "invalid --block-range '3-20': expected START-END with 0 <= START <= END <= 15",
+        ),
+        (&["--block-range", "9", "2"], "invalid --block-range '9 2'"),
+        (
+            &["--colors", "1", "300"],
+            "invalid --colors '1 300': '300' is not a number from 0 to 255",
+        ),
+        (
+            &["--ascii-colors", "distro,red"],
+            "'red' is not a number from 0 to 255, 'distro' or 'fg'",
+        ),
+        (
+            &["--block-width", "0"],
+            "invalid value '0' for '--block-width <NUM>'",
+        ),
+        (
+            &["--block-height", "x"],
+            "invalid value 'x' for '--block-height <NUM>'",
+        ),
+    ] {
+        let output = neofetch(args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert_eq!(output.status.code(), Some(2), "{:?}: {}", args, stderr);
+        assert!(stderr.contains(message), "{:?}: {}", args, stderr);
+        assert!(output.stdout.is_empty(), "{:?}", args);
+    }
+}
+
+/// Run a full gather and render with everything pointed away from `outside`
+fn portable_run(outside: &std::path::Path, configure: impl FnOnce(&mut Command)) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_neofetch"));
+    command
+        .arg("--stdout")
+        .env("NO_COLOR", "1")
+        .env("HOME", outside)
+        .env("XDG_CONFIG_HOME", outside.join("config"))
+        .env("XDG_CACHE_HOME", outside.join("cache"))
+        .env("APPDATA", outside)
+        .env("LOCALAPPDATA", outside)
+        .env_remove("NEOFETCH_HOME");
+    configure(&mut command);
+    command.output().unwrap()
+}
+
+#[test]
+fn portable_mode_stays_inside_its_root() {
+    for use_env in [false, true] {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("config")).unwrap();
+        std::fs::write(
+            root.path().join("config/config.toml"),
+            "[info]\nunderline_char = \"=\"\n",
+        )
+        .unwrap();
+
+        let output = portable_run(outside.path(), |command| {
+            if use_env {
+                command.env("NEOFETCH_HOME", root.path());
+            } else {
+                command.arg("--portable").arg(root.path());
+            }
+        });
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(
+            stdout.lines().nth(1).unwrap().starts_with("==="),
+            "{}",
+            stdout
+        );
+        assert!(root.path().join("cache/cache.json").is_file());
+        assert_eq!(
+            std::fs::read_dir(outside.path()).unwrap().count(),
+            0,
+            "wrote outside the portable root (env: {})",
+            use_env
+        );
+    }
+}
+
+#[test]
+fn serve_answers_each_request_and_exits_at_eof() {
+    let home = tempfile::tempdir().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_neofetch"))
+        .args(["--serve", "--no-cache"])
+        .env("NEOFETCH_HOME", home.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Dropping stdin closes it, which should end the session
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"kernel\n{\"field\": \"memory\", \"refresh\": true}\nnonsense\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", stdout);
+    assert!(
+        !lines[0].is_empty() && !lines[0].starts_with("error"),
+        "{}",
+        stdout
+    );
+    let memory: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(memory["field"], "memory");
+    assert!(
+        memory["value"].as_str().unwrap().contains("MiB"),
+        "{}",
+        memory
+    );
+    assert_eq!(lines[2], "error: unknown field 'nonsense'");
+}
+
+#[test]
+fn datetime_is_shown_in_the_local_time_zone() {
+    let outside = tempfile::tempdir().unwrap();
+    let date_line = |configure: &dyn Fn(&mut Command)| {
+        let output = portable_run(outside.path(), |command| configure(command));
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|line| line.starts_with("Date: "))
+            .map(str::to_string)
+    };
+
+    // POSIX TZ rules need no time zone database
+    for (tz, offset) in [
+        ("UTC0", "+0000"),
+        ("IST-5:30", "+0530"),
+        ("<-03>3", "-0300"),
+    ] {
+        let line = date_line(&|command| {
+            command.env("TZ", tz).args(["--datetime-format", "%z"]);
+        });
+        assert_eq!(line, Some(format!("Date: {}", offset)), "TZ={}", tz);
+    }
+
+    for format in ["", "%H:%M %Q"] {
+        let line = date_line(&|command| {
+            command
+                .env("TZ", "UTC0")
+                .args(["--datetime-format", format]);
+        });
+        assert_eq!(line, None, "{:?}", format);
+    }
+}